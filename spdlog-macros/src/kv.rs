@@ -0,0 +1,163 @@
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use quote::quote;
+use syn::{
+    bracketed,
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Expr, Ident, LitStr, Path, Token,
+};
+
+/// The input of the `split_kv` macro.
+///
+/// ```ignore
+/// split_kv!($callback:path, [$($prefix:tt)*], $($input:tt)+)
+/// ```
+///
+/// It forwards to
+///
+/// ```ignore
+/// $callback!($($prefix)*, fmt: [$($format_args)+], kv: [$(($key:literal, $mode:tt, $value:expr)),*])
+/// ```
+///
+/// where `$mode` is `_` for capturing the value by `ToValue`, `%` for
+/// `Display` and `?` for `Debug`.
+pub struct SplitKv {
+    callback: Path,
+    prefix: TokenStream,
+    input: TokenStream,
+}
+
+impl Parse for SplitKv {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let callback = input.parse::<Path>()?;
+        input.parse::<Token![,]>()?;
+        let prefix;
+        bracketed!(prefix in input);
+        let prefix = prefix.parse::<TokenStream>()?;
+        input.parse::<Token![,]>()?;
+        let input = input.parse::<TokenStream>()?;
+
+        Ok(Self {
+            callback,
+            prefix,
+            input,
+        })
+    }
+}
+
+struct KvPair {
+    key: LitStr,
+    mode: TokenStream,
+    value: Expr,
+}
+
+impl Parse for KvPair {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Shorthand with capture mode, e.g. `%addr` or `?state`
+        if input.peek(Token![%]) || input.peek(Token![?]) {
+            let mode = parse_mode(input)?;
+            let ident = input.call(Ident::parse_any)?;
+            return Ok(Self {
+                key: LitStr::new(&ident.unraw().to_string(), ident.span()),
+                mode,
+                value: syn::parse_quote!(#ident),
+            });
+        }
+
+        let (key, shorthand) = if input.peek(LitStr) {
+            (input.parse::<LitStr>()?, None)
+        } else {
+            let first = input.call(Ident::parse_any)?;
+            let mut key = first.unraw().to_string();
+            let mut is_dotted = false;
+            while input.peek(Token![.]) {
+                input.parse::<Token![.]>()?;
+                key.push('.');
+                key.push_str(&input.call(Ident::parse_any)?.unraw().to_string());
+                is_dotted = true;
+            }
+            let shorthand = if is_dotted { None } else { Some(first.clone()) };
+            (LitStr::new(&key, first.span()), shorthand)
+        };
+
+        if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            let mode = if input.peek(Token![%]) || input.peek(Token![?]) {
+                parse_mode(input)?
+            } else {
+                quote!(_)
+            };
+            let value = input.parse::<Expr>()?;
+            Ok(Self { key, mode, value })
+        } else {
+            let ident = shorthand.ok_or_else(|| {
+                input.error("expected `=` and a value after a dotted or literal key")
+            })?;
+            Ok(Self {
+                key,
+                mode: quote!(_),
+                value: syn::parse_quote!(#ident),
+            })
+        }
+    }
+}
+
+fn parse_mode(input: ParseStream) -> syn::Result<TokenStream> {
+    if input.peek(Token![%]) {
+        let percent = input.parse::<Token![%]>()?;
+        Ok(quote!(#percent))
+    } else {
+        let question = input.parse::<Token![?]>()?;
+        Ok(quote!(#question))
+    }
+}
+
+pub fn split_kv_impl(split_kv: SplitKv) -> syn::Result<TokenStream> {
+    let SplitKv {
+        callback,
+        prefix,
+        input,
+    } = split_kv;
+
+    let mut tokens = input.into_iter().collect::<Vec<_>>();
+    if matches!(tokens.last(), Some(TokenTree::Punct(p)) if p.as_char() == ',') {
+        tokens.pop();
+    }
+
+    let kv_group = match tokens.as_slice() {
+        [.., TokenTree::Punct(comma), TokenTree::Ident(kv), TokenTree::Punct(colon), TokenTree::Group(group)]
+            if comma.as_char() == ','
+                && kv == "kv"
+                && colon.as_char() == ':'
+                && colon.spacing() == Spacing::Alone
+                && group.delimiter() == Delimiter::Brace =>
+        {
+            let group = group.stream();
+            tokens.truncate(tokens.len() - 4);
+            Some(group)
+        }
+        _ => None,
+    };
+
+    let pairs = match kv_group {
+        Some(group) => {
+            syn::parse::Parser::parse2(Punctuated::<KvPair, Token![,]>::parse_terminated, group)?
+                .into_iter()
+                .map(|KvPair { key, mode, value }| quote!((#key, #mode, #value)))
+                .collect()
+        }
+        None => vec![],
+    };
+
+    let fmt_args = tokens.into_iter().collect::<TokenStream>();
+    let prefix = if prefix.is_empty() {
+        prefix
+    } else {
+        quote!(#prefix,)
+    };
+
+    Ok(quote! {
+        #callback!(#prefix fmt: [#fmt_args], kv: [#(#pairs),*])
+    })
+}
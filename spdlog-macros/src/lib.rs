@@ -5,6 +5,7 @@
 //!
 //! [`spdlog-rs`]: https://crates.io/crates/spdlog-rs
 
+mod kv;
 mod pattern;
 
 use proc_macro::TokenStream;
@@ -26,6 +27,19 @@ pub fn runtime_pattern(input: TokenStream) -> TokenStream {
     into_or_error(pattern::runtime_pattern_impl(runtime_pattern))
 }
 
+#[doc(hidden)]
+#[proc_macro]
+pub fn split_kv(input: TokenStream) -> TokenStream {
+    // We must make this macro a procedural macro because a declarative macro cannot
+    // split the trailing `kv: { ... }` from the format arguments without
+    // recursing on every token.
+
+    let split_kv = syn::parse_macro_input!(input);
+    kv::split_kv_impl(split_kv)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
 fn into_or_error(result: Result<TokenStream2>) -> TokenStream {
     match result {
         Ok(stream) => stream.into(),
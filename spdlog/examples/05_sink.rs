@@ -1,27 +1,21 @@
 use std::sync::Arc;
 
-use atomic::{Atomic, Ordering};
 use spdlog::{
-    formatter::{Formatter, FormatterContext, FullFormatter},
     prelude::*,
-    sink::Sink,
-    ErrorHandler, Record, StringBuf,
+    sink::{Sink, SinkBase},
+    Record, StringBuf,
 };
-use spin::{Mutex, RwLock};
+use spin::Mutex;
 
 struct CollectVecSink {
-    level_filter: Atomic<LevelFilter>,
-    formatter: RwLock<Box<dyn Formatter>>,
-    error_handler: Atomic<Option<ErrorHandler>>,
+    base: SinkBase,
     collected: Mutex<Vec<String>>,
 }
 
 impl CollectVecSink {
     fn new() -> Self {
         Self {
-            level_filter: Atomic::new(LevelFilter::All),
-            formatter: RwLock::new(Box::new(FullFormatter::new())),
-            error_handler: Atomic::new(None),
+            base: SinkBase::new(),
             collected: Mutex::new(Vec::new()),
         }
     }
@@ -34,10 +28,7 @@ impl CollectVecSink {
 impl Sink for CollectVecSink {
     fn log(&self, record: &Record) -> spdlog::Result<()> {
         let mut string_buf = StringBuf::new();
-        let mut ctx = FormatterContext::new();
-        self.formatter
-            .read()
-            .format(record, &mut string_buf, &mut ctx)?;
+        self.base.format(record, &mut string_buf)?;
         self.collected.lock().push(string_buf.to_string());
         Ok(())
     }
@@ -46,21 +37,9 @@ impl Sink for CollectVecSink {
         Ok(())
     }
 
-    fn level_filter(&self) -> LevelFilter {
-        self.level_filter.load(Ordering::Relaxed)
-    }
-
-    fn set_level_filter(&self, level_filter: LevelFilter) {
-        self.level_filter.store(level_filter, Ordering::Relaxed);
-    }
-
-    fn set_formatter(&self, formatter: Box<dyn Formatter>) {
-        *self.formatter.write() = formatter;
-    }
-
-    fn set_error_handler(&self, handler: Option<ErrorHandler>) {
-        self.error_handler.store(handler, Ordering::Relaxed);
-    }
+    // Implements `level_filter`, `set_level_filter`, `set_formatter` and
+    // `set_error_handler` with the `SinkBase`.
+    spdlog::impl_sink_base!(@Sink: base);
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -0,0 +1,1168 @@
+//! Provides building loggers and sinks from configuration documents.
+//!
+//! With the crate feature `config` enabled, the whole object graph of loggers,
+//! sinks and formatters can be described in a declarative document, so that the
+//! log layout can be changed without rebuilding the program.
+//!
+//! [`Config`] implements [`serde::Deserialize`], TOML and JSON are supported
+//! out of the box via [`Config::from_toml_str`], [`Config::from_json_str`] and
+//! [`Config::from_file`]. Other formats can be used by deserializing [`Config`]
+//! with their serde implementations.
+//!
+//! To reload a configuration file at runtime, see [`ConfigReloader`].
+//!
+//! # Schema
+//!
+//! The document contains three optional top-level keys:
+//!
+//! | Key              | Description                                          |
+//! |------------------|------------------------------------------------------|
+//! | `sinks`          | A table of sinks, keyed by an identifier of the sink |
+//! | `loggers`        | A table of loggers, keyed by the logger name         |
+//! | `default_logger` | A logger to be set as the global default logger      |
+//!
+//! ## Sinks
+//!
+//! | Key                  | Description                                                           |
+//! |----------------------|-----------------------------------------------------------------------|
+//! | `type`               | **Required**, one of `std_stream`, `file` and `rotating_file`         |
+//! | `level_filter`       | See [level filters](#level-filters)                                   |
+//! | `formatter`          | `full` (default) or `json`                                            |
+//! | `pattern`            | A [runtime pattern] template, can't be used together with `formatter` |
+//! | `std_stream`         | **Required** for `std_stream`, `stdout` or `stderr`                   |
+//! | `style_mode`         | For `std_stream`, one of `always`, `auto` (default) and `never`       |
+//! | `path`               | **Required** for `file` and `rotating_file` (or `file_name_template`) |
+//! | `file_name_template` | For `rotating_file`, a [file name template] instead of `path`         |
+//! | `truncate`           | For `file`, a boolean                                                 |
+//! | `rotation_policy`    | **Required** for `rotating_file`, see below                           |
+//! | `max_files`          | For `rotating_file`, an integer                                       |
+//! | `max_age`            | For `rotating_file`, an integer (in seconds)                          |
+//! | `max_total_size`     | For `rotating_file`, an integer (in bytes)                            |
+//! | `rotate_on_open`     | For `rotating_file`, a boolean                                        |
+//! | `compression`        | For `rotating_file`, `none` (default), `gzip` or `zstd`               |
+//! | `file_size_naming`   | For `rotating_file`, `index` (default), `timestamp` or `sequence`     |
+//!
+//! The `rotation_policy` is a table with a required key `type`, and other keys
+//! depending on the type:
+//!
+//! | `type`                   | Other keys                                                            |
+//! |--------------------------|-----------------------------------------------------------------------|
+//! | `file_size`              | `max_size` (**required**, in bytes)                                   |
+//! | `daily`                  | `hour` and `minute` (default to `0`)                                  |
+//! | `hourly`                 |                                                                       |
+//! | `duration`               | `hours`, `minutes` and `seconds` (default to `0`)                     |
+//! | `weekly`                 | `weekday` (`0` is Sunday), `hour` and `minute` (default to `0`)       |
+//! | `monthly`                | `day` (default to `1`), `hour` and `minute` (default to `0`)          |
+//! | `daily_and_file_size`    | `max_size` (**required**, in bytes), `hour` and `minute`              |
+//! | `duration_and_file_size` | `max_size` (**required**, in bytes), `hours`, `minutes` and `seconds` |
+//!
+//! See [`RotationPolicy`] for the meaning of them.
+//!
+//! ## Loggers
+//!
+//! | Key                  | Description                                    |
+//! |----------------------|------------------------------------------------|
+//! | `sinks`              | An array of identifiers of sinks, default `[]` |
+//! | `level_filter`       | See [level filters](#level-filters)            |
+//! | `flush_level_filter` | See [level filters](#level-filters)            |
+//!
+//! ## Level filters
+//!
+//! A level filter is a string, `off`, `all` or a level name such as `info`,
+//! which means `LevelFilter::MoreSevereEqual(level)`. They are not
+//! case-sensitive.
+//!
+//! # Examples
+//!
+//! ```
+//! use spdlog::config::Config;
+//!
+//! # fn main() -> Result<(), spdlog::Error> {
+//! let config = Config::from_toml_str(
+//!     r#"
+//!     [sinks.console]
+//!     type = "std_stream"
+//!     std_stream = "stdout"
+//!     level_filter = "info"
+//!     pattern = "[{level}] {payload}{eol}"
+//!
+//!     [loggers.net]
+//!     sinks = ["console"]
+//!     level_filter = "debug"
+//!
+//!     [default_logger]
+//!     sinks = ["console"]
+//!     "#,
+//! )?;
+//!
+//! let loggers = config.build()?;
+//! let net = loggers.get("net").unwrap();
+//! spdlog::info!(logger: net, "hello from net");
+//!
+//! // Or register all loggers into the `registry` and set the default logger.
+//! config.apply()?;
+//! assert!(spdlog::registry::get("net").is_some());
+//! # Ok(()) }
+//! ```
+//!
+//! [runtime pattern]: crate::formatter::RuntimePattern
+//! [file name template]: crate::sink::RotatingFileSinkBuilder::file_name_template
+//! [`RotationPolicy`]: crate::sink::RotationPolicy
+
+use std::{
+    collections::{btree_map, hash_map::DefaultHasher, BTreeMap},
+    fs,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::Deserialize;
+
+use crate::{
+    error::{ConfigError, Error, ErrorHandler},
+    formatter::{Formatter, FullFormatter, JsonFormatter, PatternFormatter, RuntimePattern},
+    periodic_worker::PeriodicWorker,
+    registry,
+    sink::{
+        Compression, FileSink, FileSizeNaming, RotatingFileSink, RotationPolicy, Sink, StdStream,
+        StdStreamSink,
+    },
+    sync::*,
+    terminal_style::StyleMode,
+    LevelFilter, Logger, Result,
+};
+
+/// A deserialized configuration document.
+///
+/// See the [module level documentation](self) for the schema and examples.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    sinks: BTreeMap<String, SinkConfig>,
+    #[serde(default)]
+    loggers: BTreeMap<String, LoggerConfig>,
+    default_logger: Option<LoggerConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SinkConfig {
+    #[serde(rename = "type")]
+    kind: String,
+    level_filter: Option<String>,
+    formatter: Option<String>,
+    pattern: Option<String>,
+    std_stream: Option<String>,
+    style_mode: Option<String>,
+    path: Option<PathBuf>,
+    file_name_template: Option<String>,
+    truncate: Option<bool>,
+    rotation_policy: Option<RotationPolicyConfig>,
+    max_files: Option<usize>,
+    max_age: Option<u64>,
+    max_total_size: Option<u64>,
+    rotate_on_open: Option<bool>,
+    compression: Option<String>,
+    file_size_naming: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RotationPolicyConfig {
+    #[serde(rename = "type")]
+    kind: String,
+    max_size: Option<u64>,
+    hour: Option<u32>,
+    minute: Option<u32>,
+    weekday: Option<u32>,
+    day: Option<u32>,
+    hours: Option<u32>,
+    minutes: Option<u32>,
+    seconds: Option<u32>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LoggerConfig {
+    #[serde(default)]
+    sinks: Vec<String>,
+    level_filter: Option<String>,
+    flush_level_filter: Option<String>,
+}
+
+/// Loggers built from a [`Config`].
+#[derive(Clone, Default)]
+pub struct Loggers {
+    default_logger: Option<Arc<Logger>>,
+    loggers: BTreeMap<String, Arc<Logger>>,
+}
+
+impl Config {
+    /// Deserializes a configuration from a TOML document.
+    pub fn from_toml_str(input: &str) -> Result<Self> {
+        toml::from_str(input)
+            .map_err(|err| Error::Config(ConfigError::Deserialize(err.to_string())))
+    }
+
+    /// Deserializes a configuration from a JSON document.
+    pub fn from_json_str(input: &str) -> Result<Self> {
+        serde_json::from_str(input)
+            .map_err(|err| Error::Config(ConfigError::Deserialize(err.to_string())))
+    }
+
+    /// Reads and deserializes a configuration from a file.
+    ///
+    /// The format is inferred from the file extension, `.toml` and `.json` are
+    /// supported.
+    pub fn from_file<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let parse = Self::parser(path)?;
+        parse(&read_file(path)?)
+    }
+
+    // Gets the parser of the format inferred from the file extension.
+    fn parser(path: &Path) -> Result<fn(&str) -> Result<Self>> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        match extension.as_str() {
+            "toml" => Ok(Self::from_toml_str),
+            "json" => Ok(Self::from_json_str),
+            _ => Err(Error::Config(ConfigError::UnsupportedFormat(
+                path.display().to_string(),
+            ))),
+        }
+    }
+
+    /// Builds the loggers described by the configuration.
+    ///
+    /// Sinks are built only if they are referenced by a logger, and a sink
+    /// referenced by multiple loggers is shared between them. Sinks not
+    /// referenced are still validated, but not built, so that they don't
+    /// create any file.
+    pub fn build(&self) -> Result<Loggers> {
+        let mut sinks = BTreeMap::new();
+
+        let default_logger = self
+            .default_logger
+            .as_ref()
+            .map(|logger| self.build_logger(&mut sinks, "default_logger", None, logger))
+            .transpose()?;
+
+        let loggers = self
+            .loggers
+            .iter()
+            .map(|(name, logger)| {
+                let key = format!("loggers.{}", name);
+                let logger = self.build_logger(&mut sinks, &key, Some(name), logger)?;
+                Ok((name.clone(), logger))
+            })
+            .collect::<Result<_>>()?;
+
+        for (sink_id, sink_config) in &self.sinks {
+            if !sinks.contains_key(sink_id) {
+                build_sink(&format!("sinks.{}", sink_id), sink_config, false)?;
+            }
+        }
+
+        Ok(Loggers {
+            default_logger,
+            loggers,
+        })
+    }
+
+    /// Builds the loggers and applies them globally.
+    ///
+    /// The described default logger, if any, is set as the global default
+    /// logger, and the other loggers are registered into the [`registry`],
+    /// replacing the registered ones with the same name. All the loggers are
+    /// swapped into the [`registry`] at once, and then the default logger is
+    /// swapped.
+    ///
+    /// Nothing is applied if an error occurs while building.
+    pub fn apply(&self) -> Result<()> {
+        self.build()?.apply();
+        Ok(())
+    }
+
+    fn build_logger(
+        &self,
+        sinks: &mut BTreeMap<String, Arc<dyn Sink>>,
+        key: &str,
+        name: Option<&str>,
+        config: &LoggerConfig,
+    ) -> Result<Arc<Logger>> {
+        let mut builder = Logger::builder();
+        if let Some(name) = name {
+            builder.name(name);
+        }
+        if let Some(level_filter) = &config.level_filter {
+            builder.level_filter(parse_level_filter(key, "level_filter", level_filter)?);
+        }
+        if let Some(level_filter) = &config.flush_level_filter {
+            builder.flush_level_filter(parse_level_filter(
+                key,
+                "flush_level_filter",
+                level_filter,
+            )?);
+        }
+        for (index, sink_id) in config.sinks.iter().enumerate() {
+            let sink = match sinks.entry(sink_id.clone()) {
+                btree_map::Entry::Occupied(entry) => entry.get().clone(),
+                btree_map::Entry::Vacant(entry) => {
+                    let sink_config = self.sinks.get(sink_id).ok_or_else(|| {
+                        invalid_value(
+                            format!("{}.sinks[{}]", key, index),
+                            format!("sink '{}' is not defined in 'sinks'", sink_id),
+                        )
+                    })?;
+                    let sink = build_sink(&format!("sinks.{}", sink_id), sink_config, true)?;
+                    // Always `Some` when instantiating.
+                    entry.insert(sink.unwrap()).clone()
+                }
+            };
+            builder.sink(sink);
+        }
+
+        let logger = builder.build().map_err(|err| build_error(key, err))?;
+        Ok(Arc::new(logger))
+    }
+}
+
+impl Loggers {
+    /// Gets the described default logger.
+    #[must_use]
+    pub fn default_logger(&self) -> Option<&Arc<Logger>> {
+        self.default_logger.as_ref()
+    }
+
+    /// Gets a logger by its name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Arc<Logger>> {
+        self.loggers.get(name)
+    }
+
+    /// Gets an iterator over the named loggers, sorted by their names.
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<Logger>> {
+        self.loggers.values()
+    }
+
+    /// Applies the loggers globally.
+    ///
+    /// See [`Config::apply`] for details.
+    pub fn apply(self) {
+        self.apply_removing(&[]);
+    }
+
+    // Same as `apply`, but also removes the `removed` loggers from the registry.
+    fn apply_removing(self, removed: &[String]) {
+        // Loggers built from a config always have a name.
+        let mut replaced = registry::replace_many(self.loggers.into_values(), removed);
+        if let Some(default_logger) = self.default_logger {
+            replaced.push(crate::swap_default_logger(default_logger));
+        }
+        // Records logged to the replaced loggers before swapping may still be buffered
+        // in their sinks, flush them so they are not lost.
+        replaced.iter().for_each(|logger| logger.flush());
+    }
+}
+
+/// Reloads a configuration file and applies it globally.
+///
+/// The file is loaded and applied with [`Config::apply`] when the reloader is
+/// constructed, and can be reloaded later on demand or periodically. Each
+/// reload builds the whole configuration first, so a configuration with errors
+/// never takes effect partially. Then the described loggers are swapped into
+/// the [`registry`] at once, loggers described by the previous load but no
+/// longer described are removed from the [`registry`] at the same time, and
+/// then the global default logger is swapped. The global default logger is kept
+/// if it's no longer described. The replaced loggers are flushed after
+/// swapping, records being logged to them at that time are still handled by
+/// their old sinks.
+///
+/// Note that the replacement only affects users who get loggers from the
+/// [`registry`] or [`default_logger`] each time they log. Loggers cloned and
+/// stored elsewhere keep the old configuration.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use spdlog::config::ConfigReloader;
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// let reloader = ConfigReloader::new("logging.toml")?;
+/// // Reload if the file is modified, checking every 5 seconds.
+/// reloader.set_poll_interval(Some(Duration::from_secs(5)));
+///
+/// // Or reload on demand, e.g. from a `SIGHUP` handler.
+/// reloader.reload()?;
+/// # Ok(()) }
+/// ```
+///
+/// [`default_logger`]: crate::default_logger
+pub struct ConfigReloader {
+    inner: Arc<ConfigReloaderInner>,
+    poller: Mutex<Option<PeriodicWorker>>,
+}
+
+struct ConfigReloaderInner {
+    path: PathBuf,
+    state: Mutex<ReloadState>,
+    error_handler: Atomic<Option<ErrorHandler>>,
+}
+
+#[derive(Default)]
+struct ReloadState {
+    // The hash of the content loaded last time, whether it was applied
+    // successfully or not.
+    last_content_hash: Option<u64>,
+    // The names of the loggers registered by the last successful load.
+    logger_names: Vec<String>,
+}
+
+impl ConfigReloader {
+    /// Loads and applies a configuration file, and constructs a
+    /// `ConfigReloader` for it.
+    ///
+    /// See [`Config::from_file`] for the supported formats.
+    pub fn new<P>(path: P) -> Result<Self>
+    where
+        P: Into<PathBuf>,
+    {
+        let inner = ConfigReloaderInner {
+            path: path.into(),
+            state: Mutex::new(ReloadState::default()),
+            error_handler: Atomic::new(None),
+        };
+        inner.reload(true)?;
+
+        Ok(Self {
+            inner: Arc::new(inner),
+            poller: Mutex::new(None),
+        })
+    }
+
+    /// Gets the path of the configuration file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    /// Reloads and applies the configuration file.
+    ///
+    /// If an error occurs, the current configuration is kept.
+    pub fn reload(&self) -> Result<()> {
+        self.inner.reload(true).map(|_| ())
+    }
+
+    /// Reloads and applies the configuration file if its content has changed
+    /// since it was loaded last time.
+    ///
+    /// Returns whether the file was reloaded. The content is compared rather
+    /// than the modification time, so a change is detected even if the
+    /// modification time is unchanged. A file failed to load is not reloaded
+    /// again until its content changes, so the same error is not reported
+    /// repeatedly.
+    pub fn reload_if_modified(&self) -> Result<bool> {
+        self.inner.reload(false)
+    }
+
+    /// Sets a interval for checking the configuration file periodically.
+    ///
+    /// If it's `Some`, the file will be reloaded if it has been modified, as
+    /// [`ConfigReloader::reload_if_modified`] does. Errors occurred while
+    /// reloading are reported to the error handler.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    pub fn set_poll_interval(&self, interval: Option<Duration>) {
+        let mut poller = self.poller.lock_expect();
+
+        // Drop the old worker first, it will wait for the running reload to finish.
+        *poller = None;
+        if let Some(interval) = interval {
+            let inner = self.inner.clone();
+            *poller = Some(PeriodicWorker::new(
+                move || {
+                    if let Err(err) = inner.reload(false) {
+                        inner.handle_error(err);
+                    }
+                    true
+                },
+                interval,
+            ));
+        }
+    }
+
+    /// Sets a error handler.
+    ///
+    /// Errors occurred while reloading periodically will be passed to it. If
+    /// it's `None`, the [default error handler] will be used.
+    ///
+    /// [default error handler]: ../error/index.html#default-error-handler
+    pub fn set_error_handler(&self, handler: Option<ErrorHandler>) {
+        self.inner.error_handler.store(handler, Ordering::Relaxed);
+    }
+}
+
+impl ConfigReloaderInner {
+    fn reload(&self, force: bool) -> Result<bool> {
+        // Hold the lock while reading and applying, so that concurrent reloads
+        // are serialized.
+        let mut state = self.state.lock_expect();
+
+        let parse = Config::parser(&self.path)?;
+        let input = read_file(&self.path)?;
+        let content_hash = {
+            let mut hasher = DefaultHasher::new();
+            input.hash(&mut hasher);
+            hasher.finish()
+        };
+        if !force && state.last_content_hash == Some(content_hash) {
+            return Ok(false);
+        }
+        // Recorded even if it fails, so that the same error is not reported on
+        // every poll.
+        state.last_content_hash = Some(content_hash);
+
+        let loggers = parse(&input)?.build()?;
+        let logger_names = loggers
+            .iter()
+            .filter_map(|logger| logger.name().map(String::from))
+            .collect::<Vec<_>>();
+        let removed = state
+            .logger_names
+            .iter()
+            .filter(|name| !logger_names.contains(name))
+            .cloned()
+            .collect::<Vec<_>>();
+        loggers.apply_removing(&removed);
+        state.logger_names = logger_names;
+        Ok(true)
+    }
+
+    fn handle_error(&self, err: Error) {
+        match self.error_handler.load(Ordering::Relaxed) {
+            Some(handler) => handler(err),
+            None => crate::default_error_handler("ConfigReloader", err),
+        }
+    }
+}
+
+fn read_file(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|err| Error::Config(ConfigError::ReadFile(err)))
+}
+
+// Builds a sink, or only validates the configuration if `instantiate` is false.
+fn build_sink(key: &str, config: &SinkConfig, instantiate: bool) -> Result<Option<Arc<dyn Sink>>> {
+    let check_unsupported = |field: &str, is_some: bool| {
+        if is_some {
+            Err(invalid_value(
+                format!("{}.{}", key, field),
+                format!("not supported by sink type '{}'", config.kind),
+            ))
+        } else {
+            Ok(())
+        }
+    };
+    let required = |field: &str| {
+        invalid_value(
+            format!("{}.{}", key, field),
+            format!("required by sink type '{}'", config.kind),
+        )
+    };
+
+    let level_filter = config
+        .level_filter
+        .as_deref()
+        .map(|level_filter| parse_level_filter(key, "level_filter", level_filter))
+        .transpose()?
+        .unwrap_or(LevelFilter::All);
+    let formatter = build_formatter(key, config)?;
+
+    let sink: Arc<dyn Sink> = match config.kind.as_str() {
+        "std_stream" => {
+            check_unsupported("path", config.path.is_some())?;
+            check_unsupported("file_name_template", config.file_name_template.is_some())?;
+            check_unsupported("truncate", config.truncate.is_some())?;
+            check_unsupported("rotation_policy", config.rotation_policy.is_some())?;
+            check_unsupported("max_files", config.max_files.is_some())?;
+            check_unsupported("max_age", config.max_age.is_some())?;
+            check_unsupported("max_total_size", config.max_total_size.is_some())?;
+            check_unsupported("rotate_on_open", config.rotate_on_open.is_some())?;
+            check_unsupported("compression", config.compression.is_some())?;
+            check_unsupported("file_size_naming", config.file_size_naming.is_some())?;
+
+            let std_stream = match config.std_stream.as_deref() {
+                Some(std_stream) => parse_std_stream(key, std_stream)?,
+                None => return Err(required("std_stream")),
+            };
+            let mut builder = StdStreamSink::builder()
+                .std_stream(std_stream)
+                .level_filter(level_filter)
+                .formatter(formatter);
+            if let Some(style_mode) = &config.style_mode {
+                builder = builder.style_mode(parse_style_mode(key, style_mode)?);
+            }
+            if !instantiate {
+                return Ok(None);
+            }
+            Arc::new(builder.build().map_err(|err| build_error(key, err))?)
+        }
+        "file" => {
+            check_unsupported("std_stream", config.std_stream.is_some())?;
+            check_unsupported("style_mode", config.style_mode.is_some())?;
+            check_unsupported("file_name_template", config.file_name_template.is_some())?;
+            check_unsupported("rotation_policy", config.rotation_policy.is_some())?;
+            check_unsupported("max_files", config.max_files.is_some())?;
+            check_unsupported("max_age", config.max_age.is_some())?;
+            check_unsupported("max_total_size", config.max_total_size.is_some())?;
+            check_unsupported("rotate_on_open", config.rotate_on_open.is_some())?;
+            check_unsupported("compression", config.compression.is_some())?;
+            check_unsupported("file_size_naming", config.file_size_naming.is_some())?;
+
+            let path = config.path.as_ref().ok_or_else(|| required("path"))?;
+            let builder = FileSink::builder()
+                .path(path)
+                .truncate(config.truncate.unwrap_or(false))
+                .level_filter(level_filter)
+                .formatter(formatter);
+            if !instantiate {
+                return Ok(None);
+            }
+            Arc::new(builder.build().map_err(|err| build_error(key, err))?)
+        }
+        "rotating_file" => {
+            check_unsupported("std_stream", config.std_stream.is_some())?;
+            check_unsupported("style_mode", config.style_mode.is_some())?;
+            check_unsupported("truncate", config.truncate.is_some())?;
+
+            let builder = match (&config.path, &config.file_name_template) {
+                (Some(_), Some(_)) => {
+                    return Err(invalid_value(
+                        format!("{}.file_name_template", key),
+                        "cannot be used together with 'path'",
+                    ))
+                }
+                (Some(path), None) => RotatingFileSink::builder().base_path(path),
+                (None, Some(template)) => RotatingFileSink::builder().file_name_template(template),
+                (None, None) => return Err(required("path")),
+            };
+            let rotation_policy = match &config.rotation_policy {
+                Some(policy) => build_rotation_policy(&format!("{}.rotation_policy", key), policy)?,
+                None => return Err(required("rotation_policy")),
+            };
+            let mut builder = builder
+                .rotation_policy(rotation_policy)
+                .level_filter(level_filter)
+                .formatter(formatter);
+            if let Some(max_files) = config.max_files {
+                builder = builder.max_files(max_files);
+            }
+            if let Some(max_age) = config.max_age {
+                builder = builder.max_age(Some(Duration::from_secs(max_age)));
+            }
+            if let Some(max_total_size) = config.max_total_size {
+                builder = builder.max_total_size(Some(max_total_size));
+            }
+            if let Some(rotate_on_open) = config.rotate_on_open {
+                builder = builder.rotate_on_open(rotate_on_open);
+            }
+            if let Some(compression) = &config.compression {
+                builder = builder.compression(parse_compression(key, compression)?);
+            }
+            if let Some(naming) = &config.file_size_naming {
+                builder = builder.file_size_naming(parse_file_size_naming(key, naming)?);
+            }
+            builder.validate().map_err(|err| build_error(key, err))?;
+            if !instantiate {
+                return Ok(None);
+            }
+            Arc::new(builder.build().map_err(|err| build_error(key, err))?)
+        }
+        kind => {
+            return Err(invalid_value(
+                format!("{}.type", key),
+                format!(
+                    "unknown sink type '{}', expected one of 'std_stream', 'file', 'rotating_file'",
+                    kind
+                ),
+            ))
+        }
+    };
+    Ok(Some(sink))
+}
+
+fn build_formatter(key: &str, config: &SinkConfig) -> Result<Box<dyn Formatter>> {
+    match (config.formatter.as_deref(), config.pattern.as_deref()) {
+        (Some(_), Some(_)) => Err(invalid_value(
+            format!("{}.pattern", key),
+            "cannot be used together with 'formatter'",
+        )),
+        (None, Some(template)) => {
+            let pattern = RuntimePattern::new(template)
+                .map_err(|err| build_error(format!("{}.pattern", key), err))?;
+            Ok(Box::new(PatternFormatter::new(pattern)))
+        }
+        (None | Some("full"), None) => Ok(Box::new(FullFormatter::new())),
+        (Some("json"), None) => Ok(Box::new(JsonFormatter::new())),
+        (Some(formatter), None) => Err(invalid_value(
+            format!("{}.formatter", key),
+            format!(
+                "unknown formatter '{}', expected one of 'full', 'json'",
+                formatter
+            ),
+        )),
+    }
+}
+
+fn build_rotation_policy(key: &str, config: &RotationPolicyConfig) -> Result<RotationPolicy> {
+    let fields = [
+        ("max_size", config.max_size.is_some()),
+        ("hour", config.hour.is_some()),
+        ("minute", config.minute.is_some()),
+        ("weekday", config.weekday.is_some()),
+        ("day", config.day.is_some()),
+        ("hours", config.hours.is_some()),
+        ("minutes", config.minutes.is_some()),
+        ("seconds", config.seconds.is_some()),
+    ];
+    let allowed: &[&str] = match config.kind.as_str() {
+        "file_size" => &["max_size"],
+        "daily" => &["hour", "minute"],
+        "hourly" => &[],
+        "duration" => &["hours", "minutes", "seconds"],
+        "weekly" => &["weekday", "hour", "minute"],
+        "monthly" => &["day", "hour", "minute"],
+        "daily_and_file_size" => &["max_size", "hour", "minute"],
+        "duration_and_file_size" => &["max_size", "hours", "minutes", "seconds"],
+        kind => {
+            return Err(invalid_value(
+                format!("{}.type", key),
+                format!(
+                    "unknown rotation policy type '{}', expected one of 'file_size', 'daily', 'hourly', 'duration', 'weekly', 'monthly', 'daily_and_file_size', 'duration_and_file_size'",
+                    kind
+                ),
+            ))
+        }
+    };
+    if let Some((field, _)) = fields
+        .iter()
+        .find(|(field, is_some)| *is_some && !allowed.contains(field))
+    {
+        return Err(invalid_value(
+            format!("{}.{}", key, field),
+            format!("not supported by rotation policy type '{}'", config.kind),
+        ));
+    }
+
+    let max_size = || {
+        config.max_size.ok_or_else(|| {
+            invalid_value(
+                format!("{}.max_size", key),
+                format!("required by rotation policy type '{}'", config.kind),
+            )
+        })
+    };
+
+    Ok(match config.kind.as_str() {
+        "file_size" => RotationPolicy::FileSize(max_size()?),
+        "daily" => RotationPolicy::Daily {
+            hour: config.hour.unwrap_or(0),
+            minute: config.minute.unwrap_or(0),
+        },
+        "hourly" => RotationPolicy::Hourly,
+        "duration" => RotationPolicy::Duration {
+            hours: config.hours.unwrap_or(0),
+            minutes: config.minutes.unwrap_or(0),
+            seconds: config.seconds.unwrap_or(0),
+        },
+        "weekly" => RotationPolicy::Weekly {
+            weekday: config.weekday.unwrap_or(0),
+            hour: config.hour.unwrap_or(0),
+            minute: config.minute.unwrap_or(0),
+        },
+        "monthly" => RotationPolicy::Monthly {
+            day: config.day.unwrap_or(1),
+            hour: config.hour.unwrap_or(0),
+            minute: config.minute.unwrap_or(0),
+        },
+        "daily_and_file_size" => RotationPolicy::DailyAndFileSize {
+            hour: config.hour.unwrap_or(0),
+            minute: config.minute.unwrap_or(0),
+            max_size: max_size()?,
+        },
+        _ => RotationPolicy::DurationAndFileSize {
+            hours: config.hours.unwrap_or(0),
+            minutes: config.minutes.unwrap_or(0),
+            seconds: config.seconds.unwrap_or(0),
+            max_size: max_size()?,
+        },
+    })
+}
+
+fn parse_level_filter(key: &str, field: &str, input: &str) -> Result<LevelFilter> {
+    LevelFilter::from_str_for_env(input).ok_or_else(|| {
+        invalid_value(
+            format!("{}.{}", key, field),
+            format!("unknown level filter '{}'", input),
+        )
+    })
+}
+
+fn parse_std_stream(key: &str, input: &str) -> Result<StdStream> {
+    match input {
+        "stdout" => Ok(StdStream::Stdout),
+        "stderr" => Ok(StdStream::Stderr),
+        _ => Err(invalid_value(
+            format!("{}.std_stream", key),
+            format!(
+                "unknown std stream '{}', expected one of 'stdout', 'stderr'",
+                input
+            ),
+        )),
+    }
+}
+
+fn parse_style_mode(key: &str, input: &str) -> Result<StyleMode> {
+    match input {
+        "always" => Ok(StyleMode::Always),
+        "auto" => Ok(StyleMode::Auto),
+        "never" => Ok(StyleMode::Never),
+        _ => Err(invalid_value(
+            format!("{}.style_mode", key),
+            format!(
+                "unknown style mode '{}', expected one of 'always', 'auto', 'never'",
+                input
+            ),
+        )),
+    }
+}
+
+fn parse_compression(key: &str, input: &str) -> Result<Compression> {
+    match input {
+        "none" => Ok(Compression::None),
+        #[cfg(feature = "gzip")]
+        "gzip" => Ok(Compression::Gzip),
+        #[cfg(not(feature = "gzip"))]
+        "gzip" => Err(invalid_value(
+            format!("{}.compression", key),
+            "'gzip' requires crate feature 'gzip'",
+        )),
+        #[cfg(feature = "zstd")]
+        "zstd" => Ok(Compression::Zstd),
+        #[cfg(not(feature = "zstd"))]
+        "zstd" => Err(invalid_value(
+            format!("{}.compression", key),
+            "'zstd' requires crate feature 'zstd'",
+        )),
+        _ => Err(invalid_value(
+            format!("{}.compression", key),
+            format!(
+                "unknown compression '{}', expected one of 'none', 'gzip', 'zstd'",
+                input
+            ),
+        )),
+    }
+}
+
+fn parse_file_size_naming(key: &str, input: &str) -> Result<FileSizeNaming> {
+    match input {
+        "index" => Ok(FileSizeNaming::Index),
+        "timestamp" => Ok(FileSizeNaming::Timestamp),
+        "sequence" => Ok(FileSizeNaming::Sequence),
+        _ => Err(invalid_value(
+            format!("{}.file_size_naming", key),
+            format!(
+                "unknown file size naming '{}', expected one of 'index', 'timestamp', 'sequence'",
+                input
+            ),
+        )),
+    }
+}
+
+#[must_use]
+fn invalid_value(key: impl Into<String>, message: impl Into<String>) -> Error {
+    Error::Config(ConfigError::InvalidValue {
+        key: key.into(),
+        message: message.into(),
+    })
+}
+
+#[must_use]
+fn build_error(key: impl Into<String>, err: Error) -> Error {
+    Error::Config(ConfigError::Build {
+        key: key.into(),
+        source: Box::new(err),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::*, Level};
+
+    #[must_use]
+    fn error_key(err: Error) -> String {
+        match err {
+            Error::Config(ConfigError::InvalidValue { key, .. })
+            | Error::Config(ConfigError::Build { key, .. }) => key,
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn build_toml() {
+        let path = TEST_LOGS_PATH.join("config_build_toml.log");
+        let config = Config::from_toml_str(&format!(
+            r#"
+            [sinks.console]
+            type = "std_stream"
+            std_stream = "stderr"
+            style_mode = "never"
+            level_filter = "off"
+
+            [sinks.file]
+            type = "file"
+            path = {:?}
+            truncate = true
+            pattern = "[{{level}}] {{payload}}{{eol}}"
+
+            [loggers."config_test.net"]
+            sinks = ["console", "file"]
+            level_filter = "debug"
+            flush_level_filter = "all"
+
+            [loggers."config_test.db"]
+            sinks = ["file"]
+            "#,
+            path
+        ))
+        .unwrap();
+
+        let loggers = config.build().unwrap();
+        assert!(loggers.default_logger().is_none());
+        assert_eq!(loggers.iter().count(), 2);
+
+        let net = loggers.get("config_test.net").unwrap();
+        assert_eq!(net.name(), Some("config_test.net"));
+        assert_eq!(
+            net.level_filter(),
+            LevelFilter::MoreSevereEqual(Level::Debug)
+        );
+        assert_eq!(net.sinks().len(), 2);
+        assert_eq!(net.sinks()[0].level_filter(), LevelFilter::Off);
+
+        // Sinks with the same identifier are shared.
+        let db = loggers.get("config_test.db").unwrap();
+        assert!(Arc::ptr_eq(&net.sinks()[1], &db.sinks()[0]));
+
+        crate::debug!(logger: net, "hello");
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("[debug] hello{}", crate::__EOL)
+        );
+    }
+
+    #[test]
+    fn build_json() {
+        let config = Config::from_json_str(
+            r#"{
+                "sinks": {
+                    "rotating": {
+                        "type": "rotating_file",
+                        "path": "config_build_json.log",
+                        "rotation_policy": { "type": "daily", "hour": 1 },
+                        "max_files": 3,
+                        "max_age": 2592000,
+                        "max_total_size": 1048576,
+                        "formatter": "json"
+                    }
+                },
+                "default_logger": { "level_filter": "warn" }
+            }"#,
+        )
+        .unwrap();
+
+        assert!(config.sinks.contains_key("rotating"));
+        let loggers = config.build().unwrap();
+        let default_logger = loggers.default_logger().unwrap();
+        assert_eq!(default_logger.name(), None);
+        assert_eq!(
+            default_logger.level_filter(),
+            LevelFilter::MoreSevereEqual(Level::Warn)
+        );
+        assert!(default_logger.sinks().is_empty());
+    }
+
+    #[test]
+    fn errors() {
+        let build_err =
+            |input: &str| error_key(Config::from_toml_str(input).unwrap().build().err().unwrap());
+
+        assert_eq!(
+            build_err("[loggers.a]\nsinks = ['nope']"),
+            "loggers.a.sinks[0]"
+        );
+        assert_eq!(
+            build_err("[loggers.a]\nlevel_filter = 'verbose'"),
+            "loggers.a.level_filter"
+        );
+        assert_eq!(
+            build_err("[sinks.s]\ntype = 'socket'\n[loggers.a]\nsinks = ['s']"),
+            "sinks.s.type"
+        );
+        assert_eq!(
+            build_err("[sinks.s]\ntype = 'std_stream'\n[loggers.a]\nsinks = ['s']"),
+            "sinks.s.std_stream"
+        );
+        assert_eq!(
+            build_err("[sinks.s]\ntype = 'std_stream'\nstd_stream = 'stdout'\npath = 'a.log'\n[loggers.a]\nsinks = ['s']"),
+            "sinks.s.path"
+        );
+        assert_eq!(
+            build_err("[sinks.s]\ntype = 'std_stream'\nstd_stream = 'stdout'\nformatter = 'json'\npattern = '{payload}'\n[loggers.a]\nsinks = ['s']"),
+            "sinks.s.pattern"
+        );
+        assert_eq!(
+            build_err("[sinks.s]\ntype = 'std_stream'\nstd_stream = 'stdout'\npattern = '{nope}'\n[loggers.a]\nsinks = ['s']"),
+            "sinks.s.pattern"
+        );
+        assert_eq!(
+            build_err("[sinks.s]\ntype = 'rotating_file'\npath = 'a.log'\nrotation_policy = {type = 'daily', max_size = 1}\n[loggers.a]\nsinks = ['s']"),
+            "sinks.s.rotation_policy.max_size"
+        );
+        assert_eq!(
+            build_err("[sinks.s]\ntype = 'rotating_file'\npath = 'a.log'\nrotation_policy = {type = 'daily_and_file_size', hour = 1}\n[loggers.a]\nsinks = ['s']"),
+            "sinks.s.rotation_policy.max_size"
+        );
+        assert_eq!(
+            build_err("[sinks.s]\ntype = 'rotating_file'\npath = 'a.log'\nrotation_policy = {type = 'daily', hour = 24}\n[loggers.a]\nsinks = ['s']"),
+            "sinks.s"
+        );
+        assert_eq!(
+            build_err("[sinks.s]\ntype = 'rotating_file'\npath = 'a.log'\nrotation_policy = {type = 'weekly', day = 1}\n[loggers.a]\nsinks = ['s']"),
+            "sinks.s.rotation_policy.day"
+        );
+        assert_eq!(
+            build_err("[sinks.s]\ntype = 'rotating_file'\npath = 'a.log'\nrotation_policy = {type = 'monthly', day = 32}\n[loggers.a]\nsinks = ['s']"),
+            "sinks.s"
+        );
+        assert_eq!(
+            build_err("[sinks.s]\ntype = 'rotating_file'\npath = 'a.log'\nrotation_policy = {type = 'hourly'}\ncompression = 'zip'\n[loggers.a]\nsinks = ['s']"),
+            "sinks.s.compression"
+        );
+        assert_eq!(
+            build_err("[sinks.s]\ntype = 'rotating_file'\npath = 'a.log'\nrotation_policy = {type = 'file_size', max_size = 1}\nfile_size_naming = 'date'\n[loggers.a]\nsinks = ['s']"),
+            "sinks.s.file_size_naming"
+        );
+        assert_eq!(
+            build_err("[sinks.s]\ntype = 'rotating_file'\npath = 'a.log'\nfile_name_template = 'a-%i.log'\nrotation_policy = {type = 'file_size', max_size = 1}\n[loggers.a]\nsinks = ['s']"),
+            "sinks.s.file_name_template"
+        );
+        assert_eq!(
+            build_err("[sinks.s]\ntype = 'rotating_file'\nfile_name_template = 'a-%Y.log'\nrotation_policy = {type = 'file_size', max_size = 1}\n[loggers.a]\nsinks = ['s']"),
+            "sinks.s"
+        );
+        assert_eq!(build_err("[loggers.'a=b']"), "loggers.a=b");
+
+        // Sinks not referenced are validated too.
+        assert_eq!(build_err("[sinks.s]\ntype = 'file'"), "sinks.s.path");
+        assert_eq!(
+            build_err("[sinks.s]\ntype = 'rotating_file'\npath = 'a.log'\nrotation_policy = {type = 'daily', hour = 24}"),
+            "sinks.s"
+        );
+
+        assert!(matches!(
+            Config::from_toml_str("[sinks.s]\ntype = 'file'\nunknown = 1"),
+            Err(Error::Config(ConfigError::Deserialize(message))) if message.contains("unknown")
+        ));
+        assert!(matches!(
+            Config::from_json_str(r#"{"loggers": {"a": {"sinks": 1}}}"#),
+            Err(Error::Config(ConfigError::Deserialize(_)))
+        ));
+        assert!(matches!(
+            Config::from_file("config.yaml"),
+            Err(Error::Config(ConfigError::UnsupportedFormat(_)))
+        ));
+    }
+
+    #[test]
+    fn unreferenced_sinks_not_built() {
+        let path = TEST_LOGS_PATH.join("config_unreferenced.log");
+        _ = fs::remove_file(&path);
+        let config =
+            Config::from_toml_str(&format!("[sinks.s]\ntype = 'file'\npath = {:?}", path)).unwrap();
+
+        config.build().unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn reload() {
+        let path = TEST_LOGS_PATH.join("config_reload.toml");
+        let write_config = |level: &str| {
+            fs::write(
+                &path,
+                format!("[loggers.config_reload_test]\nlevel_filter = '{}'", level),
+            )
+            .unwrap()
+        };
+        let level = || registry::get("config_reload_test").unwrap().level_filter();
+
+        fs::create_dir_all(&*TEST_LOGS_PATH).unwrap();
+        write_config("warn");
+        let reloader = ConfigReloader::new(&path).unwrap();
+        assert_eq!(reloader.path(), path);
+        assert_eq!(level(), LevelFilter::MoreSevereEqual(Level::Warn));
+        assert!(!reloader.reload_if_modified().unwrap());
+
+        write_config("error");
+        reloader.reload().unwrap();
+        assert_eq!(level(), LevelFilter::MoreSevereEqual(Level::Error));
+
+        // Errors keep the current configuration.
+        write_config("verbose");
+        assert!(reloader.reload().is_err());
+        assert_eq!(level(), LevelFilter::MoreSevereEqual(Level::Error));
+        // The failed content is not reloaded until it changes.
+        assert!(!reloader.reload_if_modified().unwrap());
+        // A changed content is reloaded regardless of the modification time.
+        write_config("info");
+        assert!(reloader.reload_if_modified().unwrap());
+        assert_eq!(level(), LevelFilter::MoreSevereEqual(Level::Info));
+        assert!(!reloader.reload_if_modified().unwrap());
+
+        reloader.set_error_handler(Some(|_| {}));
+        reloader.set_poll_interval(Some(Duration::from_millis(10)));
+        std::thread::sleep(Duration::from_millis(50));
+        write_config("trace");
+        for _ in 0..100 {
+            if level() == LevelFilter::MoreSevereEqual(Level::Trace) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(level(), LevelFilter::MoreSevereEqual(Level::Trace));
+        reloader.set_poll_interval(None);
+
+        // Loggers no longer described are removed.
+        fs::write(&path, "[loggers.config_reload_test_2]").unwrap();
+        reloader.reload().unwrap();
+        assert!(registry::get("config_reload_test").is_none());
+        assert!(registry::get("config_reload_test_2").is_some());
+
+        registry::drop("config_reload_test_2");
+    }
+}
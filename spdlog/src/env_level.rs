@@ -1,4 +1,5 @@
 use std::{
+    cmp::Reverse,
     collections::{hash_map::Entry, HashMap},
     env::VarError,
 };
@@ -15,6 +16,7 @@ static ENV_LEVEL: Lazy<RwLock<Option<EnvLevel>>> = Lazy::new(|| RwLock::new(None
 pub(crate) enum EnvLevelLogger {
     Default,
     Named(String),
+    Pattern(String),
     Unnamed,
     AllExceptDefault,
 }
@@ -41,6 +43,8 @@ impl EnvLevelLogger {
             EnvLevelLogger::Unnamed
         } else if logger_name == "*" {
             EnvLevelLogger::AllExceptDefault
+        } else if logger_name.contains(['*', '?']) {
+            EnvLevelLogger::Pattern(logger_name.into())
         } else {
             EnvLevelLogger::Named(logger_name.into())
         }
@@ -129,11 +133,75 @@ pub(crate) fn logger_level_inner(env_level: &EnvLevel, kind: LoggerKind) -> Opti
         LoggerKind::Default => env_level.get(&EnvLevelLogger::Default)?,
         LoggerKind::Other(logger_name) => env_level
             .get(&EnvLevelLogger::from_logger(logger_name))
+            .or_else(|| {
+                let logger_name = logger_name?;
+                env_level
+                    .iter()
+                    .filter_map(|(logger, level)| match logger {
+                        EnvLevelLogger::Pattern(pattern) if glob_match(pattern, logger_name) => {
+                            Some((pattern, level))
+                        }
+                        _ => None,
+                    })
+                    .max_by(|(lhs, _), (rhs, _)| {
+                        pattern_specificity(lhs)
+                            .cmp(&pattern_specificity(rhs))
+                            // Make the result deterministic regardless of the order of iteration
+                            .then_with(|| rhs.cmp(lhs))
+                    })
+                    .map(|(_, level)| level)
+            })
             .or_else(|| env_level.get(&EnvLevelLogger::AllExceptDefault))?,
     };
     Some(*level)
 }
 
+// The more literal characters a pattern has, the more specific it is. Between
+// patterns with the same number of literal characters, the one with fewer `*`
+// is more specific.
+#[must_use]
+fn pattern_specificity(pattern: &str) -> (usize, Reverse<usize>) {
+    let wildcards = pattern.matches('*').count();
+    let literals = pattern.chars().filter(|&ch| ch != '*' && ch != '?').count();
+    (literals, Reverse(wildcards))
+}
+
+// Matches `name` against `pattern`, where `*` matches any sequence of
+// characters (including an empty one) and `?` matches any single character.
+#[must_use]
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let (pattern, name) = (
+        pattern.chars().collect::<Vec<_>>(),
+        name.chars().collect::<Vec<_>>(),
+    );
+    let (mut p, mut n) = (0, 0);
+    // The position of the last `*` in pattern and the position in name it's
+    // currently matched up to.
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&ch) if ch == '?' || ch == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_n)) => {
+                    backtrack = Some((star_p, star_n + 1));
+                    p = star_p + 1;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&ch| ch == '*')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -280,4 +348,70 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn glob() {
+        assert!(glob_match("net.*", "net.http"));
+        assert!(glob_match("net.*", "net."));
+        assert!(!glob_match("net.*", "net"));
+        assert!(glob_match("db::pool*", "db::pool"));
+        assert!(glob_match("db::pool*", "db::pool::conn"));
+        assert!(glob_match("*.http", "net.http"));
+        assert!(glob_match("n?t.*", "nut.tcp"));
+        assert!(!glob_match("n?t.*", "nt.tcp"));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(!glob_match("a*b*c", "aXbYbZ"));
+        assert!(glob_match("**", ""));
+    }
+
+    #[test]
+    fn patterns() {
+        let env_level = from_str_inner(
+            "*=error,net.*=info,n?t.*=warn,net.http*=debug,net.http.client=trace,db::pool*=trace",
+        )
+        .unwrap();
+        assert_eq!(
+            env_level.get(&EnvLevelLogger::Pattern("net.*".into())),
+            Some(&LevelFilter::MoreSevereEqual(Level::Info))
+        );
+
+        let level = |name| logger_level_inner(&env_level, LoggerKind::Other(name));
+
+        // Exact name wins
+        assert_eq!(
+            level(Some("net.http.client")),
+            Some(LevelFilter::MoreSevereEqual(Level::Trace))
+        );
+        // The pattern with the most literal characters wins
+        assert_eq!(
+            level(Some("net.http.server")),
+            Some(LevelFilter::MoreSevereEqual(Level::Debug))
+        );
+        assert_eq!(
+            level(Some("net.tcp")),
+            Some(LevelFilter::MoreSevereEqual(Level::Info))
+        );
+        assert_eq!(
+            level(Some("nut.tcp")),
+            Some(LevelFilter::MoreSevereEqual(Level::Warn))
+        );
+        assert_eq!(
+            level(Some("db::pool::conn")),
+            Some(LevelFilter::MoreSevereEqual(Level::Trace))
+        );
+        // Falls back to `*`
+        assert_eq!(
+            level(Some("gui")),
+            Some(LevelFilter::MoreSevereEqual(Level::Error))
+        );
+        assert_eq!(
+            level(None),
+            Some(LevelFilter::MoreSevereEqual(Level::Error))
+        );
+
+        assert!(matches!(
+            from_str_inner("net.*=info,net.*=warn"),
+            Err(EnvLevelError::ParseEnvVar(_))
+        ));
+    }
 }
@@ -47,6 +47,12 @@ pub enum Error {
     #[error("create directory error: {0}")]
     CreateDirectory(io::Error),
 
+    /// Returned by [`Sink`]s when an error occurs in reading a directory.
+    ///
+    /// [`Sink`]: crate::sink::Sink
+    #[error("read directory error: {0}")]
+    ReadDirectory(io::Error),
+
     /// Returned by [`Sink`]s when an error occurs in opening a file.
     ///
     /// [`Sink`]: crate::sink::Sink
@@ -72,6 +78,34 @@ pub enum Error {
     #[error("remove file error: {0}")]
     RemoveFile(io::Error),
 
+    /// Returned by [`RotatingFileSink`] when an error occurs in compressing a
+    /// rotated file.
+    ///
+    /// [`RotatingFileSink`]: crate::sink::RotatingFileSink
+    #[error("compress file error: {0}")]
+    CompressFile(io::Error),
+
+    /// Returned by [`Sink`]s when an error occurs in connecting to a remote
+    /// endpoint.
+    ///
+    /// [`Sink`]: crate::sink::Sink
+    #[error("connect error: {0}")]
+    Connect(io::Error),
+
+    /// Returned by [`Sink`]s when an error occurs in resolving a remote
+    /// address.
+    ///
+    /// [`Sink`]: crate::sink::Sink
+    #[error("resolve address error: {0}")]
+    ResolveAddress(io::Error),
+
+    /// Returned by [`Sink`]s when an error occurs in creating or binding a local
+    /// socket.
+    ///
+    /// [`Sink`]: crate::sink::Sink
+    #[error("bind socket error: {0}")]
+    BindSocket(io::Error),
+
     /// Returned by [`from_str`] when the string doesn't match any of the log
     /// levels.
     ///
@@ -105,11 +139,32 @@ pub enum Error {
     #[error("failed to serialize log: {0}")]
     SerializeRecord(io::Error),
 
+    /// Returned by [`registry`] functions when a logger cannot be registered.
+    ///
+    /// [`registry`]: crate::registry
+    #[error("registry error: {0}")]
+    Registry(RegistryError),
+
+    /// Returned by [`Config`] when a configuration cannot be loaded or built.
+    ///
+    /// [`Config`]: crate::config::Config
+    #[cfg(feature = "config")]
+    #[error("config error: {0}")]
+    Config(ConfigError),
+
+    /// Returned by [`HttpSink`] when records cannot be delivered.
+    ///
+    /// [`HttpSink`]: crate::sink::HttpSink
+    #[cfg(feature = "http")]
+    #[error("http error: {0}")]
+    Http(HttpError),
+
     /// Returned when multiple errors occurred.
     #[error("{0:?}")]
     Multiple(Vec<Error>),
 
     #[cfg(test)]
+    #[doc(hidden)]
     #[error("{0}")]
     __ForInternalTestsUseOnly(i32),
 }
@@ -138,6 +193,45 @@ pub enum InvalidArgumentError {
     /// Invalid thread pool capacity.
     #[error("'thread pool capacity': {0}")]
     ThreadPoolCapacity(String),
+
+    /// Invalid maximum datagram size.
+    #[error("'max datagram size': {0}")]
+    MaxDatagramSize(String),
+
+    /// Invalid syslog header field or structured data.
+    ///
+    /// See the documentation of [`SyslogSinkBuilder`] for the input
+    /// requirements.
+    ///
+    /// [`SyslogSinkBuilder`]: crate::sink::SyslogSinkBuilder
+    #[error("'syslog': {0}")]
+    Syslog(String),
+
+    /// Invalid journald field.
+    ///
+    /// See the documentation of [`JournaldSinkBuilder::field`] for the input
+    /// requirements.
+    ///
+    /// [`JournaldSinkBuilder::field`]: crate::sink::JournaldSinkBuilder::field
+    #[error("'journald field': {0}")]
+    JournaldField(String),
+
+    /// Invalid HTTP header.
+    #[error("'http header': {0}")]
+    HttpHeader(String),
+
+    /// Invalid ring buffer capacity.
+    #[error("'ring buffer capacity': {0}")]
+    RingBufferCapacity(String),
+
+    /// Invalid file name template.
+    ///
+    /// See the documentation of [`RotatingFileSinkBuilder::file_name_template`]
+    /// for the template requirements.
+    ///
+    /// [`RotatingFileSinkBuilder::file_name_template`]: crate::sink::RotatingFileSinkBuilder::file_name_template
+    #[error("'file name template': {0}")]
+    FileNameTemplate(String),
 }
 
 /// Indicates that an invalid logger name was set.
@@ -169,6 +263,97 @@ impl Display for SetLoggerNameError {
     }
 }
 
+/// Indicates that a logger cannot be registered to the [`registry`].
+///
+/// [`registry`]: crate::registry
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum RegistryError {
+    /// The logger has no name.
+    #[error("the logger has no name")]
+    UnnamedLogger,
+
+    /// A logger with the same name has been registered.
+    #[error("logger '{0}' has already been registered")]
+    AlreadyRegistered(String),
+}
+
+/// Indicates that an error occurred while loading or building a [`Config`].
+///
+/// [`Config`]: crate::config::Config
+#[cfg(feature = "config")]
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// Failed to read the configuration file.
+    #[error("read file error: {0}")]
+    ReadFile(io::Error),
+
+    /// The format of the configuration file cannot be inferred from its
+    /// extension.
+    #[error("unsupported file format: '{0}'")]
+    UnsupportedFormat(String),
+
+    /// Failed to deserialize the configuration document.
+    #[error("{0}")]
+    Deserialize(String),
+
+    /// A key has an invalid value.
+    #[error("'{key}': {message}")]
+    InvalidValue {
+        /// The path of the offending key, e.g. `sinks.console.level_filter`.
+        key: String,
+        /// Describes why the value is invalid.
+        message: String,
+    },
+
+    /// Failed to build the object described by a key.
+    #[error("'{key}': {source}")]
+    Build {
+        /// The path of the offending key, e.g. `sinks.file`.
+        key: String,
+        /// The underlying error.
+        source: Box<Error>,
+    },
+}
+
+/// Indicates that an error occurred in [`HttpSink`].
+///
+/// [`HttpSink`]: crate::sink::HttpSink
+#[cfg(feature = "http")]
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum HttpError {
+    /// The URL is invalid or unsupported.
+    #[error("invalid url: {0}")]
+    InvalidUrl(String),
+
+    /// The queue is full, the record is dropped.
+    #[error("the queue is full, the record is dropped")]
+    QueueFull,
+
+    /// The worker thread has stopped, the record or the flush is dropped.
+    #[error("the worker thread has stopped")]
+    WorkerStopped,
+
+    /// An I/O error occurred in sending a request or receiving the response.
+    #[error("request error: {0}")]
+    Request(io::Error),
+
+    /// The server responded with a non-success status code.
+    #[error("unexpected status: {status} {reason}")]
+    Status {
+        /// The status code.
+        status: u16,
+        /// The reason phrase.
+        reason: String,
+    },
+
+    /// The response is not a valid HTTP response.
+    #[error("invalid response: {0}")]
+    InvalidResponse(String),
+}
+
 /// Indicates that an error occurred while sending to channel.
 #[cfg(feature = "multi-thread")]
 #[derive(Error, Debug)]
@@ -6,7 +6,7 @@ use cfg_if::cfg_if;
 
 use crate::{
     formatter::{fmt_with_time, Formatter, FormatterContext, TimeDate},
-    Error, Record, StringBuf, __EOL,
+    kv, Error, Record, StringBuf, __EOL,
 };
 
 #[rustfmt::skip]
@@ -33,6 +33,12 @@ use crate::{
 ///    <pre>
 ///    [2022-11-02 09:23:12.263] [logger-name] [<font color="#0DBC79">info</font>] [mod::path, src/main.rs:4] hello, world!
 ///    </pre>
+///
+///  - If the log has key-value pairs:
+///
+///    <pre>
+///    [2022-11-02 09:23:12.263] [<font color="#0DBC79">info</font>] hello, world! { user_id=42 ip=127.0.0.1 }
+///    </pre>
 #[derive(Clone)]
 pub struct FullFormatter {
     with_eol: bool,
@@ -94,6 +100,13 @@ impl FullFormatter {
         dest.write_str("] ")?;
         dest.write_str(record.payload())?;
 
+        let kvs = record.key_values();
+        if !kvs.is_empty() {
+            dest.write_str(" { ")?;
+            kv::write_kv(dest, &kvs)?;
+            dest.write_str(" }")?;
+        }
+
         if self.with_eol {
             dest.write_str(__EOL)?;
         }
@@ -126,7 +139,7 @@ mod tests {
     use chrono::prelude::*;
 
     use super::*;
-    use crate::{Level, __EOL};
+    use crate::{kv::Key, Level, __EOL};
 
     #[test]
     fn format() {
@@ -148,4 +161,30 @@ mod tests {
         );
         assert_eq!(Some(27..31), ctx.style_range());
     }
+
+    #[test]
+    fn format_kv() {
+        let kvs = [
+            (Key::__from_static_str("user_id"), 42.into()),
+            (Key::__from_static_str("ip"), "127.0.0.1".into()),
+        ];
+        let record = Record::builder(Level::Info, "login")
+            .key_values(&kvs)
+            .build();
+        let mut buf = StringBuf::new();
+        let mut ctx = FormatterContext::new();
+        FullFormatter::new()
+            .format(&record, &mut buf, &mut ctx)
+            .unwrap();
+
+        let local_time: DateTime<Local> = record.time().into();
+        assert_eq!(
+            format!(
+                "[{}] [info] login {{ user_id=42 ip=127.0.0.1 }}{}",
+                local_time.format("%Y-%m-%d %H:%M:%S.%3f"),
+                __EOL
+            ),
+            buf
+        );
+    }
 }
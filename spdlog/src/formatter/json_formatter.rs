@@ -23,8 +23,11 @@ impl<'a> Serialize for JsonRecord<'a> {
     where
         S: serde::Serializer,
     {
-        let fields_len =
-            4 + opt_to_num(self.0.logger_name()) + opt_to_num(self.0.source_location());
+        let kvs = self.0.key_values();
+        let fields_len = 4
+            + opt_to_num(self.0.logger_name())
+            + opt_to_num(self.0.source_location())
+            + usize::from(!kvs.is_empty());
         let mut record = serializer.serialize_struct("JsonRecord", fields_len)?;
 
         record.serialize_field("level", &self.0.level())?;
@@ -47,6 +50,9 @@ impl<'a> Serialize for JsonRecord<'a> {
         if let Some(src_loc) = self.0.source_location() {
             record.serialize_field("source", src_loc)?;
         }
+        if !kvs.is_empty() {
+            record.serialize_field("kv", &kvs)?;
+        }
 
         record.end()
     }
@@ -99,6 +105,7 @@ impl From<JsonFormatterError> for crate::Error {
 /// | `logger`    | String/Null  | The name of the logger. Null if the logger has no name.                                                                        |
 /// | `tid`       | Integer(u64) | The thread ID when the log was generated.                                                                                      |
 /// | `source`    | Object/Null  | The source location of the log. See [`SourceLocation`] for its schema. Null if crate feature `source-location` is not enabled. |
+/// | `kv`        | Object/Null  | The key-value pairs of the log, keyed by their keys. See [`kv`] module. Null if the log has no key-value pairs.                 |
 /// 
 /// <div class="warning">
 /// 
@@ -133,8 +140,15 @@ impl From<JsonFormatterError> for crate::Error {
 ///    {"level":"error","timestamp":1722817572709,"payload":"something went wrong","tid":3479856,"source":{"module_path":"my_app::say_hi","file":"src/say_hi.rs","line":5,"column":5}}
 ///    ```
 /// 
+///  - If the log has key-value pairs:
+/// 
+///    ```json
+///    {"level":"info","timestamp":1722817424798,"payload":"login","tid":3472525,"kv":{"user_id":42,"ip":"127.0.0.1"}}
+///    ```
+/// 
 /// [`Level::as_str`]: crate::Level::as_str
 /// [`SourceLocation`]: crate::SourceLocation
+/// [`kv`]: crate::kv
 #[derive(Clone)]
 pub struct JsonFormatter(PhantomData<()>);
 
@@ -193,7 +207,7 @@ mod tests {
     use chrono::prelude::*;
 
     use super::*;
-    use crate::{Level, SourceLocation, __EOL};
+    use crate::{kv::Key, Level, SourceLocation, __EOL};
 
     #[test]
     fn should_format_json() {
@@ -267,4 +281,33 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn should_format_json_with_kv() {
+        let mut dest = StringBuf::new();
+        let formatter = JsonFormatter::new();
+        let kvs = [
+            (Key::__from_static_str("user_id"), 42.into()),
+            (Key::__from_static_str("ip"), "127.0.0.1".into()),
+        ];
+        let record = Record::builder(Level::Info, "payload")
+            .key_values(&kvs)
+            .build();
+        let mut ctx = FormatterContext::new();
+        formatter.format(&record, &mut dest, &mut ctx).unwrap();
+
+        let local_time: DateTime<Local> = record.time().into();
+
+        assert_eq!(ctx.style_range(), None);
+        assert_eq!(
+            dest.to_string(),
+            format!(
+                r#"{{"level":"info","timestamp":{},"payload":"{}","tid":{},"kv":{{"user_id":42,"ip":"127.0.0.1"}}}}{}"#,
+                local_time.timestamp_millis(),
+                "payload",
+                record.tid(),
+                __EOL
+            )
+        );
+    }
 }
@@ -49,10 +49,7 @@
 //! [./examples]: https://github.com/SpriteOvO/spdlog-rs/tree/main/spdlog/examples
 
 mod full_formatter;
-#[cfg(any(
-    all(target_os = "linux", feature = "native", feature = "libsystemd"),
-    all(doc, not(doctest))
-))]
+#[cfg(any(all(target_os = "linux", feature = "native"), all(doc, not(doctest))))]
 mod journald_formatter;
 #[cfg(feature = "serde_json")]
 mod json_formatter;
@@ -63,10 +60,7 @@ use std::ops::Range;
 
 use dyn_clone::*;
 pub use full_formatter::*;
-#[cfg(any(
-    all(target_os = "linux", feature = "native", feature = "libsystemd"),
-    all(doc, not(doctest))
-))]
+#[cfg(any(all(target_os = "linux", feature = "native"), all(doc, not(doctest))))]
 pub(crate) use journald_formatter::*;
 #[cfg(feature = "serde_json")]
 pub use json_formatter::*;
@@ -331,6 +331,7 @@ use crate::{
 /// | `{module_path}`       | Source module path           | `mod::module` [^1]                           |
 /// | `{logger}`            | Logger name                  | `my-logger`                                  |
 /// | `{payload}`           | Log payload                  | `log message`                                |
+/// | `{kv}`                | Key-value pairs              | `user_id=42 ip=127.0.0.1`                    |
 /// | `{pid}`               | Process ID                   | `3824`                                       |
 /// | `{tid}`               | Thread ID                    | `3132`                                       |
 /// | `{eol}`               | End of line                  | `\n` (on non-Windows) or `\r\n` (on Windows) |
@@ -401,7 +402,7 @@ pub struct PatternContext<'a, 'b> {
 
 impl PatternContext<'_, '_> {
     #[must_use]
-    fn time_date(&mut self) -> TimeDate {
+    fn time_date(&mut self) -> TimeDate<'_> {
         self.fmt_ctx.locked_time_date.as_mut().unwrap().get()
     }
 }
@@ -461,7 +462,7 @@ impl Pattern for str {
     }
 }
 
-impl<'a, T> Pattern for &'a T
+impl<T> Pattern for &T
 where
     T: ?Sized + Pattern,
 {
@@ -1199,6 +1200,7 @@ tuple_pattern! {
 }
 
 #[cfg(test)]
+#[doc(hidden)]
 pub mod tests {
     use std::ops::Range;
 
@@ -0,0 +1,20 @@
+use crate::{
+    formatter::pattern_formatter::{Pattern, PatternContext},
+    kv, Error, Record, StringBuf,
+};
+
+/// A pattern that writes the key-value pairs of a log record into the output.
+/// Example: `user_id=42 ip=127.0.0.1`.
+#[derive(Clone, Default)]
+pub struct KV;
+
+impl Pattern for KV {
+    fn format(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+        _ctx: &mut PatternContext,
+    ) -> crate::Result<()> {
+        kv::write_kv(dest, &record.key_values()).map_err(Error::FormatRecord)
+    }
+}
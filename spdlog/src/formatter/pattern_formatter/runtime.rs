@@ -132,6 +132,11 @@ pub use spdlog_macros::runtime_pattern;
 pub struct RuntimePattern(Patterns);
 
 impl RuntimePattern {
+    #[cfg(feature = "config")]
+    pub(crate) fn new(template: &str) -> Result<Self> {
+        Self::__with_custom_patterns(template, PatternRegistry::with_builtin())
+    }
+
     // Private function, do not use in your code directly.
     #[doc(hidden)]
     pub fn __with_custom_patterns(template: &str, registry: PatternRegistry) -> Result<Self> {
@@ -253,6 +258,7 @@ fn build_builtin_pattern(builtin: &BuiltInFormatter) -> Box<dyn Pattern> {
         SourceModulePath,
         LoggerName,
         Payload,
+        KV,
         ProcessId,
         ThreadId,
         Eol
@@ -0,0 +1,697 @@
+//! Provides structured key-value pairs for log records.
+//!
+//! Key-value pairs are specified as the trailing named parameter `kv` of log
+//! macros, and they are stored in [`Record`] alongside the payload. All
+//! formatters are able to see them, for example, [`FullFormatter`] appends them
+//! after the payload, [`JsonFormatter`] emits them as fields of object `kv`,
+//! and the placeholder `{kv}` renders them in [`pattern!`].
+//!
+//! # Syntax
+//!
+//! ```ignore
+//! info!("format string", format_args..., kv: { key = value, ... });
+//! ```
+//!
+//! | Input              | Captures                                                   |
+//! |--------------------|------------------------------------------------------------|
+//! | `key = value`      | `value` through trait [`ToValue`], e.g. ints, floats, strs |
+//! | `key = %value`     | `value` through trait [`Display`]                          |
+//! | `key = ?value`     | `value` through trait [`Debug`]                            |
+//! | `key`              | Shorthand for `key = key`                                  |
+//! | `%key` / `?key`    | Shorthand for `key = %key` / `key = ?key`                  |
+//!
+//! A key can be an identifier, dot-separated identifiers (`http.status`), or a
+//! string literal (`"content-type"`).
+//!
+//! # Examples
+//!
+//! ```
+//! use std::net::{IpAddr, Ipv4Addr};
+//!
+//! use spdlog::prelude::*;
+//!
+//! let addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+//! let state = Some("online");
+//!
+//! info!("login", kv: { user_id = 42, ip = %addr, ?state, http.status = 200 });
+//! // [2022-11-02 09:23:12.263] [info] login { user_id=42 ip=127.0.0.1 state=Some("online") http.status=200 }
+//! ```
+//!
+//! [`Record`]: crate::Record
+//! [`FullFormatter`]: crate::formatter::FullFormatter
+//! [`JsonFormatter`]: crate::formatter::JsonFormatter
+//! [`pattern!`]: crate::formatter::pattern
+//! [`Display`]: std::fmt::Display
+//! [`Debug`]: std::fmt::Debug
+
+use std::{
+    borrow::Cow,
+    fmt::{self, Debug, Display},
+};
+
+/// Represents the key of a key-value pair.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Key<'a>(Cow<'a, str>);
+
+impl<'a> Key<'a> {
+    /// Gets the key as a string.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    #[doc(hidden)]
+    #[must_use]
+    pub const fn __from_static_str(key: &'static str) -> Key<'static> {
+        Key(Cow::Borrowed(key))
+    }
+
+    #[must_use]
+    pub(crate) fn to_owned(&self) -> Key<'static> {
+        Key(Cow::Owned(self.0.to_string()))
+    }
+
+    #[must_use]
+    fn as_borrowed(&self) -> Key<'_> {
+        Key(Cow::Borrowed(&self.0))
+    }
+}
+
+impl<'a> From<&'a str> for Key<'a> {
+    fn from(key: &'a str) -> Self {
+        Key(Cow::Borrowed(key))
+    }
+}
+
+impl From<String> for Key<'static> {
+    fn from(key: String) -> Self {
+        Key(Cow::Owned(key))
+    }
+}
+
+impl Display for Key<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Represents the value of a key-value pair.
+///
+/// A `Value` is either a primitive (integers, floats, `bool`, `char`), a
+/// string, or a capture of a type implementing [`Display`] or [`Debug`].
+#[derive(Clone)]
+pub struct Value<'a>(ValueInner<'a>);
+
+#[derive(Clone)]
+enum ValueInner<'a> {
+    Bool(bool),
+    Char(char),
+    I64(i64),
+    U64(u64),
+    I128(i128),
+    U128(u128),
+    F64(f64),
+    Str(&'a str),
+    Display(&'a dyn Display),
+    Debug(&'a dyn Debug),
+}
+
+impl<'a> Value<'a> {
+    /// Captures a value through its [`Display`] implementation.
+    #[must_use]
+    pub fn from_display<T>(value: &'a T) -> Self
+    where
+        T: Display,
+    {
+        Value(ValueInner::Display(value))
+    }
+
+    /// Captures a value through its [`Debug`] implementation.
+    #[must_use]
+    pub fn from_debug<T>(value: &'a T) -> Self
+    where
+        T: Debug,
+    {
+        Value(ValueInner::Debug(value))
+    }
+
+    /// Gets the value as a `bool` if it was captured from a `bool`.
+    #[must_use]
+    pub fn to_bool(&self) -> Option<bool> {
+        match self.0 {
+            ValueInner::Bool(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a `char` if it was captured from a `char`.
+    #[must_use]
+    pub fn to_char(&self) -> Option<char> {
+        match self.0 {
+            ValueInner::Char(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as an `i64` if it was captured from an integer that fits
+    /// into it.
+    #[must_use]
+    pub fn to_i64(&self) -> Option<i64> {
+        match self.0 {
+            ValueInner::I64(v) => Some(v),
+            ValueInner::U64(v) => i64::try_from(v).ok(),
+            ValueInner::I128(v) => i64::try_from(v).ok(),
+            ValueInner::U128(v) => i64::try_from(v).ok(),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a `u64` if it was captured from an integer that fits
+    /// into it.
+    #[must_use]
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.0 {
+            ValueInner::I64(v) => u64::try_from(v).ok(),
+            ValueInner::U64(v) => Some(v),
+            ValueInner::I128(v) => u64::try_from(v).ok(),
+            ValueInner::U128(v) => u64::try_from(v).ok(),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as an `f64` if it was captured from a float.
+    #[must_use]
+    pub fn to_f64(&self) -> Option<f64> {
+        match self.0 {
+            ValueInner::F64(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a `&str` if it was captured from a string.
+    #[must_use]
+    pub fn to_str(&self) -> Option<&str> {
+        match self.0 {
+            ValueInner::Str(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn to_owned(&self) -> ValueOwned {
+        ValueOwned(match self.0 {
+            ValueInner::Bool(v) => ValueOwnedInner::Bool(v),
+            ValueInner::Char(v) => ValueOwnedInner::Char(v),
+            ValueInner::I64(v) => ValueOwnedInner::I64(v),
+            ValueInner::U64(v) => ValueOwnedInner::U64(v),
+            ValueInner::I128(v) => ValueOwnedInner::I128(v),
+            ValueInner::U128(v) => ValueOwnedInner::U128(v),
+            ValueInner::F64(v) => ValueOwnedInner::F64(v),
+            ValueInner::Str(v) => ValueOwnedInner::String(v.to_string()),
+            ValueInner::Display(v) => ValueOwnedInner::String(v.to_string()),
+            ValueInner::Debug(v) => ValueOwnedInner::String(format!("{:?}", v)),
+        })
+    }
+}
+
+impl Display for Value<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            ValueInner::Bool(v) => Display::fmt(&v, f),
+            ValueInner::Char(v) => Display::fmt(&v, f),
+            ValueInner::I64(v) => Display::fmt(&v, f),
+            ValueInner::U64(v) => Display::fmt(&v, f),
+            ValueInner::I128(v) => Display::fmt(&v, f),
+            ValueInner::U128(v) => Display::fmt(&v, f),
+            ValueInner::F64(v) => Display::fmt(&v, f),
+            ValueInner::Str(v) => Display::fmt(v, f),
+            ValueInner::Display(v) => Display::fmt(v, f),
+            ValueInner::Debug(v) => Debug::fmt(v, f),
+        }
+    }
+}
+
+impl Debug for Value<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            ValueInner::Bool(v) => Debug::fmt(&v, f),
+            ValueInner::Char(v) => Debug::fmt(&v, f),
+            ValueInner::I64(v) => Debug::fmt(&v, f),
+            ValueInner::U64(v) => Debug::fmt(&v, f),
+            ValueInner::I128(v) => Debug::fmt(&v, f),
+            ValueInner::U128(v) => Debug::fmt(&v, f),
+            ValueInner::F64(v) => Debug::fmt(&v, f),
+            ValueInner::Str(v) => Debug::fmt(v, f),
+            ValueInner::Display(v) => Display::fmt(v, f),
+            ValueInner::Debug(v) => Debug::fmt(v, f),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Value<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self.0 {
+            ValueInner::Bool(v) => serializer.serialize_bool(v),
+            ValueInner::Char(v) => serializer.serialize_char(v),
+            ValueInner::I64(v) => serializer.serialize_i64(v),
+            ValueInner::U64(v) => serializer.serialize_u64(v),
+            ValueInner::I128(v) => serializer.serialize_i128(v),
+            ValueInner::U128(v) => serializer.serialize_u128(v),
+            ValueInner::F64(v) => serializer.serialize_f64(v),
+            ValueInner::Str(v) => serializer.serialize_str(v),
+            ValueInner::Display(v) => serializer.collect_str(v),
+            ValueInner::Debug(v) => serializer.collect_str(&format_args!("{:?}", v)),
+        }
+    }
+}
+
+macro_rules! impl_from_for_value {
+    ( $($ty:ty => $variant:ident as $as_ty:ty),+ $(,)? ) => {
+        $(
+            impl From<$ty> for Value<'_> {
+                fn from(value: $ty) -> Self {
+                    Value(ValueInner::$variant(value as $as_ty))
+                }
+            }
+
+            impl ToValue for $ty {
+                fn to_value(&self) -> Value<'_> {
+                    Value::from(*self)
+                }
+            }
+        )+
+    };
+}
+
+impl_from_for_value! {
+    bool => Bool as bool,
+    char => Char as char,
+    i8 => I64 as i64,
+    i16 => I64 as i64,
+    i32 => I64 as i64,
+    i64 => I64 as i64,
+    isize => I64 as i64,
+    u8 => U64 as u64,
+    u16 => U64 as u64,
+    u32 => U64 as u64,
+    u64 => U64 as u64,
+    usize => U64 as u64,
+    i128 => I128 as i128,
+    u128 => U128 as u128,
+    f32 => F64 as f64,
+    f64 => F64 as f64,
+}
+
+impl<'a> From<&'a str> for Value<'a> {
+    fn from(value: &'a str) -> Self {
+        Value(ValueInner::Str(value))
+    }
+}
+
+/// Represents a type that can be captured as a [`Value`].
+///
+/// This trait is used by the `kv` parameter of log macros when a value is
+/// specified without `%` or `?`.
+pub trait ToValue {
+    /// Captures the value.
+    #[must_use]
+    fn to_value(&self) -> Value<'_>;
+}
+
+impl ToValue for str {
+    fn to_value(&self) -> Value<'_> {
+        Value::from(self)
+    }
+}
+
+impl ToValue for String {
+    fn to_value(&self) -> Value<'_> {
+        Value::from(self.as_str())
+    }
+}
+
+impl ToValue for Cow<'_, str> {
+    fn to_value(&self) -> Value<'_> {
+        Value::from(&**self)
+    }
+}
+
+impl ToValue for Value<'_> {
+    fn to_value(&self) -> Value<'_> {
+        self.clone()
+    }
+}
+
+impl<T> ToValue for &T
+where
+    T: ToValue + ?Sized,
+{
+    fn to_value(&self) -> Value<'_> {
+        (**self).to_value()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct ValueOwned(ValueOwnedInner);
+
+#[derive(Clone, Debug)]
+enum ValueOwnedInner {
+    Bool(bool),
+    Char(char),
+    I64(i64),
+    U64(u64),
+    I128(i128),
+    U128(u128),
+    F64(f64),
+    String(String),
+}
+
+impl ValueOwned {
+    #[cfg(feature = "log")]
+    #[must_use]
+    fn from_log_crate_value(value: &log::kv::Value) -> Self {
+        struct Visitor(Option<ValueOwnedInner>);
+
+        impl<'v> log::kv::VisitValue<'v> for Visitor {
+            fn visit_any(&mut self, value: log::kv::Value) -> Result<(), log::kv::Error> {
+                self.0 = Some(ValueOwnedInner::String(value.to_string()));
+                Ok(())
+            }
+
+            fn visit_u64(&mut self, value: u64) -> Result<(), log::kv::Error> {
+                self.0 = Some(ValueOwnedInner::U64(value));
+                Ok(())
+            }
+
+            fn visit_i64(&mut self, value: i64) -> Result<(), log::kv::Error> {
+                self.0 = Some(ValueOwnedInner::I64(value));
+                Ok(())
+            }
+
+            fn visit_u128(&mut self, value: u128) -> Result<(), log::kv::Error> {
+                self.0 = Some(ValueOwnedInner::U128(value));
+                Ok(())
+            }
+
+            fn visit_i128(&mut self, value: i128) -> Result<(), log::kv::Error> {
+                self.0 = Some(ValueOwnedInner::I128(value));
+                Ok(())
+            }
+
+            fn visit_f64(&mut self, value: f64) -> Result<(), log::kv::Error> {
+                self.0 = Some(ValueOwnedInner::F64(value));
+                Ok(())
+            }
+
+            fn visit_bool(&mut self, value: bool) -> Result<(), log::kv::Error> {
+                self.0 = Some(ValueOwnedInner::Bool(value));
+                Ok(())
+            }
+
+            fn visit_str(&mut self, value: &str) -> Result<(), log::kv::Error> {
+                self.0 = Some(ValueOwnedInner::String(value.to_string()));
+                Ok(())
+            }
+
+            fn visit_char(&mut self, value: char) -> Result<(), log::kv::Error> {
+                self.0 = Some(ValueOwnedInner::Char(value));
+                Ok(())
+            }
+        }
+
+        let mut visitor = Visitor(None);
+        // `Visitor` never returns errors
+        _ = value.visit(&mut visitor);
+        ValueOwned(
+            visitor
+                .0
+                .unwrap_or_else(|| ValueOwnedInner::String(value.to_string())),
+        )
+    }
+
+    #[must_use]
+    fn as_ref(&self) -> Value<'_> {
+        Value(match &self.0 {
+            ValueOwnedInner::Bool(v) => ValueInner::Bool(*v),
+            ValueOwnedInner::Char(v) => ValueInner::Char(*v),
+            ValueOwnedInner::I64(v) => ValueInner::I64(*v),
+            ValueOwnedInner::U64(v) => ValueInner::U64(*v),
+            ValueOwnedInner::I128(v) => ValueInner::I128(*v),
+            ValueOwnedInner::U128(v) => ValueInner::U128(*v),
+            ValueOwnedInner::F64(v) => ValueInner::F64(*v),
+            ValueOwnedInner::String(v) => ValueInner::Str(v),
+        })
+    }
+}
+
+pub(crate) type Pair<'a> = (Key<'a>, Value<'a>);
+pub(crate) type PairOwned = (Key<'static>, ValueOwned);
+
+/// Represents the key-value pairs of a log record.
+///
+/// # Examples
+///
+/// ```
+/// use spdlog::Record;
+///
+/// fn print_kv(record: &Record) {
+///     for (key, value) in record.key_values().iter() {
+///         println!("{}={}", key, value);
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct KeyValues<'a>(KeyValuesInner<'a>);
+
+#[derive(Clone)]
+enum KeyValuesInner<'a> {
+    Borrowed(&'a [Pair<'a>]),
+    Owned(Cow<'a, [PairOwned]>),
+}
+
+impl<'a> KeyValues<'a> {
+    #[must_use]
+    pub(crate) fn empty() -> Self {
+        KeyValues(KeyValuesInner::Borrowed(&[]))
+    }
+
+    #[must_use]
+    pub(crate) fn with_borrowed(pairs: &'a [Pair<'a>]) -> Self {
+        KeyValues(KeyValuesInner::Borrowed(pairs))
+    }
+
+    #[cfg(feature = "log")]
+    #[must_use]
+    pub(crate) fn from_log_crate_record(record: &log::Record) -> Self {
+        struct Visitor(Vec<PairOwned>);
+
+        impl<'kvs> log::kv::VisitSource<'kvs> for Visitor {
+            fn visit_pair(
+                &mut self,
+                key: log::kv::Key<'kvs>,
+                value: log::kv::Value<'kvs>,
+            ) -> Result<(), log::kv::Error> {
+                self.0.push((
+                    Key::from(key.as_str().to_string()),
+                    ValueOwned::from_log_crate_value(&value),
+                ));
+                Ok(())
+            }
+        }
+
+        let mut visitor = Visitor(vec![]);
+        // `Visitor` never returns errors
+        _ = record.key_values().visit(&mut visitor);
+        Self::with_owned(visitor.0)
+    }
+
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    #[must_use]
+    pub(crate) fn with_owned(pairs: Vec<PairOwned>) -> Self {
+        KeyValues(KeyValuesInner::Owned(Cow::Owned(pairs)))
+    }
+
+    #[must_use]
+    pub(crate) fn with_owned_borrowed(pairs: &'a [PairOwned]) -> Self {
+        KeyValues(KeyValuesInner::Owned(Cow::Borrowed(pairs)))
+    }
+
+    /// Gets the number of key-value pairs.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        match &self.0 {
+            KeyValuesInner::Borrowed(pairs) => pairs.len(),
+            KeyValuesInner::Owned(pairs) => pairs.len(),
+        }
+    }
+
+    /// Returns `true` if there are no key-value pairs.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets an iterator over the key-value pairs, in the order they were
+    /// specified.
+    #[must_use]
+    pub fn iter(&self) -> KeyValuesIter<'_> {
+        KeyValuesIter {
+            kvs: self,
+            index: 0,
+        }
+    }
+
+    /// Gets the value of the first pair with the given key.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<Value<'_>> {
+        self.iter().find(|(k, _)| k.as_str() == key).map(|(_, v)| v)
+    }
+
+    #[must_use]
+    pub(crate) fn as_borrowed(&self) -> KeyValues<'_> {
+        match &self.0 {
+            KeyValuesInner::Borrowed(pairs) => KeyValues::with_borrowed(pairs),
+            KeyValuesInner::Owned(pairs) => KeyValues::with_owned_borrowed(pairs),
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn to_owned(&self) -> Vec<PairOwned> {
+        match &self.0 {
+            KeyValuesInner::Borrowed(pairs) => pairs
+                .iter()
+                .map(|(k, v)| (k.to_owned(), v.to_owned()))
+                .collect(),
+            KeyValuesInner::Owned(pairs) => pairs.to_vec(),
+        }
+    }
+
+    #[must_use]
+    fn get_index(&self, index: usize) -> Option<(Key<'_>, Value<'_>)> {
+        match &self.0 {
+            KeyValuesInner::Borrowed(pairs) => {
+                pairs.get(index).map(|(k, v)| (k.as_borrowed(), v.clone()))
+            }
+            KeyValuesInner::Owned(pairs) => {
+                pairs.get(index).map(|(k, v)| (k.as_borrowed(), v.as_ref()))
+            }
+        }
+    }
+}
+
+impl Debug for KeyValues<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for KeyValues<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_map(self.iter().map(|(k, v)| (k.as_str().to_string(), v)))
+    }
+}
+
+impl<'a> IntoIterator for &'a KeyValues<'_> {
+    type Item = (Key<'a>, Value<'a>);
+    type IntoIter = KeyValuesIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the key-value pairs of a log record.
+///
+/// Returned by [`KeyValues::iter`].
+pub struct KeyValuesIter<'a> {
+    kvs: &'a KeyValues<'a>,
+    index: usize,
+}
+
+impl<'a> Iterator for KeyValuesIter<'a> {
+    type Item = (Key<'a>, Value<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pair = self.kvs.get_index(self.index)?;
+        self.index += 1;
+        Some(pair)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.kvs.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for KeyValuesIter<'_> {}
+
+// Used by `{kv}` pattern and `FullFormatter`
+pub(crate) fn write_kv(dest: &mut impl fmt::Write, kvs: &KeyValues) -> fmt::Result {
+    for (index, (key, value)) in kvs.iter().enumerate() {
+        if index != 0 {
+            dest.write_str(" ")?;
+        }
+        write!(dest, "{}={}", key, value)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_value() {
+        assert_eq!(1_u8.to_value().to_u64(), Some(1));
+        assert_eq!((-1_i32).to_value().to_i64(), Some(-1));
+        assert_eq!((-1_i32).to_value().to_u64(), None);
+        assert_eq!(u128::MAX.to_value().to_u64(), None);
+        assert_eq!(1.5_f32.to_value().to_f64(), Some(1.5));
+        assert_eq!(true.to_value().to_bool(), Some(true));
+        assert_eq!('c'.to_value().to_char(), Some('c'));
+        assert_eq!("str".to_value().to_str(), Some("str"));
+        assert_eq!(String::from("string").to_value().to_str(), Some("string"));
+        assert_eq!((&&"ref").to_value().to_str(), Some("ref"));
+    }
+
+    #[test]
+    fn display_and_debug() {
+        let display = Value::from_display(&"hello");
+        let debug = Value::from_debug(&"hello");
+        assert_eq!(display.to_string(), "hello");
+        assert_eq!(debug.to_string(), "\"hello\"");
+        assert_eq!(display.to_str(), None);
+        assert_eq!(debug.to_owned().as_ref().to_str(), Some("\"hello\""));
+    }
+
+    #[test]
+    fn key_values() {
+        let value = 42;
+        let pairs = [
+            (Key::__from_static_str("a"), value.to_value()),
+            (Key::__from_static_str("b"), Value::from_debug(&"b")),
+        ];
+
+        let borrowed = KeyValues::with_borrowed(&pairs);
+        let owned_pairs = borrowed.to_owned();
+        let owned = KeyValues::with_owned_borrowed(&owned_pairs);
+
+        for kvs in [borrowed, owned] {
+            assert_eq!(kvs.len(), 2);
+            assert_eq!(kvs.get("a").unwrap().to_i64(), Some(42));
+            assert!(kvs.get("c").is_none());
+
+            let mut buf = String::new();
+            write_kv(&mut buf, &kvs).unwrap();
+            assert_eq!(buf, r#"a=42 b="b""#);
+        }
+    }
+}
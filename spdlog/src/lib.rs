@@ -14,9 +14,13 @@
 //! - [Supported Rust versions](#supported-rust-versions)
 //! - Overview of features
 //!   - [Configured via environment variable](init_env_level)
+//!   - [Global registry of named loggers](registry)
+//!   - [Configured via configuration files](config)
 //!   - [Compile-time and runtime pattern formatter]
 //!   - [Asynchronous support]
 //!   - [Compatible with log crate](LogCrateProxy)
+//!   - [Compatible with tracing crate](TracingLayer)
+//!   - [Structured key-value pairs](kv)
 //!
 //! [Compile-time and runtime pattern formatter]: formatter/index.html#compile-time-and-runtime-pattern-formatter
 //! [Asynchronous support]: crate::sink::AsyncPoolSink
@@ -197,7 +201,23 @@
 //!    it contains unsafe code. For more details, see the documentation of
 //!    [`StringBuf`].
 //!
-//!  - `log` enables the compatibility with [log crate].
+//!  - `log` enables the compatibility with [log crate], including forwarding
+//!    its key-value pairs.
+//!
+//!  - `config` enables building loggers and sinks from TOML or JSON
+//!    configuration documents. See [`config`] module for more details.
+//!
+//!  - `tracing` enables the compatibility with [tracing crate] via
+//!    [`TracingLayer`].
+//!
+//!  - `http` enables [`sink::HttpSink`], which sends records to log collectors
+//!    over HTTP.
+//!
+//!  - `gzip` enables [`sink::Compression::Gzip`] for compressing rotated files
+//!    of [`sink::RotatingFileSink`].
+//!
+//!  - `zstd` enables [`sink::Compression::Zstd`] for compressing rotated files
+//!    of [`sink::RotatingFileSink`].
 //!
 //!  - `native` enables platform-specific components, such as
 //!    [`sink::WinDebugSink`] for Windows, [`sink::JournaldSink`] for Linux,
@@ -266,6 +286,7 @@
 //! [open a discussion]: https://github.com/SpriteOvO/spdlog-rs/discussions/new
 //! [open an issue]: https://github.com/SpriteOvO/spdlog-rs/issues/new/choose
 //! [log crate]: https://crates.io/crates/log
+//! [tracing crate]: https://crates.io/crates/tracing
 //! [`Formatter`]: crate::formatter::Formatter
 //! [`RuntimePattern`]: crate::formatter::RuntimePattern
 //! [`RotationPolicy::Daily`]: crate::sink::RotationPolicy::Daily
@@ -276,9 +297,12 @@
 #![cfg_attr(all(doc, CHANNEL_NIGHTLY), feature(doc_auto_cfg))]
 #![warn(missing_docs)]
 
+#[cfg(feature = "config")]
+pub mod config;
 mod env_level;
 pub mod error;
 pub mod formatter;
+pub mod kv;
 mod level;
 #[cfg(feature = "log")]
 mod log_crate_proxy;
@@ -287,6 +311,7 @@ mod logger;
 mod periodic_worker;
 pub mod re_export;
 mod record;
+pub mod registry;
 pub mod sink;
 mod source_location;
 #[doc(hidden)]
@@ -297,6 +322,8 @@ pub mod terminal_style;
 mod test_utils;
 #[cfg(feature = "multi-thread")]
 mod thread_pool;
+#[cfg(feature = "tracing")]
+mod tracing_layer;
 mod utils;
 
 pub use error::{Error, ErrorHandler, Result};
@@ -306,9 +333,13 @@ pub use log_crate_proxy::*;
 pub use logger::*;
 pub use record::*;
 pub use source_location::*;
+#[doc(hidden)]
+pub use spdlog_macros::split_kv as __split_kv;
 pub use string_buf::StringBuf;
 #[cfg(feature = "multi-thread")]
 pub use thread_pool::*;
+#[cfg(feature = "tracing")]
+pub use tracing_layer::*;
 
 /// Contains all log macros and common types.
 pub mod prelude {
@@ -532,6 +563,14 @@ pub fn set_default_logger(logger: Arc<Logger>) {
 ///
 /// ---
 ///
+/// - Specifies the level filter for ***loggers with names matching the
+///   specified pattern***, where `*` matches any sequence of characters and `?`
+///   matches any single character.
+///
+///   Possible inputs: `net.*=debug`, `db::pool*=trace`, `*.http=warn`, etc.
+///
+/// ---
+///
 /// - Specifies the level filter for ***all loggers except the default logger***
 ///   (respect the above rules first if they are matched).
 ///
@@ -567,6 +606,24 @@ pub fn set_default_logger(logger: Arc<Logger>) {
 ///
 /// ---
 ///
+/// - `*=warn,net.*=info,net.http*=debug,net.http.client=trace`
+///
+///   Specifies the level filter for loggers with name "net.http.client" as
+///   `LevelFilter::MoreSevereEqual(Level::Trace)`, loggers with names starting
+///   with "net.http" as `LevelFilter::MoreSevereEqual(Level::Debug)`, other
+///   loggers with names starting with "net." as
+///   `LevelFilter::MoreSevereEqual(Level::Info)`, the rest of loggers except
+///   the default logger as `LevelFilter::MoreSevereEqual(Level::Warn)`.
+///
+/// ---
+///
+/// When multiple rules match a logger name, the most specific one wins:
+///
+/// 1. The rule with exactly the name.
+/// 2. The rule with a matching pattern that has the most non-wildcard
+///    characters. If there is still a tie, the one with fewer `*` wins.
+/// 3. The rule `*`.
+///
 /// However, the same rule cannot be specified more than once.
 ///
 /// # Examples
@@ -690,6 +747,62 @@ pub fn init_env_level_from<K: AsRef<OsStr>>(env_key: K) -> StdResult<bool, EnvLe
     Ok(true)
 }
 
+/// Applies a level spec to existing loggers at runtime.
+///
+/// The spec has the same format as the environment variable described in
+/// [`init_env_level`]. Unlike [`init_env_level`], which only affects loggers
+/// built afterward, this function also updates the level filters of the global
+/// default logger and all loggers in the [`registry`] that are matched by the
+/// spec. Loggers not matched by the spec keep their current level filters,
+/// including the ones set by a previously applied spec, i.e. they are not reset
+/// to the level filters they were built with.
+///
+/// The spec replaces the previously initialized environment level, so loggers
+/// built afterward respect it as well.
+///
+/// Loggers that are neither the default logger nor registered are unknown to
+/// this function and are not affected.
+///
+/// This is useful for changing verbosity without restarting, e.g. from an
+/// admin endpoint or a `SIGHUP` handler.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::prelude::*;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let net = Arc::new(Logger::builder().name("net").build()?);
+/// spdlog::registry::register(net.clone())?;
+///
+/// spdlog::apply_level_spec("warn,net=debug")?;
+///
+/// assert_eq!(
+///     spdlog::default_logger().level_filter(),
+///     LevelFilter::MoreSevereEqual(Level::Warn)
+/// );
+/// assert_eq!(
+///     net.level_filter(),
+///     LevelFilter::MoreSevereEqual(Level::Debug)
+/// );
+/// assert_eq!(
+///     Logger::builder().name("net").build()?.level_filter(),
+///     LevelFilter::MoreSevereEqual(Level::Debug)
+/// );
+/// # Ok(()) }
+/// ```
+pub fn apply_level_spec(spec: &str) -> StdResult<(), EnvLevelError> {
+    env_level::from_str(spec)?;
+
+    if let Some(level) = env_level::logger_level(env_level::LoggerKind::Default) {
+        default_logger().set_level_filter(level);
+    }
+    registry::apply_all(|logger| registry::apply_env_level(logger));
+    Ok(())
+}
+
 /// Initializes the log crate proxy.
 ///
 /// This function calls [`log::set_logger`] to set up a [`LogCrateProxy`] and
@@ -781,6 +894,7 @@ pub fn __log(
     logger: &Logger,
     level: Level,
     srcloc: Option<SourceLocation>,
+    kvs: &[(kv::Key, kv::Value)],
     fmt_args: std::fmt::Arguments,
 ) {
     // use `Cow` to avoid allocation as much as we can
@@ -789,7 +903,9 @@ pub fn __log(
         None => fmt_args.to_string().into(),
     };
 
-    let mut builder = Record::builder(level, payload).source_location(srcloc);
+    let mut builder = Record::builder(level, payload)
+        .source_location(srcloc)
+        .key_values(kvs);
     if let Some(logger_name) = logger.name() {
         builder = builder.logger_name(logger_name);
     }
@@ -827,4 +943,47 @@ mod tests {
             vec!["hello".to_string(), "rust".to_string()]
         );
     }
+
+    #[test]
+    fn respecify_registered_loggers() {
+        let build = |name: &str| {
+            Arc::new(build_test_logger(|b| {
+                b.name(name)
+                    .level_filter(LevelFilter::MoreSevereEqual(Level::Info))
+            }))
+        };
+        let (a, b) = (build("level_spec_test.a"), build("level_spec_test.b"));
+        registry::register(a.clone()).unwrap();
+        registry::register(b.clone()).unwrap();
+        let unregistered = build("level_spec_test.unregistered");
+
+        apply_level_spec("level_spec_test.a=debug").unwrap();
+        assert_eq!(a.level_filter(), LevelFilter::MoreSevereEqual(Level::Debug));
+        assert_eq!(b.level_filter(), LevelFilter::MoreSevereEqual(Level::Info));
+
+        // Unmatched loggers keep the level filters set by the previous spec.
+        apply_level_spec("level_spec_test.b=error").unwrap();
+        assert_eq!(a.level_filter(), LevelFilter::MoreSevereEqual(Level::Debug));
+        assert_eq!(b.level_filter(), LevelFilter::MoreSevereEqual(Level::Error));
+
+        apply_level_spec("level_spec_test.*=trace").unwrap();
+        assert_eq!(a.level_filter(), LevelFilter::MoreSevereEqual(Level::Trace));
+        assert_eq!(b.level_filter(), LevelFilter::MoreSevereEqual(Level::Trace));
+        assert_eq!(
+            unregistered.level_filter(),
+            LevelFilter::MoreSevereEqual(Level::Info)
+        );
+        assert_eq!(
+            build_test_logger(|b| b.name("level_spec_test.c")).level_filter(),
+            LevelFilter::MoreSevereEqual(Level::Trace)
+        );
+
+        // An invalid spec changes nothing.
+        assert!(apply_level_spec("level_spec_test.a=invalid").is_err());
+        assert_eq!(a.level_filter(), LevelFilter::MoreSevereEqual(Level::Trace));
+
+        apply_level_spec("").unwrap();
+        registry::drop("level_spec_test.a").unwrap();
+        registry::drop("level_spec_test.b").unwrap();
+    }
 }
@@ -16,6 +16,10 @@ use crate::{default_logger, sync::*, Logger, Record};
 /// messages from `log` crate, you may need to call
 /// [`re_export::log::set_max_level`] with [`re_export::log::LevelFilter`].
 ///
+/// Key-value pairs attached to log messages with `log` crate's `kv` feature are
+/// forwarded as well, they are available via [`Record::key_values`] to sinks
+/// and formatters.
+///
 /// ## Examples
 ///
 /// ```
@@ -16,20 +16,53 @@
 ///
 /// // Or using the specified logger
 /// log!(logger: app_events, Level::Info, "Received data: {}, {}", data.0, data.1);
+///
+/// // With structured key-value pairs
+/// log!(Level::Info, "Received data", kv: { id = data.0, name = data.1 });
 /// ```
 ///
 /// [`Level`]: crate::Level
 #[macro_export]
 macro_rules! log {
-    (logger: $logger:expr, $level:expr, $($arg:tt)+) => ({
+    (logger: $logger:expr, $level:expr, $($arg:tt)+) => (
+        $crate::__split_kv!($crate::__log_impl, [$logger, $level], $($arg)+)
+    );
+    ($level:expr, $($arg:tt)+) => ($crate::log!(logger: $crate::default_logger(), $level, $($arg)+))
+}
+
+// Called back by `__split_kv!` after the trailing `kv: { ... }` is split from
+// the format arguments.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_impl {
+    ($logger:expr, $level:expr, fmt: [$($arg:tt)+], kv: [$(($key:literal, $mode:tt, $value:expr)),*]) => ({
         let logger = &$logger;
         const LEVEL: $crate::Level = $level;
         const SHOULD_LOG: bool = $crate::STATIC_LEVEL_FILTER.__test_const(LEVEL);
         if SHOULD_LOG && logger.should_log(LEVEL) {
-            $crate::__log(logger, LEVEL, $crate::source_location_current!(), format_args!($($arg)+));
+            $crate::__log(
+                logger,
+                LEVEL,
+                $crate::source_location_current!(),
+                &[$(($crate::kv::Key::__from_static_str($key), $crate::__kv_value!($mode $value))),*],
+                format_args!($($arg)+),
+            );
         }
     });
-    ($level:expr, $($arg:tt)+) => ($crate::log!(logger: $crate::default_logger(), $level, $($arg)+))
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __kv_value {
+    (_ $value:expr) => {
+        $crate::kv::ToValue::to_value(&$value)
+    };
+    (% $value:expr) => {
+        $crate::kv::Value::from_display(&$value)
+    };
+    (? $value:expr) => {
+        $crate::kv::Value::from_debug(&$value)
+    };
 }
 
 /// Logs a message at the critical level.
@@ -126,6 +159,9 @@ macro_rules! warn {
 ///
 /// // Or using the specified logger
 /// info!(logger: conn_events, "Successfull connection, port: {}, speed: {}", conn_info.port, conn_info.speed);
+///
+/// // With structured key-value pairs
+/// info!("Successfull connection", kv: { port = conn_info.port, speed = conn_info.speed });
 /// ```
 #[macro_export]
 macro_rules! info {
@@ -4,7 +4,10 @@ use std::{
     time::SystemTime,
 };
 
-use crate::{Level, SourceLocation};
+use crate::{
+    kv::{self, KeyValues},
+    Level, SourceLocation,
+};
 
 /// Represents a log record.
 ///
@@ -24,6 +27,7 @@ use crate::{Level, SourceLocation};
 pub struct Record<'a> {
     logger_name: Option<Cow<'a, str>>,
     payload: Cow<'a, str>,
+    kvs: KeyValues<'a>,
     inner: Cow<'a, RecordInner>,
 }
 
@@ -44,6 +48,7 @@ impl<'a> Record<'a> {
         Record {
             logger_name: None,
             payload: payload.into(),
+            kvs: KeyValues::empty(),
             inner: Cow::Owned(RecordInner {
                 level,
                 source_location: None,
@@ -67,6 +72,7 @@ impl<'a> Record<'a> {
         RecordOwned {
             logger_name: self.logger_name.clone().map(|n| n.into_owned()),
             payload: self.payload.to_string(),
+            kvs: self.kvs.to_owned(),
             inner: self.inner.clone().into_owned(),
         }
     }
@@ -107,6 +113,16 @@ impl<'a> Record<'a> {
         self.inner.tid
     }
 
+    /// Gets the structured key-value pairs.
+    ///
+    /// See [`kv`] module for how to attach them to a log.
+    ///
+    /// [`kv`]: crate::kv
+    #[must_use]
+    pub fn key_values(&self) -> KeyValues<'_> {
+        self.kvs.as_borrowed()
+    }
+
     // When adding more getters, also add to `RecordOwned`
 
     #[must_use]
@@ -114,6 +130,7 @@ impl<'a> Record<'a> {
         Self {
             logger_name: self.logger_name.clone(),
             payload: new.into(),
+            kvs: self.kvs.as_borrowed(),
             inner: Cow::Borrowed(&self.inner),
         }
     }
@@ -142,6 +159,7 @@ impl<'a> Record<'a> {
                 Some(literal_str) => literal_str.into(),
                 None => args.to_string().into(),
             },
+            kvs: KeyValues::from_log_crate_record(record),
             inner: Cow::Owned(RecordInner {
                 level: record.level().into(),
                 source_location: SourceLocation::from_log_crate_record(record),
@@ -153,6 +171,29 @@ impl<'a> Record<'a> {
         }
     }
 
+    #[cfg(feature = "tracing")]
+    #[must_use]
+    pub(crate) fn from_tracing_event(
+        logger_name: Option<&'a str>,
+        metadata: &'static tracing::Metadata<'static>,
+        payload: String,
+        kvs: Vec<kv::PairOwned>,
+        time: SystemTime,
+    ) -> Self {
+        Self {
+            logger_name: logger_name.map(Cow::Borrowed),
+            payload: payload.into(),
+            kvs: KeyValues::with_owned(kvs),
+            inner: Cow::Owned(RecordInner {
+                level: (*metadata.level()).into(),
+                source_location: SourceLocation::from_tracing_metadata(metadata),
+                time,
+                // `tracing` dispatches events synchronously on the thread they are emitted
+                tid: get_current_tid(),
+            }),
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn set_time(&mut self, new: SystemTime) {
         self.inner.to_mut().time = new;
@@ -166,16 +207,18 @@ impl<'a> Record<'a> {
 pub struct RecordOwned {
     logger_name: Option<String>,
     payload: String,
+    kvs: Vec<kv::PairOwned>,
     inner: RecordInner,
 }
 
 impl RecordOwned {
     /// References as [`Record`] cheaply.
     #[must_use]
-    pub fn as_ref(&self) -> Record {
+    pub fn as_ref(&self) -> Record<'_> {
         Record {
             logger_name: self.logger_name.as_deref().map(Cow::Borrowed),
             payload: Cow::Borrowed(&self.payload),
+            kvs: KeyValues::with_owned_borrowed(&self.kvs),
             inner: Cow::Borrowed(&self.inner),
         }
     }
@@ -210,6 +253,12 @@ impl RecordOwned {
         self.inner.time
     }
 
+    /// Gets the structured key-value pairs.
+    #[must_use]
+    pub fn key_values(&self) -> KeyValues<'_> {
+        KeyValues::with_owned_borrowed(&self.kvs)
+    }
+
     // When adding more getters, also add to `Record`
 }
 
@@ -253,6 +302,13 @@ impl<'a> RecordBuilder<'a> {
         self
     }
 
+    /// Sets the key-value pairs.
+    #[must_use]
+    pub(crate) fn key_values(mut self, kvs: &'a [kv::Pair<'a>]) -> Self {
+        self.record.kvs = KeyValues::with_borrowed(kvs);
+        self
+    }
+
     /// Builds a [`Record`].
     #[must_use]
     pub(crate) fn build(self) -> Record<'a> {
@@ -0,0 +1,271 @@
+//! Provides a global registry of named loggers.
+//!
+//! Loggers are registered by their names and can be looked up from anywhere in
+//! the program, so named loggers no longer have to be passed around manually.
+//!
+//! # Hierarchy
+//!
+//! Logger names are treated as dot-separated hierarchies. When [`get`] is
+//! called with a name that is not registered, the nearest registered ancestor
+//! is looked up (e.g. `net` for `net.http`, then `net.http` for
+//! `net.http.client`). If one is found, a child logger is forked from it, which
+//! inherits its sinks, level filter, flush level filter and error handler, and
+//! the child is registered under the requested name. A child can be overridden
+//! by registering a logger with the same name explicitly before it's first
+//! looked up, or by replacing it with [`register_or_replace`].
+//!
+//! Loggers inherit properties at the time they are created, changes to a parent
+//! after that are not propagated to its children. Use [`apply_all`] to change
+//! all registered loggers at once.
+//!
+//! # Environment level
+//!
+//! If the [environment level] is initialized, the level filter specified for a
+//! logger name is applied to loggers when they are registered, including those
+//! created through the hierarchy. To change the level filters of registered
+//! loggers at runtime, use [`apply_level_spec`].
+//!
+//! # Examples
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use spdlog::{prelude::*, registry};
+//!
+//! # fn main() -> Result<(), spdlog::Error> {
+//! let net = Arc::new(Logger::builder().name("net").build()?);
+//! registry::register(net)?;
+//!
+//! // `net.http` is not registered, it's forked from `net`.
+//! let http = registry::get("net.http").unwrap();
+//! assert_eq!(http.name(), Some("net.http"));
+//! info!(logger: http, "hello from net.http");
+//!
+//! registry::apply_all(|logger| logger.set_level_filter(LevelFilter::All));
+//!
+//! registry::drop("net.http");
+//! # Ok(()) }
+//! ```
+//!
+//! [environment level]: crate::init_env_level
+//! [`apply_level_spec`]: crate::apply_level_spec
+
+use std::collections::HashMap;
+
+use crate::{
+    env_level,
+    error::{Error, RegistryError},
+    sync::*,
+    Logger, Result,
+};
+
+static REGISTRY: Lazy<RwLock<HashMap<String, Arc<Logger>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Registers a logger by its name.
+///
+/// The level filter specified in the [environment level] for the name, if any,
+/// is applied to the logger.
+///
+/// # Errors
+///
+/// Returns [`RegistryError::UnnamedLogger`] if the logger has no name, or
+/// [`RegistryError::AlreadyRegistered`] if a logger with the same name has been
+/// registered.
+///
+/// [environment level]: crate::init_env_level
+pub fn register(logger: Arc<Logger>) -> Result<()> {
+    let name = logger_name(&logger)?;
+
+    let mut registry = REGISTRY.write_expect();
+    if registry.contains_key(&name) {
+        return Err(Error::Registry(RegistryError::AlreadyRegistered(name)));
+    }
+    apply_env_level(&logger);
+    registry.insert(name, logger);
+    Ok(())
+}
+
+/// Registers a logger by its name, and returns the replaced one, if any.
+///
+/// Same as [`register`], but replaces the logger with the same name instead of
+/// returning an error.
+///
+/// # Errors
+///
+/// Returns [`RegistryError::UnnamedLogger`] if the logger has no name.
+pub fn register_or_replace(logger: Arc<Logger>) -> Result<Option<Arc<Logger>>> {
+    let name = logger_name(&logger)?;
+
+    apply_env_level(&logger);
+    Ok(REGISTRY.write_expect().insert(name, logger))
+}
+
+// Registers or replaces the loggers and removes the `removed` names under a
+// single lock, so that the registry never contains some of the new loggers
+// along with the old ones. Returns the replaced and removed loggers.
+//
+// The loggers must be named.
+#[cfg(feature = "config")]
+pub(crate) fn replace_many(
+    loggers: impl IntoIterator<Item = Arc<Logger>>,
+    removed: &[String],
+) -> Vec<Arc<Logger>> {
+    let loggers = loggers
+        .into_iter()
+        .filter_map(|logger| Some((logger_name(&logger).ok()?, logger)))
+        .collect::<Vec<_>>();
+    loggers
+        .iter()
+        .for_each(|(_, logger)| apply_env_level(logger));
+
+    let mut registry = REGISTRY.write_expect();
+    let mut old = removed
+        .iter()
+        .filter_map(|name| registry.remove(name))
+        .collect::<Vec<_>>();
+    old.extend(
+        loggers
+            .into_iter()
+            .filter_map(|(name, logger)| registry.insert(name, logger)),
+    );
+    old
+}
+
+/// Gets a logger by its name.
+///
+/// If the name is not registered, a child logger forked from its nearest
+/// registered ancestor is registered and returned. See the [module level
+/// documentation](self#hierarchy) for details.
+///
+/// Returns `None` if neither the name nor any of its ancestors is registered.
+#[must_use]
+pub fn get(name: &str) -> Option<Arc<Logger>> {
+    if let Some(logger) = REGISTRY.read_expect().get(name) {
+        return Some(logger.clone());
+    }
+
+    let mut registry = REGISTRY.write_expect();
+    // Check again, it may have been registered by another thread.
+    if let Some(logger) = registry.get(name) {
+        return Some(logger.clone());
+    }
+
+    let parent = ancestors(name).find_map(|ancestor| registry.get(ancestor))?;
+    let child = parent.fork_with_name(Some(name)).ok()?;
+    apply_env_level(&child);
+    registry.insert(name.to_string(), child.clone());
+    Some(child)
+}
+
+/// Removes a logger from the registry by its name, and returns it.
+///
+/// Children already forked from it are kept.
+pub fn drop(name: &str) -> Option<Arc<Logger>> {
+    REGISTRY.write_expect().remove(name)
+}
+
+/// Removes all loggers from the registry.
+pub fn drop_all() {
+    REGISTRY.write_expect().clear();
+}
+
+/// Calls a function for each registered logger.
+///
+/// The registry is locked during the call, so the function should not call
+/// other functions of this module.
+pub fn apply_all<F>(f: F)
+where
+    F: FnMut(&Arc<Logger>),
+{
+    REGISTRY.read_expect().values().for_each(f);
+}
+
+fn logger_name(logger: &Logger) -> Result<String> {
+    logger
+        .name()
+        .map(String::from)
+        .ok_or(Error::Registry(RegistryError::UnnamedLogger))
+}
+
+pub(crate) fn apply_env_level(logger: &Logger) {
+    if let Some(level) = env_level::logger_level(env_level::LoggerKind::Other(logger.name())) {
+        logger.set_level_filter(level);
+    }
+}
+
+// Yields `a.b` and then `a` for `a.b.c`.
+fn ancestors(name: &str) -> impl Iterator<Item = &str> {
+    let mut rest = name;
+    std::iter::from_fn(move || {
+        let index = rest.rfind('.')?;
+        rest = &rest[..index];
+        Some(rest)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::*, Level, LevelFilter};
+
+    #[test]
+    fn ancestors_of_name() {
+        assert_eq!(ancestors("a.b.c").collect::<Vec<_>>(), vec!["a.b", "a"]);
+        assert_eq!(ancestors("a").count(), 0);
+    }
+
+    #[test]
+    fn register_and_get() {
+        let sink = Arc::new(TestSink::new());
+        let parent = Arc::new(build_test_logger(|b| {
+            b.name("registry_test")
+                .sink(sink.clone())
+                .level_filter(LevelFilter::MoreSevereEqual(Level::Warn))
+        }));
+
+        register(parent.clone()).unwrap();
+        assert!(matches!(
+            register(parent.clone()),
+            Err(Error::Registry(RegistryError::AlreadyRegistered(_)))
+        ));
+        assert!(matches!(
+            register(Arc::new(build_test_logger(|b| b))),
+            Err(Error::Registry(RegistryError::UnnamedLogger))
+        ));
+        assert!(Arc::ptr_eq(&get("registry_test").unwrap(), &parent));
+
+        assert!(get("registry_test_other.child").is_none());
+
+        let child = get("registry_test.a.b").unwrap();
+        assert_eq!(child.name(), Some("registry_test.a.b"));
+        assert_eq!(
+            child.level_filter(),
+            LevelFilter::MoreSevereEqual(Level::Warn)
+        );
+        assert!(Arc::ptr_eq(&get("registry_test.a.b").unwrap(), &child));
+
+        crate::error!(logger: child, "to the parent sink");
+        assert_eq!(sink.log_count(), 1);
+
+        // An explicitly registered logger overrides the inherited one.
+        let overridden = Arc::new(build_test_logger(|b| b.name("registry_test.a.b")));
+        assert!(Arc::ptr_eq(
+            &register_or_replace(overridden.clone()).unwrap().unwrap(),
+            &child
+        ));
+        assert!(Arc::ptr_eq(&get("registry_test.a.b").unwrap(), &overridden));
+
+        let mut count = 0;
+        apply_all(|logger| {
+            if logger.name().unwrap().starts_with("registry_test") {
+                count += 1;
+            }
+        });
+        assert_eq!(count, 2);
+
+        assert!(drop("registry_test.a.b").is_some());
+        assert!(drop("registry_test").is_some());
+        assert!(get("registry_test.a.b").is_none());
+    }
+}
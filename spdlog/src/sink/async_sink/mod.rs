@@ -2,12 +2,13 @@ mod async_pool_sink;
 
 pub use async_pool_sink::*;
 
-/// Overflow policy for [asynchronous sinks].
+/// Overflow policy for [asynchronous sinks] and [`ChannelSink`].
 ///
 /// When the channel is full, an incoming operation is handled according to the
 /// specified policy.
 ///
 /// [asynchronous sinks]: crate::sink::AsyncPoolSink
+/// [`ChannelSink`]: crate::sink::ChannelSink
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 #[non_exhaustive]
 pub enum OverflowPolicy {
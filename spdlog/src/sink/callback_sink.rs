@@ -0,0 +1,234 @@
+use std::convert::Infallible;
+
+use crate::{
+    formatter::FormatterContext,
+    sink::{helper, Sink},
+    Record, Result, StringBuf,
+};
+
+type BoxedCallback = Box<dyn Fn(&Record, &str) -> Result<()> + Send + Sync>;
+
+/// A sink that calls a closure for each record.
+///
+/// This is useful for one-off integrations that don't deserve a whole [`Sink`]
+/// implementation. Level filtering, the formatter and the error handler are
+/// handled by the sink itself, same as other built-in sinks.
+///
+/// There are two kinds of callbacks:
+///
+///  - [`CallbackSinkBuilder::callback`] receives the [`Record`], the formatter
+///    of the sink is not used.
+///
+///  - [`CallbackSinkBuilder::formatted_callback`] receives the [`Record`] and
+///    the string formatted by the formatter of the sink.
+///
+/// Errors returned from the callback are returned from [`Sink::log`].
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::{prelude::*, sink::CallbackSink};
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// let sink = CallbackSink::builder()
+///     .formatted_callback(|record, formatted| {
+///         eprint!("alert from {:?}: {}", record.logger_name(), formatted);
+///         Ok(())
+///     })
+///     .level_filter(LevelFilter::MoreSevereEqual(Level::Error))
+///     .build()?;
+/// let logger = Logger::builder().sink(Arc::new(sink)).build()?;
+///
+/// error!(logger: logger, "disk is full");
+/// # Ok(()) }
+/// ```
+pub struct CallbackSink {
+    common_impl: helper::CommonImpl,
+    callback: BoxedCallback,
+    formatted: bool,
+}
+
+impl CallbackSink {
+    /// Gets a builder of `CallbackSink` with default parameters:
+    ///
+    /// | Parameter                          | Default Value           |
+    /// |------------------------------------|-------------------------|
+    /// | [level_filter]                     | `All`                   |
+    /// | [formatter]                        | `FullFormatter`         |
+    /// | [error_handler]                    | [default error handler] |
+    /// |                                    |                         |
+    /// | [callback] or [formatted_callback] | *must be specified*     |
+    ///
+    /// [level_filter]: CallbackSinkBuilder::level_filter
+    /// [formatter]: CallbackSinkBuilder::formatter
+    /// [error_handler]: CallbackSinkBuilder::error_handler
+    /// [default error handler]: error/index.html#default-error-handler
+    /// [callback]: CallbackSinkBuilder::callback
+    /// [formatted_callback]: CallbackSinkBuilder::formatted_callback
+    #[must_use]
+    pub fn builder() -> CallbackSinkBuilder<()> {
+        CallbackSinkBuilder {
+            common_builder_impl: helper::CommonBuilderImpl::new(),
+            callback: (),
+            formatted: false,
+        }
+    }
+}
+
+impl Sink for CallbackSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        if !self.formatted {
+            return (self.callback)(record, "");
+        }
+
+        let mut string_buf = StringBuf::new();
+        let mut ctx = FormatterContext::new();
+        self.common_impl
+            .formatter
+            .read()
+            .format(record, &mut string_buf, &mut ctx)?;
+        (self.callback)(record, &string_buf)
+    }
+
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    helper::common_impl!(@Sink: common_impl);
+}
+
+/// #
+#[doc = include_str!("../include/doc/generic-builder-note.md")]
+pub struct CallbackSinkBuilder<ArgCb> {
+    common_builder_impl: helper::CommonBuilderImpl,
+    callback: ArgCb,
+    formatted: bool,
+}
+
+impl<ArgCb> CallbackSinkBuilder<ArgCb> {
+    /// Specifies the callback that receives each record.
+    ///
+    /// The formatter of the sink is not used.
+    ///
+    /// This parameter or [`formatted_callback`] is **required**.
+    ///
+    /// [`formatted_callback`]: CallbackSinkBuilder::formatted_callback
+    #[must_use]
+    pub fn callback<F>(self, callback: F) -> CallbackSinkBuilder<BoxedCallback>
+    where
+        F: Fn(&Record) -> Result<()> + Send + Sync + 'static,
+    {
+        CallbackSinkBuilder {
+            common_builder_impl: self.common_builder_impl,
+            callback: Box::new(move |record, _| callback(record)),
+            formatted: false,
+        }
+    }
+
+    /// Specifies the callback that receives each record and the string
+    /// formatted by the formatter of the sink.
+    ///
+    /// This parameter or [`callback`] is **required**.
+    ///
+    /// [`callback`]: CallbackSinkBuilder::callback
+    #[must_use]
+    pub fn formatted_callback<F>(self, callback: F) -> CallbackSinkBuilder<BoxedCallback>
+    where
+        F: Fn(&Record, &str) -> Result<()> + Send + Sync + 'static,
+    {
+        CallbackSinkBuilder {
+            common_builder_impl: self.common_builder_impl,
+            callback: Box::new(callback),
+            formatted: true,
+        }
+    }
+
+    helper::common_impl!(@SinkBuilder: common_builder_impl);
+}
+
+impl CallbackSinkBuilder<()> {
+    #[doc(hidden)]
+    #[deprecated(note = "\n\n\
+        builder compile-time error:\n\
+        - missing required parameter `callback` or `formatted_callback`\n\n\
+    ")]
+    pub fn build(self, _: Infallible) {}
+}
+
+impl CallbackSinkBuilder<BoxedCallback> {
+    /// Builds a [`CallbackSink`].
+    pub fn build(self) -> Result<CallbackSink> {
+        Ok(CallbackSink {
+            common_impl: helper::CommonImpl::from_builder(self.common_builder_impl),
+            callback: self.callback,
+            formatted: self.formatted,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, sync::*, test_utils::*, Error};
+
+    #[test]
+    fn callback() {
+        let records = Arc::new(Mutex::new(vec![]));
+        let sink = {
+            let records = records.clone();
+            CallbackSink::builder()
+                .callback(move |record| {
+                    records.lock_expect().push(record.to_owned());
+                    Ok(())
+                })
+                .level_filter(LevelFilter::MoreSevereEqual(Level::Warn))
+                .build()
+                .unwrap()
+        };
+        let test = build_test_logger(|b| b.sink(Arc::new(sink)));
+
+        info!(logger: test, "filtered");
+        warn!(logger: test, "hello");
+
+        let records = records.lock_expect();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].level(), Level::Warn);
+        assert_eq!(records[0].payload(), "hello");
+    }
+
+    #[test]
+    fn formatted_callback() {
+        let formatted = Arc::new(Mutex::new(vec![]));
+        let sink = {
+            let formatted = formatted.clone();
+            CallbackSink::builder()
+                .formatted_callback(move |record, string| {
+                    formatted
+                        .lock_expect()
+                        .push(format!("{} {}", record.level(), string));
+                    Ok(())
+                })
+                .formatter(Box::new(NoModFormatter::new()))
+                .build()
+                .unwrap()
+        };
+
+        sink.log(&Record::new(Level::Info, "hello")).unwrap();
+        assert_eq!(*formatted.lock_expect(), ["info hello"]);
+    }
+
+    #[test]
+    fn error() {
+        let sink = CallbackSink::builder()
+            .callback(|_| Err(Error::__ForInternalTestsUseOnly(42)))
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            sink.log(&Record::new(Level::Info, "hello")),
+            Err(Error::__ForInternalTestsUseOnly(42))
+        ));
+    }
+}
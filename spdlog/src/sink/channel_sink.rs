@@ -0,0 +1,369 @@
+use std::{
+    marker::PhantomData,
+    sync::mpsc::{RecvError, RecvTimeoutError, TryRecvError},
+    time::Duration,
+};
+
+use crossbeam::channel::{self as mpmc, Receiver, Sender, TrySendError};
+
+use crate::{
+    error::{SendToChannelError, SendToChannelErrorDropped},
+    formatter::{Formatter, FormatterContext},
+    sink::{helper, OverflowPolicy, Sink},
+    Error, Record, RecordOwned, Result, StdResult, StringBuf,
+};
+
+mod sealed {
+    use crate::{formatter::Formatter, Record, Result};
+
+    pub trait Sealed: Sized {
+        fn from_record(record: &Record, formatter: &dyn Formatter) -> Result<Self>;
+    }
+}
+
+/// Types of items that [`ChannelSink`] can send.
+///
+/// It's implemented for:
+///
+///  - [`RecordOwned`], the record itself, the formatter of the sink is not
+///    used.
+///
+///  - [`String`], the record formatted by the formatter of the sink.
+///
+/// This trait is sealed and cannot be implemented outside this crate.
+pub trait ChannelItem: sealed::Sealed + Send + 'static {}
+
+impl sealed::Sealed for RecordOwned {
+    fn from_record(record: &Record, _: &dyn Formatter) -> Result<Self> {
+        Ok(record.to_owned())
+    }
+}
+
+impl ChannelItem for RecordOwned {}
+
+impl sealed::Sealed for String {
+    fn from_record(record: &Record, formatter: &dyn Formatter) -> Result<Self> {
+        let mut string_buf = StringBuf::new();
+        let mut ctx = FormatterContext::new();
+        formatter.format(record, &mut string_buf, &mut ctx)?;
+        Ok(string_buf.to_string())
+    }
+}
+
+impl ChannelItem for String {}
+
+/// A sink that sends records into a channel.
+///
+/// This allows consuming records programmatically, e.g. displaying logs in the
+/// UI of an application. The receiving end, a [`ChannelReceiver`], is returned
+/// when building the sink, it can be cloned and moved to other threads.
+///
+/// By default, [`RecordOwned`]s are sent. Calling
+/// [`ChannelSinkBuilder::formatted`] makes the sink send formatted `String`s
+/// instead.
+///
+/// If the channel is bounded and full, the incoming record is handled
+/// according to the [overflow policy]. If all receivers are dropped,
+/// [`Error::SendToChannel`] is returned.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use spdlog::{prelude::*, sink::ChannelSink};
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// let (sink, receiver) = ChannelSink::builder().capacity(Some(1024)).build()?;
+/// let logger = Logger::builder().sink(Arc::new(sink)).build()?;
+///
+/// info!(logger: logger, "hello, UI");
+///
+/// let record = receiver.recv().unwrap();
+/// assert_eq!(record.level(), Level::Info);
+/// assert_eq!(record.payload(), "hello, UI");
+/// # Ok(()) }
+/// ```
+///
+/// [overflow policy]: ChannelSinkBuilder::overflow_policy
+pub struct ChannelSink<T: ChannelItem = RecordOwned> {
+    common_impl: helper::CommonImpl,
+    sender: Sender<T>,
+    overflow_policy: OverflowPolicy,
+}
+
+impl ChannelSink {
+    /// Gets a builder of `ChannelSink` with default parameters:
+    ///
+    /// | Parameter         | Default Value           |
+    /// |-------------------|-------------------------|
+    /// | [level_filter]    | `All`                   |
+    /// | [formatter]       | `FullFormatter`         |
+    /// | [error_handler]   | [default error handler] |
+    /// |                   |                         |
+    /// | [formatted]       | `false`                 |
+    /// | [capacity]        | `None` (unbounded)      |
+    /// | [overflow_policy] | `Block`                 |
+    ///
+    /// [level_filter]: ChannelSinkBuilder::level_filter
+    /// [formatter]: ChannelSinkBuilder::formatter
+    /// [error_handler]: ChannelSinkBuilder::error_handler
+    /// [default error handler]: error/index.html#default-error-handler
+    /// [formatted]: ChannelSinkBuilder::formatted
+    /// [capacity]: ChannelSinkBuilder::capacity
+    /// [overflow_policy]: ChannelSinkBuilder::overflow_policy
+    #[must_use]
+    pub fn builder() -> ChannelSinkBuilder<RecordOwned> {
+        ChannelSinkBuilder {
+            common_builder_impl: helper::CommonBuilderImpl::new(),
+            capacity: None,
+            overflow_policy: OverflowPolicy::Block,
+            _item: PhantomData,
+        }
+    }
+}
+
+impl<T: ChannelItem> Sink for ChannelSink<T> {
+    fn log(&self, record: &Record) -> Result<()> {
+        let item = T::from_record(record, &**self.common_impl.formatter.read())?;
+
+        let error = match self.overflow_policy {
+            OverflowPolicy::Block => match self.sender.send(item) {
+                Ok(()) => return Ok(()),
+                Err(_) => SendToChannelError::Disconnected,
+            },
+            OverflowPolicy::DropIncoming => match self.sender.try_send(item) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Full(_)) => SendToChannelError::Full,
+                Err(TrySendError::Disconnected(_)) => SendToChannelError::Disconnected,
+            },
+        };
+        Err(Error::SendToChannel(
+            error,
+            SendToChannelErrorDropped::Record(Box::new(record.to_owned())),
+        ))
+    }
+
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    helper::common_impl!(@Sink: common_impl);
+}
+
+/// The receiving end of the channel of a [`ChannelSink`].
+///
+/// It can be cloned, each item is received by only one of the clones. The
+/// channel is disconnected when the sink is dropped, or when all receivers are
+/// dropped.
+pub struct ChannelReceiver<T> {
+    receiver: Receiver<T>,
+}
+
+impl<T> ChannelReceiver<T> {
+    /// Blocks until an item is received.
+    ///
+    /// # Error
+    ///
+    /// If the channel is empty and disconnected, [`RecvError`] will be
+    /// returned.
+    pub fn recv(&self) -> StdResult<T, RecvError> {
+        self.receiver.recv().map_err(|_| RecvError)
+    }
+
+    /// Receives an item without blocking.
+    ///
+    /// # Error
+    ///
+    /// If the channel is empty, [`TryRecvError::Empty`] will be returned. If
+    /// the channel is empty and disconnected, [`TryRecvError::Disconnected`]
+    /// will be returned.
+    pub fn try_recv(&self) -> StdResult<T, TryRecvError> {
+        self.receiver.try_recv().map_err(|err| match err {
+            mpmc::TryRecvError::Empty => TryRecvError::Empty,
+            mpmc::TryRecvError::Disconnected => TryRecvError::Disconnected,
+        })
+    }
+
+    /// Blocks until an item is received or `timeout` elapses.
+    ///
+    /// # Error
+    ///
+    /// If `timeout` elapses, [`RecvTimeoutError::Timeout`] will be returned.
+    /// If the channel is empty and disconnected,
+    /// [`RecvTimeoutError::Disconnected`] will be returned.
+    pub fn recv_timeout(&self, timeout: Duration) -> StdResult<T, RecvTimeoutError> {
+        self.receiver
+            .recv_timeout(timeout)
+            .map_err(|err| match err {
+                mpmc::RecvTimeoutError::Timeout => RecvTimeoutError::Timeout,
+                mpmc::RecvTimeoutError::Disconnected => RecvTimeoutError::Disconnected,
+            })
+    }
+
+    /// Returns an iterator that blocks waiting for items, it ends when the
+    /// channel is disconnected.
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.receiver.iter()
+    }
+
+    /// Returns an iterator over the items already in the channel, without
+    /// blocking.
+    pub fn try_iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.receiver.try_iter()
+    }
+}
+
+impl<T> Clone for ChannelReceiver<T> {
+    fn clone(&self) -> Self {
+        Self {
+            receiver: self.receiver.clone(),
+        }
+    }
+}
+
+/// #
+#[doc = include_str!("../include/doc/generic-builder-note.md")]
+pub struct ChannelSinkBuilder<T> {
+    common_builder_impl: helper::CommonBuilderImpl,
+    capacity: Option<usize>,
+    overflow_policy: OverflowPolicy,
+    _item: PhantomData<T>,
+}
+
+impl<T: ChannelItem> ChannelSinkBuilder<T> {
+    /// Sends records formatted by the [formatter] as `String`s, instead of
+    /// [`RecordOwned`]s.
+    ///
+    /// This parameter is **optional**.
+    ///
+    /// [formatter]: ChannelSinkBuilder::formatter
+    #[must_use]
+    pub fn formatted(self) -> ChannelSinkBuilder<String> {
+        ChannelSinkBuilder {
+            common_builder_impl: self.common_builder_impl,
+            capacity: self.capacity,
+            overflow_policy: self.overflow_policy,
+            _item: PhantomData,
+        }
+    }
+
+    /// Specifies the capacity of the channel.
+    ///
+    /// `None` means the channel is unbounded.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn capacity(mut self, capacity: Option<usize>) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Specifies the overflow policy when the bounded channel is full.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn overflow_policy(mut self, overflow_policy: OverflowPolicy) -> Self {
+        self.overflow_policy = overflow_policy;
+        self
+    }
+
+    helper::common_impl!(@SinkBuilder: common_builder_impl);
+
+    /// Builds a [`ChannelSink`] and the receiving end of its channel.
+    pub fn build(self) -> Result<(ChannelSink<T>, ChannelReceiver<T>)> {
+        let (sender, receiver) = match self.capacity {
+            Some(capacity) => mpmc::bounded(capacity),
+            None => mpmc::unbounded(),
+        };
+
+        let sink = ChannelSink {
+            common_impl: helper::CommonImpl::from_builder(self.common_builder_impl),
+            sender,
+            overflow_policy: self.overflow_policy,
+        };
+        Ok((sink, ChannelReceiver { receiver }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    #[test]
+    fn records() {
+        let (sink, receiver) = ChannelSink::builder().build().unwrap();
+        sink.log(&Record::new(Level::Warn, "a")).unwrap();
+        sink.log(&Record::new(Level::Info, "b")).unwrap();
+
+        let records = receiver.try_iter().collect::<Vec<_>>();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].level(), Level::Warn);
+        assert_eq!(records[0].payload(), "a");
+        assert_eq!(records[1].payload(), "b");
+    }
+
+    #[test]
+    fn formatted() {
+        let (sink, receiver) = ChannelSink::builder()
+            .formatted()
+            .formatter(Box::new(NoModFormatter::new()))
+            .build()
+            .unwrap();
+        sink.log(&Record::new(Level::Info, "hello")).unwrap();
+
+        assert_eq!(receiver.try_recv().unwrap(), "hello");
+    }
+
+    #[test]
+    fn overflow() {
+        let (sink, receiver) = ChannelSink::builder()
+            .capacity(Some(1))
+            .overflow_policy(OverflowPolicy::DropIncoming)
+            .build()
+            .unwrap();
+        sink.log(&Record::new(Level::Info, "kept")).unwrap();
+        assert!(matches!(
+            sink.log(&Record::new(Level::Info, "dropped")),
+            Err(Error::SendToChannel(
+                SendToChannelError::Full,
+                SendToChannelErrorDropped::Record(record)
+            )) if record.payload() == "dropped"
+        ));
+        assert_eq!(receiver.try_recv().unwrap().payload(), "kept");
+
+        drop(receiver);
+        assert!(matches!(
+            sink.log(&Record::new(Level::Info, "disconnected")),
+            Err(Error::SendToChannel(SendToChannelError::Disconnected, _))
+        ));
+    }
+
+    #[test]
+    fn receiver() {
+        let (sink, receiver) = ChannelSink::builder()
+            .formatted()
+            .formatter(Box::new(NoModFormatter::new()))
+            .build()
+            .unwrap();
+        let cloned = receiver.clone();
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_millis(10)),
+            Err(RecvTimeoutError::Timeout)
+        );
+
+        sink.log(&Record::new(Level::Info, "a")).unwrap();
+        sink.log(&Record::new(Level::Info, "b")).unwrap();
+        assert_eq!(cloned.recv().unwrap(), "a");
+
+        drop(sink);
+        assert_eq!(receiver.iter().collect::<Vec<_>>(), ["b"]);
+        assert_eq!(cloned.recv(), Err(RecvError));
+        assert_eq!(
+            cloned.recv_timeout(Duration::from_millis(10)),
+            Err(RecvTimeoutError::Disconnected)
+        );
+    }
+}
@@ -57,6 +57,26 @@ impl CommonImpl {
             None => crate::default_error_handler(from, err),
         }
     }
+
+    // The accessors below are called by `impl_sink_base!(@Sink: ..)`, which is
+    // shared with `SinkBase`.
+
+    #[must_use]
+    pub(crate) fn level_filter(&self) -> LevelFilter {
+        self.level_filter.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_level_filter(&self, level_filter: LevelFilter) {
+        self.level_filter.store(level_filter, Ordering::Relaxed);
+    }
+
+    pub(crate) fn set_formatter(&self, formatter: Box<dyn Formatter>) {
+        *self.formatter.write() = formatter;
+    }
+
+    pub(crate) fn set_error_handler(&self, handler: Option<ErrorHandler>) {
+        self.error_handler.store(handler, Ordering::Relaxed);
+    }
 }
 
 pub(crate) struct CommonBuilderImpl {
@@ -74,17 +94,28 @@ impl CommonBuilderImpl {
             error_handler: None,
         }
     }
+
+    // The setters below are called by `impl_sink_base!(@SinkBuilder: ..)`,
+    // which is shared with `SinkBuilderBase`.
+
+    pub(crate) fn set_level_filter(&mut self, level_filter: LevelFilter) {
+        self.level_filter = level_filter;
+    }
+
+    pub(crate) fn set_formatter(&mut self, formatter: Box<dyn Formatter>) {
+        self.formatter = Some(formatter);
+    }
+
+    pub(crate) fn set_error_handler(&mut self, handler: ErrorHandler) {
+        self.error_handler = Some(handler);
+    }
 }
 
 macro_rules! common_impl {
     // Sink
 
     ( @Sink: $($field:ident).+ ) => {
-        $crate::sink::helper::common_impl!(@SinkCustom {
-            level_filter: $($field).+.level_filter,
-            formatter: $($field).+.formatter,
-            error_handler: $($field).+.error_handler,
-        });
+        $crate::impl_sink_base!(@Sink: $($field).+);
     };
     ( @SinkCustom {
         level_filter: $($level_filter:ident).+,
@@ -121,9 +152,7 @@ macro_rules! common_impl {
     // SinkBuiler
 
     ( @SinkBuilder: $($field:ident).+ ) => {
-        $crate::sink::helper::common_impl!(@SinkBuilderCustomInner@level_filter: $($field).+.level_filter);
-        $crate::sink::helper::common_impl!(@SinkBuilderCustomInner@formatter: $($field).+.formatter);
-        $crate::sink::helper::common_impl!(@SinkBuilderCustomInner@error_handler: $($field).+.error_handler);
+        $crate::impl_sink_base!(@SinkBuilder: $($field).+);
     };
     ( @SinkBuilderCustom {
         level_filter: $($level_filter:ident).+,
@@ -0,0 +1,789 @@
+//! Provides an HTTP sink.
+
+use std::{
+    convert::Infallible,
+    io::{self, BufRead, BufReader, Write},
+    sync::mpsc::{self, RecvTimeoutError, SyncSender, TrySendError},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    error::{HttpError, InvalidArgumentError},
+    formatter::FormatterContext,
+    sink::{helper, reconnect::Backoff, tcp_sink, Sink},
+    sync::*,
+    Error, Record, Result, StdResult, StringBuf,
+};
+
+enum Message {
+    Record(Vec<u8>),
+    Flush(SyncSender<()>),
+    Terminate,
+}
+
+/// A sink that sends records to an HTTP endpoint in batches.
+///
+/// Formatted records are put into a bounded in-memory queue, and a dedicated
+/// worker thread collects them into batches and POSTs each batch as the body of
+/// a request, with records concatenated in order. A batch is sent when it
+/// reaches the batch size, when the batch timeout has elapsed since its first
+/// record, or when the sink is flushed or dropped. Pairing it with
+/// [`JsonFormatter`] produces JSON lines bodies accepted by most log
+/// collectors.
+///
+/// [`Sink::log`] never blocks on the network. If the queue is full, the record
+/// is dropped and [`HttpError::QueueFull`] is returned. [`Sink::flush`] blocks
+/// until all queued records are sent.
+///
+/// Requests failed with an I/O error, a `408`, `429` or `5xx` status are
+/// retried with exponential backoff. If a batch still can't be delivered, it's
+/// dropped and the error is reported to the [error handler] of the sink.
+///
+/// Only plain `http://` URLs are supported. To send logs over TLS, put a local
+/// forwarding proxy or collector agent in front of the remote endpoint.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::{sync::Arc, time::Duration};
+///
+/// use spdlog::{prelude::*, sink::HttpSink};
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// let sink = HttpSink::builder()
+///     .url("http://127.0.0.1:8080/ingest")
+///     .header("Content-Type", "application/x-ndjson")
+///     .gzip(true)
+///     .batch_size(500)
+///     .batch_timeout(Duration::from_secs(2))
+///     .build()?;
+/// let logger = Logger::builder().sink(Arc::new(sink)).build()?;
+///
+/// info!(logger: logger, "hello, collector");
+/// # Ok(()) }
+/// ```
+///
+/// [`JsonFormatter`]: crate::formatter::JsonFormatter
+/// [error handler]: Sink::set_error_handler
+pub struct HttpSink {
+    common_impl: Arc<helper::CommonImpl>,
+    sender: SyncSender<Message>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl HttpSink {
+    /// Gets a builder of `HttpSink` with default parameters:
+    ///
+    /// | Parameter        | Default Value           |
+    /// |------------------|-------------------------|
+    /// | [level_filter]   | `All`                   |
+    /// | [formatter]      | `FullFormatter`         |
+    /// | [error_handler]  | [default error handler] |
+    /// |                  |                         |
+    /// | [url]            | *must be specified*     |
+    /// | [header]         | *empty*                 |
+    /// | [gzip]           | `false`                 |
+    /// | [batch_size]     | `100`                   |
+    /// | [batch_timeout]  | `1s`                    |
+    /// | [max_retries]    | `3`                     |
+    /// | [retry_backoff]  | `100ms`, `30s`          |
+    /// | [queue_capacity] | `8192`                  |
+    /// | [timeout]        | `10s`                   |
+    ///
+    /// [level_filter]: HttpSinkBuilder::level_filter
+    /// [formatter]: HttpSinkBuilder::formatter
+    /// [error_handler]: HttpSinkBuilder::error_handler
+    /// [default error handler]: error/index.html#default-error-handler
+    /// [url]: HttpSinkBuilder::url
+    /// [header]: HttpSinkBuilder::header
+    /// [gzip]: HttpSinkBuilder::gzip
+    /// [batch_size]: HttpSinkBuilder::batch_size
+    /// [batch_timeout]: HttpSinkBuilder::batch_timeout
+    /// [max_retries]: HttpSinkBuilder::max_retries
+    /// [retry_backoff]: HttpSinkBuilder::retry_backoff
+    /// [queue_capacity]: HttpSinkBuilder::queue_capacity
+    /// [timeout]: HttpSinkBuilder::timeout
+    #[must_use]
+    pub fn builder() -> HttpSinkBuilder<()> {
+        HttpSinkBuilder {
+            common_builder_impl: helper::CommonBuilderImpl::new(),
+            url: (),
+            headers: vec![],
+            gzip: false,
+            batch_size: 100,
+            batch_timeout: Duration::from_secs(1),
+            max_retries: 3,
+            retry_backoff: Backoff::default(),
+            queue_capacity: 8192,
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Sink for HttpSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        let mut string_buf = StringBuf::new();
+        let mut ctx = FormatterContext::new();
+        self.common_impl
+            .formatter
+            .read()
+            .format(record, &mut string_buf, &mut ctx)?;
+
+        self.sender
+            .try_send(Message::Record(string_buf.as_bytes().to_vec()))
+            .map_err(|err| match err {
+                TrySendError::Full(_) => Error::Http(HttpError::QueueFull),
+                TrySendError::Disconnected(_) => Error::Http(HttpError::WorkerStopped),
+            })
+    }
+
+    fn flush(&self) -> Result<()> {
+        let (ack_sender, ack_receiver) = mpsc::sync_channel(1);
+        self.sender
+            .send(Message::Flush(ack_sender))
+            .map_err(|_| Error::Http(HttpError::WorkerStopped))?;
+        ack_receiver
+            .recv()
+            .map_err(|_| Error::Http(HttpError::WorkerStopped))
+    }
+
+    helper::common_impl!(@Sink: common_impl);
+}
+
+impl Drop for HttpSink {
+    fn drop(&mut self) {
+        // Sends the remaining records and waits for the worker to finish.
+        if self.sender.send(Message::Terminate).is_ok() {
+            if let Some(worker) = self.worker.take() {
+                let _ = worker.join();
+            }
+        }
+    }
+}
+
+struct Endpoint {
+    // `host:port`
+    addr: String,
+    host: String,
+    path: String,
+}
+
+impl Endpoint {
+    fn parse(url: &str) -> StdResult<Self, HttpError> {
+        let invalid = |message: &str| HttpError::InvalidUrl(format!("'{}' {}", url, message));
+
+        let rest = match url.find("://") {
+            Some(index) if url[..index].eq_ignore_ascii_case("http") => &url[index + 3..],
+            Some(_) => return Err(invalid("has an unsupported scheme, only http is supported")),
+            None => return Err(invalid("has no scheme")),
+        };
+        let (authority, path) = match rest.find(['/', '?']) {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, "/"),
+        };
+        if authority.is_empty() || authority.contains('@') {
+            return Err(invalid("has an invalid authority"));
+        }
+
+        // The port is after the last `:`, unless it's inside an IPv6 literal.
+        let has_port = match authority.rfind(':') {
+            Some(index) => !authority[index..].contains(']'),
+            None => false,
+        };
+        let addr = if has_port {
+            let port = &authority[authority.rfind(':').unwrap() + 1..];
+            if port.parse::<u16>().is_err() {
+                return Err(invalid("has an invalid port"));
+            }
+            authority.to_string()
+        } else {
+            format!("{}:80", authority)
+        };
+
+        Ok(Self {
+            addr,
+            host: authority.to_string(),
+            path: if path.starts_with('?') {
+                format!("/{}", path)
+            } else {
+                path.to_string()
+            },
+        })
+    }
+}
+
+// Headers written by `Client::post` itself.
+const RESERVED_HEADERS: [&str; 4] = ["Host", "Content-Length", "Connection", "Content-Encoding"];
+
+struct Client {
+    endpoint: Endpoint,
+    headers: Vec<(String, String)>,
+    gzip: bool,
+    timeout: Duration,
+}
+
+impl Client {
+    fn post(&self, body: &[u8]) -> StdResult<(), HttpError> {
+        let mut stream = tcp_sink::resolve(&self.endpoint.addr)
+            .and_then(|addrs| tcp_sink::connect_to(&addrs, Some(self.timeout)))
+            .map_err(HttpError::Request)?;
+        stream
+            .set_read_timeout(Some(self.timeout))
+            .and_then(|_| stream.set_write_timeout(Some(self.timeout)))
+            .map_err(HttpError::Request)?;
+
+        let mut head = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.endpoint.path,
+            self.endpoint.host,
+            body.len()
+        );
+        if self.gzip {
+            head.push_str("Content-Encoding: gzip\r\n");
+        }
+        for (name, value) in &self.headers {
+            head.push_str(name);
+            head.push_str(": ");
+            head.push_str(value);
+            head.push_str("\r\n");
+        }
+        head.push_str("\r\n");
+
+        stream
+            .write_all(head.as_bytes())
+            .and_then(|_| stream.write_all(body))
+            .and_then(|_| stream.flush())
+            .map_err(HttpError::Request)?;
+
+        let mut status_line = String::new();
+        BufReader::new(stream)
+            .read_line(&mut status_line)
+            .map_err(HttpError::Request)?;
+        let (status, reason) = parse_status_line(&status_line)?;
+        if (200..300).contains(&status) {
+            Ok(())
+        } else {
+            Err(HttpError::Status { status, reason })
+        }
+    }
+}
+
+fn parse_status_line(line: &str) -> StdResult<(u16, String), HttpError> {
+    let mut parts = line.trim_end().splitn(3, ' ');
+    let status = match (parts.next(), parts.next()) {
+        (Some(version), Some(status)) if version.starts_with("HTTP/") => status.parse().ok(),
+        _ => None,
+    };
+    match status {
+        Some(status) => Ok((status, parts.next().unwrap_or_default().to_string())),
+        None => Err(HttpError::InvalidResponse(format!(
+            "invalid status line '{}'",
+            line.trim_end()
+        ))),
+    }
+}
+
+#[must_use]
+fn is_retryable(err: &HttpError) -> bool {
+    match err {
+        HttpError::Request(_) => true,
+        HttpError::Status { status, .. } => matches!(status, 408 | 429 | 500..=599),
+        _ => false,
+    }
+}
+
+fn gzip(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = libflate::gzip::Encoder::new(Vec::new())?;
+    encoder.write_all(data)?;
+    encoder.finish().into_result()
+}
+
+struct Worker {
+    common_impl: Arc<helper::CommonImpl>,
+    client: Client,
+    batch_size: usize,
+    batch_timeout: Duration,
+    max_retries: u32,
+    retry_backoff: Backoff,
+}
+
+impl Worker {
+    fn run(self, receiver: mpsc::Receiver<Message>) {
+        let mut batch = vec![];
+        let mut batch_len = 0;
+        let mut deadline = None;
+
+        loop {
+            let message = match deadline {
+                Some(deadline) => {
+                    match receiver.recv_timeout(deadline - Instant::now().min(deadline)) {
+                        Ok(message) => Some(message),
+                        Err(RecvTimeoutError::Timeout) => None,
+                        Err(RecvTimeoutError::Disconnected) => Some(Message::Terminate),
+                    }
+                }
+                None => Some(receiver.recv().unwrap_or(Message::Terminate)),
+            };
+
+            match message {
+                Some(Message::Record(record)) => {
+                    if batch_len == 0 {
+                        deadline = Some(Instant::now() + self.batch_timeout);
+                    }
+                    batch.extend_from_slice(&record);
+                    batch_len += 1;
+                    if batch_len < self.batch_size {
+                        continue;
+                    }
+                    self.send(&batch);
+                }
+                None => self.send(&batch),
+                Some(Message::Flush(ack)) => {
+                    self.send(&batch);
+                    let _ = ack.send(());
+                }
+                Some(Message::Terminate) => {
+                    self.send(&batch);
+                    break;
+                }
+            }
+            batch.clear();
+            batch_len = 0;
+            deadline = None;
+        }
+    }
+
+    fn send(&self, batch: &[u8]) {
+        if batch.is_empty() {
+            return;
+        }
+        if let Err(err) = self.send_with_retry(batch) {
+            self.common_impl
+                .non_returnable_error("HttpSink", Error::Http(err));
+        }
+    }
+
+    fn send_with_retry(&self, batch: &[u8]) -> StdResult<(), HttpError> {
+        let compressed;
+        let body = if self.client.gzip {
+            compressed = gzip(batch).map_err(HttpError::Request)?;
+            &compressed
+        } else {
+            batch
+        };
+
+        let mut delay = self.retry_backoff.initial;
+        let mut retries = 0;
+        loop {
+            match self.client.post(body) {
+                Err(err) if retries < self.max_retries && is_retryable(&err) => {
+                    thread::sleep(delay);
+                    delay = delay.saturating_mul(2).min(self.retry_backoff.max);
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+// --------------------------------------------------
+
+/// #
+#[doc = include_str!("../include/doc/generic-builder-note.md")]
+pub struct HttpSinkBuilder<ArgUrl> {
+    common_builder_impl: helper::CommonBuilderImpl,
+    url: ArgUrl,
+    headers: Vec<(String, String)>,
+    gzip: bool,
+    batch_size: usize,
+    batch_timeout: Duration,
+    max_retries: u32,
+    retry_backoff: Backoff,
+    queue_capacity: usize,
+    timeout: Duration,
+}
+
+impl<ArgUrl> HttpSinkBuilder<ArgUrl> {
+    /// The URL to POST batches to, e.g. `http://127.0.0.1:8080/ingest`.
+    ///
+    /// Only the `http` scheme is supported.
+    ///
+    /// This parameter is **required**.
+    #[must_use]
+    pub fn url<S>(self, url: S) -> HttpSinkBuilder<String>
+    where
+        S: Into<String>,
+    {
+        HttpSinkBuilder {
+            common_builder_impl: self.common_builder_impl,
+            url: url.into(),
+            headers: self.headers,
+            gzip: self.gzip,
+            batch_size: self.batch_size,
+            batch_timeout: self.batch_timeout,
+            max_retries: self.max_retries,
+            retry_backoff: self.retry_backoff,
+            queue_capacity: self.queue_capacity,
+            timeout: self.timeout,
+        }
+    }
+
+    /// Adds a header to every request, e.g. `Content-Type` or
+    /// `Authorization`.
+    ///
+    /// `Host`, `Content-Length`, `Connection` and `Content-Encoding` are set by
+    /// the sink, specifying them makes [`build`] return an error.
+    ///
+    /// This parameter is **optional**, and can be called multiple times.
+    ///
+    /// [`build`]: HttpSinkBuilder::build
+    #[must_use]
+    pub fn header<N, V>(mut self, name: N, value: V) -> Self
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Specifies whether to compress request bodies with gzip.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn gzip(mut self, gzip: bool) -> Self {
+        self.gzip = gzip;
+        self
+    }
+
+    /// Specifies the maximum number of records in a batch.
+    ///
+    /// Values less than `1` are treated as `1`.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn batch_size(mut self, size: usize) -> Self {
+        self.batch_size = size;
+        self
+    }
+
+    /// Specifies the maximum time a record waits in an incomplete batch before
+    /// the batch is sent.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn batch_timeout(mut self, timeout: Duration) -> Self {
+        self.batch_timeout = timeout;
+        self
+    }
+
+    /// Specifies the maximum number of retries for a batch.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Specifies the initial and the maximum delay between retries.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn retry_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.retry_backoff = Backoff { initial, max };
+        self
+    }
+
+    /// Specifies the maximum number of records waiting in the queue.
+    ///
+    /// Values less than `1` are treated as `1`.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn queue_capacity(mut self, capacity: usize) -> Self {
+        self.queue_capacity = capacity;
+        self
+    }
+
+    /// Specifies the timeout of connecting, sending a request and receiving
+    /// the response.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    helper::common_impl!(@SinkBuilder: common_builder_impl);
+}
+
+impl HttpSinkBuilder<()> {
+    #[doc(hidden)]
+    #[deprecated(note = "\n\n\
+        builder compile-time error:\n\
+        - missing required parameter `url`\n\n\
+    ")]
+    pub fn build(self, _: Infallible) {}
+}
+
+impl HttpSinkBuilder<String> {
+    /// Builds a [`HttpSink`], and spawns its worker thread.
+    ///
+    /// # Error
+    ///
+    /// If the URL is invalid, [`Error::Http`] will be returned. If a header is
+    /// invalid or set by the sink itself, [`Error::InvalidArgument`] will be
+    /// returned.
+    pub fn build(self) -> Result<HttpSink> {
+        let endpoint = Endpoint::parse(&self.url).map_err(Error::Http)?;
+        for (name, value) in &self.headers {
+            let valid_name = !name.is_empty()
+                && name
+                    .bytes()
+                    .all(|b| b.is_ascii_graphic() && !b"()<>@,;:\\\"/[]?={}".contains(&b));
+            if !valid_name || value.contains(['\r', '\n']) {
+                return Err(Error::InvalidArgument(InvalidArgumentError::HttpHeader(
+                    format!("invalid header '{}: {}'", name, value),
+                )));
+            }
+            if RESERVED_HEADERS
+                .iter()
+                .any(|reserved| name.eq_ignore_ascii_case(reserved))
+            {
+                return Err(Error::InvalidArgument(InvalidArgumentError::HttpHeader(
+                    format!("header '{}' is set by the sink", name),
+                )));
+            }
+        }
+
+        let common_impl = Arc::new(helper::CommonImpl::from_builder(self.common_builder_impl));
+        let worker = Worker {
+            common_impl: common_impl.clone(),
+            client: Client {
+                endpoint,
+                headers: self.headers,
+                gzip: self.gzip,
+                timeout: self.timeout,
+            },
+            batch_size: self.batch_size.max(1),
+            batch_timeout: self.batch_timeout,
+            max_retries: self.max_retries,
+            retry_backoff: self.retry_backoff,
+        };
+
+        let (sender, receiver) = mpsc::sync_channel(self.queue_capacity.max(1));
+        let worker = thread::spawn(move || worker.run(receiver));
+
+        Ok(HttpSink {
+            common_impl,
+            sender,
+            worker: Some(worker),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Read,
+        net::TcpListener,
+        sync::atomic::{AtomicBool, Ordering},
+    };
+
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    struct Request {
+        head: String,
+        body: Vec<u8>,
+    }
+
+    // Serves a request per status, and returns the received requests.
+    fn serve(statuses: &[&'static str]) -> (String, JoinHandle<Vec<Request>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/ingest?v=1", listener.local_addr().unwrap());
+        let statuses = statuses.to_vec();
+
+        let server = thread::spawn(move || {
+            statuses
+                .into_iter()
+                .map(|status| {
+                    let (stream, _) = listener.accept().unwrap();
+                    let mut reader = BufReader::new(stream);
+                    let mut head = String::new();
+                    loop {
+                        let len = reader.read_line(&mut head).unwrap();
+                        if len <= 2 {
+                            break;
+                        }
+                    }
+                    let content_length = head
+                        .lines()
+                        .find_map(|line| line.strip_prefix("Content-Length: "))
+                        .unwrap()
+                        .parse()
+                        .unwrap();
+                    let mut body = vec![0; content_length];
+                    reader.read_exact(&mut body).unwrap();
+                    write!(
+                        reader.get_mut(),
+                        "HTTP/1.1 {}\r\nContent-Length: 0\r\n\r\n",
+                        status
+                    )
+                    .unwrap();
+                    Request { head, body }
+                })
+                .collect()
+        });
+        (url, server)
+    }
+
+    fn build_logger(builder: HttpSinkBuilder<String>) -> Logger {
+        let sink = builder
+            .formatter(Box::new(NoModFormatter::new()))
+            .retry_backoff(Duration::ZERO, Duration::ZERO)
+            .build()
+            .unwrap();
+        build_test_logger(|b| b.sink(Arc::new(sink)))
+    }
+
+    #[test]
+    fn batch() {
+        let (url, server) = serve(&["200 OK", "204 No Content"]);
+        let logger = build_logger(
+            HttpSink::builder()
+                .url(url)
+                .header("X-Api-Key", "secret")
+                .batch_size(2)
+                .batch_timeout(Duration::from_secs(3600))
+                .error_handler(|err| panic!("{}", err)),
+        );
+
+        info!(logger: logger, "a\n");
+        info!(logger: logger, "b\n");
+        info!(logger: logger, "c\n");
+        logger.flush();
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0]
+            .head
+            .starts_with("POST /ingest?v=1 HTTP/1.1\r\n"));
+        assert!(requests[0].head.contains("\r\nX-Api-Key: secret\r\n"));
+        assert_eq!(requests[0].body, b"a\nb\n");
+        assert_eq!(requests[1].body, b"c\n");
+    }
+
+    #[test]
+    fn batch_timeout() {
+        let (url, server) = serve(&["200 OK"]);
+        let logger = build_logger(
+            HttpSink::builder()
+                .url(url)
+                .batch_timeout(Duration::from_millis(10))
+                .error_handler(|err| panic!("{}", err)),
+        );
+
+        info!(logger: logger, "a\n");
+        // Sent by the timeout without flushing.
+        let requests = server.join().unwrap();
+        assert_eq!(requests[0].body, b"a\n");
+    }
+
+    #[test]
+    fn retry_and_gzip() {
+        let (url, server) = serve(&["503 Service Unavailable", "200 OK"]);
+        let logger = build_logger(
+            HttpSink::builder()
+                .url(url)
+                .gzip(true)
+                .max_retries(1)
+                .error_handler(|err| panic!("{}", err)),
+        );
+
+        info!(logger: logger, "x\n");
+        logger.flush();
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        for request in requests {
+            assert!(request.head.contains("\r\nContent-Encoding: gzip\r\n"));
+            let mut body = String::new();
+            libflate::gzip::Decoder::new(&request.body[..])
+                .unwrap()
+                .read_to_string(&mut body)
+                .unwrap();
+            assert_eq!(body, "x\n");
+        }
+    }
+
+    #[test]
+    fn failure() {
+        static REPORTED: AtomicBool = AtomicBool::new(false);
+
+        let (url, server) = serve(&["400 Bad Request"]);
+        let logger = build_logger(HttpSink::builder().url(url).error_handler(|err| {
+            assert!(matches!(
+                err,
+                Error::Http(HttpError::Status { status: 400, .. })
+            ));
+            REPORTED.store(true, Ordering::SeqCst);
+        }));
+
+        info!(logger: logger, "x\n");
+        logger.flush();
+
+        // Not retried.
+        assert_eq!(server.join().unwrap().len(), 1);
+        assert!(REPORTED.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        for url in [
+            "https://example.com",
+            "example.com",
+            "http://",
+            "http://a:b/",
+        ] {
+            assert!(matches!(
+                HttpSink::builder().url(url).build(),
+                Err(Error::Http(HttpError::InvalidUrl(_)))
+            ));
+        }
+        assert!(matches!(
+            HttpSink::builder()
+                .url("http://127.0.0.1")
+                .header("X-Bad", "a\r\nb")
+                .build(),
+            Err(Error::InvalidArgument(InvalidArgumentError::HttpHeader(_)))
+        ));
+        for name in ["Host", "content-length", "CONNECTION", "Content-Encoding"] {
+            assert!(matches!(
+                HttpSink::builder()
+                    .url("http://127.0.0.1")
+                    .header(name, "x")
+                    .build(),
+                Err(Error::InvalidArgument(InvalidArgumentError::HttpHeader(_)))
+            ));
+        }
+    }
+
+    #[test]
+    fn parse_endpoint() {
+        let endpoint = Endpoint::parse("http://localhost").unwrap();
+        assert_eq!(endpoint.addr, "localhost:80");
+        assert_eq!(endpoint.path, "/");
+
+        let endpoint = Endpoint::parse("HTTP://[::1]:8080/a/b").unwrap();
+        assert_eq!(endpoint.addr, "[::1]:8080");
+        assert_eq!(endpoint.host, "[::1]:8080");
+        assert_eq!(endpoint.path, "/a/b");
+
+        assert_eq!(Endpoint::parse("http://[::1]").unwrap().addr, "[::1]:80");
+    }
+}
@@ -1,64 +1,161 @@
-use std::{io, os::raw::c_int};
+use std::{
+    fs::File,
+    io::{self, Write},
+    mem,
+    os::{
+        raw::{c_char, c_int, c_uint, c_void},
+        unix::{
+            ffi::OsStrExt,
+            io::{AsRawFd, FromRawFd, RawFd},
+            net::UnixDatagram,
+        },
+    },
+    path::{Path, PathBuf},
+    ptr,
+};
 
 use crate::{
+    error::InvalidArgumentError,
     formatter::{FormatterContext, JournaldFormatter},
-    sink::{helper, Sink},
-    Error, Level, Record, Result, StdResult, StringBuf,
+    sink::{
+        helper,
+        syslog_sink::{executable_name, SyslogLevels},
+        Sink,
+    },
+    Error, Record, Result, StdResult, StringBuf,
 };
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
-enum SyslogLevel {
-    _Emerg = 0,
-    _Alert = 1,
-    Crit = 2,
-    Err = 3,
-    Warning = 4,
-    _Notice = 5,
-    Info = 6,
-    Debug = 7,
+#[cfg(not(feature = "libsystemd"))]
+const JOURNAL_SOCKET_PATH: &str = "/run/systemd/journal/socket";
+
+#[cfg(feature = "libsystemd")]
+fn journal_send(args: impl Iterator<Item = impl AsRef<str>>) -> StdResult<(), io::Error> {
+    #[cfg(not(doc))] // https://github.com/rust-lang/rust/issues/97976
+    use libsystemd_sys::{const_iovec, journal as ffi};
+
+    let iovecs: Vec<_> = args.map(|a| unsafe { const_iovec::from_str(a) }).collect();
+    let result = unsafe { ffi::sd_journal_sendv(iovecs.as_ptr(), iovecs.len() as c_int) };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::from_raw_os_error(result))
+    }
+}
+
+// Implements the journal native protocol.
+//
+// https://systemd.io/JOURNAL_NATIVE_PROTOCOL/
+struct NativeJournal {
+    socket: UnixDatagram,
+    path: PathBuf,
 }
 
-#[derive(Clone, Eq, PartialEq, Hash, Debug)]
-struct SyslogLevels([SyslogLevel; Level::count()]);
+impl NativeJournal {
+    fn new(path: PathBuf) -> io::Result<Self> {
+        Ok(Self {
+            socket: UnixDatagram::unbound()?,
+            path,
+        })
+    }
 
-impl SyslogLevels {
-    #[must_use]
-    const fn new() -> Self {
-        Self([
-            SyslogLevel::Crit,    // Critical
-            SyslogLevel::Err,     // Error
-            SyslogLevel::Warning, // Warn
-            SyslogLevel::Info,    // Info
-            SyslogLevel::Debug,   // Debug
-            SyslogLevel::Debug,   // Trace
-        ])
+    fn send(&self, args: impl Iterator<Item = impl AsRef<str>>) -> StdResult<(), io::Error> {
+        let mut data = vec![];
+        for arg in args {
+            let arg = arg.as_ref();
+            let (name, value) = arg.split_once('=').unwrap_or((arg, ""));
+            serialize_field(&mut data, name, value.as_bytes());
+        }
+
+        match self.socket.send_to(&data, &self.path) {
+            Ok(_) => Ok(()),
+            // The entry is too large for a datagram, pass it in a sealed memfd instead.
+            Err(err) if matches!(err.raw_os_error(), Some(libc::EMSGSIZE | libc::ENOBUFS)) => {
+                self.send_memfd(&data)
+            }
+            Err(err) => Err(err),
+        }
     }
 
-    #[must_use]
-    fn level(&self, level: Level) -> SyslogLevel {
-        self.0[level as usize]
+    fn send_memfd(&self, data: &[u8]) -> io::Result<()> {
+        let fd = unsafe {
+            libc::memfd_create(
+                b"spdlog-journald\0".as_ptr() as *const c_char,
+                libc::MFD_ALLOW_SEALING | libc::MFD_CLOEXEC,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut file = unsafe { File::from_raw_fd(fd) };
+        file.write_all(data)?;
+
+        let seals =
+            libc::F_SEAL_SHRINK | libc::F_SEAL_GROW | libc::F_SEAL_WRITE | libc::F_SEAL_SEAL;
+        if unsafe { libc::fcntl(fd, libc::F_ADD_SEALS, seals) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        send_fd(&self.socket, &self.path, fd)
     }
 }
 
-impl Default for SyslogLevels {
-    fn default() -> Self {
-        Self::new()
+fn serialize_field(dest: &mut Vec<u8>, name: &str, value: &[u8]) {
+    dest.extend_from_slice(name.as_bytes());
+    if value.contains(&b'\n') {
+        dest.push(b'\n');
+        dest.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        dest.push(b'=');
     }
+    dest.extend_from_slice(value);
+    dest.push(b'\n');
 }
 
-fn journal_send(args: impl Iterator<Item = impl AsRef<str>>) -> StdResult<(), io::Error> {
-    #[cfg(not(doc))] // https://github.com/rust-lang/rust/issues/97976
-    use libsystemd_sys::{const_iovec, journal as ffi};
+// Sends a file descriptor with an empty payload through `SCM_RIGHTS`.
+fn send_fd(socket: &UnixDatagram, path: &Path, fd: RawFd) -> io::Result<()> {
+    let mut addr: libc::sockaddr_un = unsafe { mem::zeroed() };
+    addr.sun_family = libc::AF_UNIX as libc::sa_family_t;
+    let path = path.as_os_str().as_bytes();
+    if path.len() >= addr.sun_path.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "socket path is too long",
+        ));
+    }
+    for (dest, src) in addr.sun_path.iter_mut().zip(path) {
+        *dest = *src as c_char;
+    }
 
-    let iovecs: Vec<_> = args.map(|a| unsafe { const_iovec::from_str(a) }).collect();
-    let result = unsafe { ffi::sd_journal_sendv(iovecs.as_ptr(), iovecs.len() as c_int) };
-    if result == 0 {
-        Ok(())
+    let space = unsafe { libc::CMSG_SPACE(mem::size_of::<c_int>() as c_uint) } as usize;
+    // `u64` for the alignment of `cmsghdr`.
+    let mut control = vec![0u64; (space + 7) / 8];
+
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_name = &mut addr as *mut libc::sockaddr_un as *mut c_void;
+    msg.msg_namelen = (mem::size_of::<libc::sa_family_t>() + path.len() + 1) as libc::socklen_t;
+    msg.msg_control = control.as_mut_ptr() as *mut c_void;
+    msg.msg_controllen = space as _;
+
+    unsafe {
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        (*cmsg).cmsg_level = libc::SOL_SOCKET;
+        (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(mem::size_of::<c_int>() as c_uint) as _;
+        ptr::write_unaligned(libc::CMSG_DATA(cmsg) as *mut c_int, fd);
+    }
+
+    if unsafe { libc::sendmsg(socket.as_raw_fd(), &msg, libc::MSG_NOSIGNAL) } < 0 {
+        Err(io::Error::last_os_error())
     } else {
-        Err(io::Error::from_raw_os_error(result))
+        Ok(())
     }
 }
 
+enum Backend {
+    #[cfg(feature = "libsystemd")]
+    Libsystemd,
+    Native(NativeJournal),
+}
+
 /// A sink with systemd-journal as the target.
 ///
 /// # Log Level Mapping
@@ -72,9 +169,36 @@ fn journal_send(args: impl Iterator<Item = impl AsRef<str>>) -> StdResult<(), io
 /// | `Debug`    | `debug`   |
 /// | `Trace`    | `debug`   |
 ///
+/// # Fields
+///
+/// Besides the fields listed below, static fields added by
+/// [`JournaldSinkBuilder::field`] are attached to every entry.
+///
+/// | Field               | Value                                          |
+/// |---------------------|------------------------------------------------|
+/// | `MESSAGE`           | The formatted record                           |
+/// | `PRIORITY`          | The mapped log level                           |
+/// | `CODE_FILE`         | The source file name, if available             |
+/// | `CODE_LINE`         | The source line, if available                  |
+/// | `LOGGER`            | [`Record::logger_name`], if available          |
+/// | `TID`               | [`Record::tid`]                                |
+/// | `SYSLOG_IDENTIFIER` | See [`JournaldSinkBuilder::syslog_identifier`] |
+///
+/// # Protocol
+///
+/// By default, entries are sent to journald with the [native protocol] through
+/// the `/run/systemd/journal/socket` datagram socket, which is implemented in
+/// pure Rust and has no additional system dependencies. Entries too large for a
+/// single datagram are passed in a sealed memfd.
+///
+/// If the crate feature `libsystemd` is enabled, `sd_journal_sendv` from
+/// `libsystemd` is used instead, unless a socket path is specified by
+/// [`JournaldSinkBuilder::socket_path`].
+///
 /// # Note
 ///
-/// It requires an additional system dependency `libsystemd`.
+/// The `libsystemd` feature requires an additional system dependency
+/// `libsystemd`.
 ///
 /// ## Install on Ubuntu / Debian
 ///
@@ -87,8 +211,12 @@ fn journal_send(args: impl Iterator<Item = impl AsRef<str>>) -> StdResult<(), io
 /// ```bash
 /// pacman -S systemd
 /// ```
+///
+/// [native protocol]: https://systemd.io/JOURNAL_NATIVE_PROTOCOL/
 pub struct JournaldSink {
     common_impl: helper::CommonImpl,
+    backend: Backend,
+    static_fields: Vec<String>,
 }
 
 impl JournaldSink {
@@ -96,20 +224,30 @@ impl JournaldSink {
 
     /// Gets a builder of `JournaldSink` with default parameters:
     ///
-    /// | Parameter       | Default Value           |
-    /// |-----------------|-------------------------|
-    /// | [level_filter]  | `All`                   |
-    /// | [formatter]     | `JournaldFormatter`     |
-    /// | [error_handler] | [default error handler] |
+    /// | Parameter           | Default Value                   |
+    /// |---------------------|---------------------------------|
+    /// | [level_filter]      | `All`                           |
+    /// | [formatter]         | `JournaldFormatter`             |
+    /// | [error_handler]     | [default error handler]         |
+    /// |                     |                                 |
+    /// | [socket_path]       | `None`                          |
+    /// | [syslog_identifier] | the file name of the executable |
+    /// | [field]             | *empty*                         |
     ///
     /// [level_filter]: JournaldSinkBuilder::level_filter
     /// [formatter]: JournaldSinkBuilder::formatter
     /// [error_handler]: JournaldSinkBuilder::error_handler
     /// [default error handler]: error/index.html#default-error-handler
+    /// [socket_path]: JournaldSinkBuilder::socket_path
+    /// [syslog_identifier]: JournaldSinkBuilder::syslog_identifier
+    /// [field]: JournaldSinkBuilder::field
     #[must_use]
     pub fn builder() -> JournaldSinkBuilder {
         JournaldSinkBuilder {
             common_builder_impl: helper::CommonBuilderImpl::new(),
+            socket_path: None,
+            syslog_identifier: None,
+            fields: vec![],
         }
     }
 }
@@ -123,7 +261,7 @@ impl Sink for JournaldSink {
             .read()
             .format(record, &mut string_buf, &mut ctx)?;
 
-        let kvs = [
+        let mut kvs = vec![
             format!("MESSAGE={}", string_buf),
             format!(
                 "PRIORITY={}",
@@ -131,15 +269,22 @@ impl Sink for JournaldSink {
             ),
         ];
 
-        let srcloc_kvs = match record.source_location() {
-            Some(srcloc) => [
-                Some(format!("CODE_FILE={}", srcloc.file_name())),
-                Some(format!("CODE_LINE={}", srcloc.line())),
-            ],
-            None => [None, None],
-        };
+        if let Some(srcloc) = record.source_location() {
+            kvs.push(format!("CODE_FILE={}", srcloc.file_name()));
+            kvs.push(format!("CODE_LINE={}", srcloc.line()));
+        }
+        if let Some(logger_name) = record.logger_name() {
+            kvs.push(format!("LOGGER={}", logger_name));
+        }
+        kvs.push(format!("TID={}", record.tid()));
 
-        journal_send(kvs.iter().chain(srcloc_kvs.iter().flatten())).map_err(Error::WriteRecord)
+        let args = kvs.iter().chain(self.static_fields.iter());
+        match &self.backend {
+            #[cfg(feature = "libsystemd")]
+            Backend::Libsystemd => journal_send(args),
+            Backend::Native(journal) => journal.send(args),
+        }
+        .map_err(Error::WriteRecord)
     }
 
     fn flush(&self) -> Result<()> {
@@ -152,19 +297,290 @@ impl Sink for JournaldSink {
 #[allow(missing_docs)]
 pub struct JournaldSinkBuilder {
     common_builder_impl: helper::CommonBuilderImpl,
+    socket_path: Option<PathBuf>,
+    syslog_identifier: Option<String>,
+    fields: Vec<(String, String)>,
 }
 
 impl JournaldSinkBuilder {
+    /// Specifies the path of the socket to send entries to with the native
+    /// protocol.
+    ///
+    /// If it's `None`, `/run/systemd/journal/socket` is used, or
+    /// `sd_journal_sendv` if the crate feature `libsystemd` is enabled.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn socket_path<P>(mut self, path: Option<P>) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.socket_path = path.map(Into::into);
+        self
+    }
+
+    /// Specifies the value of the `SYSLOG_IDENTIFIER` field.
+    ///
+    /// If it's not specified, the file name of the executable is used.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn syslog_identifier<S>(mut self, identifier: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.syslog_identifier = Some(identifier.into());
+        self
+    }
+
+    /// Adds a static field, which is attached to every entry.
+    ///
+    /// A field name must consist of uppercase ASCII letters, digits and
+    /// underscores, must not start with an underscore or a digit, and must be
+    /// at most 64 characters long. Fields set by the sink itself (see the
+    /// fields table of [`JournaldSink`]) cannot be added.
+    ///
+    /// This parameter is **optional**, and can be called multiple times.
+    #[must_use]
+    pub fn field<N, V>(mut self, name: N, value: V) -> Self
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        self.fields.push((name.into(), value.into()));
+        self
+    }
+
     helper::common_impl!(@SinkBuilder: common_builder_impl);
 
     /// Builds a [`JournaldSink`].
+    ///
+    /// # Error
+    ///
+    /// If a field name is invalid or reserved, [`Error::InvalidArgument`] will
+    /// be returned. If an error occurs creating the local socket,
+    /// [`Error::BindSocket`] will be returned.
     pub fn build(self) -> Result<JournaldSink> {
+        let mut static_fields = Vec::with_capacity(self.fields.len() + 1);
+        if let Some(identifier) = self.syslog_identifier.or_else(executable_name) {
+            static_fields.push(format!("SYSLOG_IDENTIFIER={}", identifier));
+        }
+        for (name, value) in self.fields {
+            check_field_name(&name)?;
+            static_fields.push(format!("{}={}", name, value));
+        }
+
+        let backend = match self.socket_path {
+            Some(path) => Backend::Native(NativeJournal::new(path).map_err(Error::BindSocket)?),
+            #[cfg(feature = "libsystemd")]
+            None => Backend::Libsystemd,
+            #[cfg(not(feature = "libsystemd"))]
+            None => Backend::Native(
+                NativeJournal::new(JOURNAL_SOCKET_PATH.into()).map_err(Error::BindSocket)?,
+            ),
+        };
+
         let sink = JournaldSink {
             common_impl: helper::CommonImpl::from_builder_with_formatter(
                 self.common_builder_impl,
                 || Box::new(JournaldFormatter::new()),
             ),
+            backend,
+            static_fields,
         };
         Ok(sink)
     }
 }
+
+// Fields set by the sink, see the fields table of `JournaldSink`.
+const RESERVED_FIELDS: [&str; 7] = [
+    "MESSAGE",
+    "PRIORITY",
+    "CODE_FILE",
+    "CODE_LINE",
+    "LOGGER",
+    "TID",
+    "SYSLOG_IDENTIFIER",
+];
+
+fn check_field_name(name: &str) -> Result<()> {
+    if RESERVED_FIELDS.contains(&name) {
+        return Err(Error::InvalidArgument(InvalidArgumentError::JournaldField(
+            format!("field '{}' is set by the sink", name),
+        )));
+    }
+
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && !name.starts_with(|ch: char| ch == '_' || ch.is_ascii_digit())
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_uppercase() || ch.is_ascii_digit() || ch == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidArgument(InvalidArgumentError::JournaldField(
+            format!("invalid field name '{}'", name),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Seek, SeekFrom};
+
+    use super::*;
+    use crate::{test_utils::*, Level};
+
+    fn parse_fields(mut data: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let mut fields = vec![];
+        while !data.is_empty() {
+            let end = data.iter().position(|&b| b == b'=' || b == b'\n').unwrap();
+            let name = String::from_utf8(data[..end].to_vec()).unwrap();
+            let value;
+            if data[end] == b'=' {
+                let len = data[end..].iter().position(|&b| b == b'\n').unwrap() - 1;
+                value = data[end + 1..end + 1 + len].to_vec();
+                data = &data[end + 1 + len + 1..];
+            } else {
+                let mut len = [0; 8];
+                len.copy_from_slice(&data[end + 1..end + 9]);
+                let len = u64::from_le_bytes(len) as usize;
+                value = data[end + 9..end + 9 + len].to_vec();
+                data = &data[end + 9 + len + 1..];
+            }
+            fields.push((name, value));
+        }
+        fields
+    }
+
+    // Receives a datagram, or the content of the memfd passed with it.
+    fn recv_entry(socket: &UnixDatagram) -> Vec<u8> {
+        let mut buf = vec![0u8; 4096];
+        let mut iov = libc::iovec {
+            iov_base: buf.as_mut_ptr() as *mut c_void,
+            iov_len: buf.len(),
+        };
+        let mut control = [0u64; 8];
+        let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = control.as_mut_ptr() as *mut c_void;
+        msg.msg_controllen = mem::size_of_val(&control) as _;
+
+        let len = unsafe { libc::recvmsg(socket.as_raw_fd(), &mut msg, 0) };
+        assert!(len >= 0, "{}", io::Error::last_os_error());
+
+        let cmsg = unsafe { libc::CMSG_FIRSTHDR(&msg) };
+        if cmsg.is_null() {
+            buf.truncate(len as usize);
+            return buf;
+        }
+        assert_eq!(len, 0);
+        let fd = unsafe { ptr::read_unaligned(libc::CMSG_DATA(cmsg) as *const c_int) };
+        let mut file = unsafe { File::from_raw_fd(fd) };
+        // The file offset is shared with the sender.
+        file.seek(SeekFrom::Start(0)).unwrap();
+        let mut data = vec![];
+        file.read_to_end(&mut data).unwrap();
+        data
+    }
+
+    fn bind(name: &str) -> (UnixDatagram, PathBuf) {
+        let path = TEST_LOGS_PATH.join(name);
+        let _ = std::fs::remove_file(&path);
+        (UnixDatagram::bind(&path).unwrap(), path)
+    }
+
+    #[test]
+    fn native_protocol() {
+        let (socket, path) = bind("journald_sink.sock");
+        let sink = JournaldSink::builder()
+            .socket_path(Some(&path))
+            .syslog_identifier("my-app")
+            .build()
+            .unwrap();
+
+        let record = Record::new(Level::Warn, "hello");
+        sink.log(&record).unwrap();
+        let fields = parse_fields(&recv_entry(&socket));
+        assert_eq!(
+            fields,
+            [
+                (
+                    "MESSAGE".to_string(),
+                    format!("[warn] hello{}", crate::__EOL).into_bytes()
+                ),
+                ("PRIORITY".to_string(), b"4".to_vec()),
+                ("TID".to_string(), record.tid().to_string().into_bytes()),
+                ("SYSLOG_IDENTIFIER".to_string(), b"my-app".to_vec()),
+            ]
+        );
+
+        // Larger than the maximum datagram size, passed in a memfd.
+        let large = "x".repeat(1024 * 1024);
+        sink.log(&Record::new(Level::Info, &large)).unwrap();
+        let fields = parse_fields(&recv_entry(&socket));
+        assert_eq!(fields.len(), 4);
+        assert!(fields[0].1.starts_with(b"[info] xxx"));
+        assert_eq!(
+            fields[0].1.len(),
+            "[info] ".len() + large.len() + crate::__EOL.len()
+        );
+    }
+
+    #[test]
+    fn fields() {
+        let (socket, path) = bind("journald_sink_fields.sock");
+        let sink = JournaldSink::builder()
+            .socket_path(Some(&path))
+            .field("APP_VERSION", "1.0")
+            .field("MULTI_LINE", "a\nb")
+            .build()
+            .unwrap();
+
+        let record = Record::builder(Level::Info, "hello")
+            .logger_name("net")
+            .build();
+        sink.log(&record).unwrap();
+        let fields = parse_fields(&recv_entry(&socket));
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, v)| String::from_utf8(v.clone()).unwrap())
+        };
+        assert_eq!(field("LOGGER").as_deref(), Some("net"));
+        assert_eq!(field("APP_VERSION").as_deref(), Some("1.0"));
+        assert_eq!(field("MULTI_LINE").as_deref(), Some("a\nb"));
+        assert_eq!(field("SYSLOG_IDENTIFIER"), executable_name());
+
+        for name in [
+            "",
+            "_TRUSTED",
+            "1ST",
+            "lower",
+            "WITH-DASH",
+            "MESSAGE",
+            "PRIORITY",
+            "TID",
+            "LOGGER",
+            "SYSLOG_IDENTIFIER",
+        ] {
+            assert!(matches!(
+                JournaldSink::builder().field(name, "").build(),
+                Err(Error::InvalidArgument(InvalidArgumentError::JournaldField(
+                    _
+                )))
+            ));
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        let mut data = vec![];
+        serialize_field(&mut data, "A", b"1");
+        serialize_field(&mut data, "B", b"x\ny");
+        assert_eq!(data, b"A=1\nB\n\x03\0\0\0\0\0\0\0x\ny\n");
+    }
+}
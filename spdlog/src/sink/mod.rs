@@ -21,31 +21,50 @@
 
 #[cfg(feature = "multi-thread")]
 pub(crate) mod async_sink;
+mod callback_sink;
+#[cfg(feature = "multi-thread")]
+mod channel_sink;
 mod dedup_sink;
 mod file_sink;
 mod helper;
-#[cfg(any(
-    all(target_os = "linux", feature = "native", feature = "libsystemd"),
-    all(doc, not(doctest))
-))]
+#[cfg(feature = "http")]
+mod http_sink;
+#[cfg(any(all(target_os = "linux", feature = "native"), all(doc, not(doctest))))]
 mod journald_sink;
+mod reconnect;
+mod ring_buffer_sink;
 mod rotating_file_sink;
+mod sink_base;
 mod std_stream_sink;
+mod syslog_sink;
+mod tcp_sink;
+mod udp_sink;
+#[cfg(unix)]
+mod unix_socket_sink;
 #[cfg(any(all(windows, feature = "native"), all(doc, not(doctest))))]
 mod win_debug_sink;
 mod write_sink;
 
 #[cfg(feature = "multi-thread")]
 pub use async_sink::*;
+pub use callback_sink::*;
+#[cfg(feature = "multi-thread")]
+pub use channel_sink::*;
 pub use dedup_sink::*;
 pub use file_sink::*;
-#[cfg(any(
-    all(target_os = "linux", feature = "native", feature = "libsystemd"),
-    all(doc, not(doctest))
-))]
+#[cfg(feature = "http")]
+pub use http_sink::*;
+#[cfg(any(all(target_os = "linux", feature = "native"), all(doc, not(doctest))))]
 pub use journald_sink::*;
+pub use ring_buffer_sink::*;
 pub use rotating_file_sink::*;
+pub use sink_base::*;
 pub use std_stream_sink::*;
+pub use syslog_sink::*;
+pub use tcp_sink::*;
+pub use udp_sink::*;
+#[cfg(unix)]
+pub use unix_socket_sink::*;
 #[cfg(any(all(windows, feature = "native"), all(doc, not(doctest))))]
 pub use win_debug_sink::*;
 pub use write_sink::*;
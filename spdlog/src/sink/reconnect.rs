@@ -0,0 +1,319 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+use crate::{Error, Result};
+
+/// Exponential backoff between reconnection attempts.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Backoff {
+    pub(crate) initial: Duration,
+    pub(crate) max: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(30),
+        }
+    }
+}
+
+/// A connection-oriented writer that reconnects with exponential backoff when
+/// the connection drops, and buffers a bounded number of messages while it's
+/// disconnected.
+///
+/// Reconnection is only attempted when writing or flushing, no background
+/// thread is involved.
+///
+/// Only messages failed by a lost connection are buffered and retried. Other
+/// errors, e.g. a datagram exceeding the maximum size, would fail again for the
+/// same message, so the message is dropped and the error is returned.
+pub(crate) struct ReconnectingWriter<W> {
+    connect: Box<dyn Fn() -> Result<W> + Send>,
+    conn: Option<W>,
+    backoff: Backoff,
+    // The delay after the last failed attempt before the next one.
+    delay: Duration,
+    last_failure: Option<Instant>,
+    pending: VecDeque<Vec<u8>>,
+    capacity: usize,
+}
+
+impl<W: Write> ReconnectingWriter<W> {
+    #[must_use]
+    pub(crate) fn new(
+        connect: impl Fn() -> Result<W> + Send + 'static,
+        backoff: Backoff,
+        capacity: usize,
+    ) -> Self {
+        Self {
+            connect: Box::new(connect),
+            conn: None,
+            backoff,
+            delay: backoff.initial,
+            last_failure: None,
+            pending: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Writes a message.
+    ///
+    /// The message is buffered if it can't be written now. Returned errors are
+    /// the ones that occurred during connecting and writing, the caller
+    /// usually reports them to the sink error handler.
+    pub(crate) fn write(&mut self, msg: &[u8]) -> Result<()> {
+        let mut result = self.reconnect_and_drain();
+
+        match &mut self.conn {
+            Some(conn) if self.pending.is_empty() => {
+                if let Err(err) = conn.write_all(msg) {
+                    let lost = is_connection_lost(&err);
+                    result = Error::push_err(result, Error::WriteRecord(err));
+                    if lost {
+                        self.disconnect();
+                        result = Error::push_result(result, self.enqueue(msg));
+                    }
+                }
+            }
+            _ => result = Error::push_result(result, self.enqueue(msg)),
+        }
+        result
+    }
+
+    /// Flushes the connection, and tries to write buffered messages.
+    pub(crate) fn flush(&mut self) -> Result<()> {
+        let result = self.reconnect_and_drain();
+        match &mut self.conn {
+            Some(conn) => match conn.flush() {
+                Ok(()) => result,
+                Err(err) => {
+                    self.disconnect();
+                    Error::push_err(result, Error::FlushBuffer(err))
+                }
+            },
+            None => result,
+        }
+    }
+
+    fn reconnect_and_drain(&mut self) -> Result<()> {
+        if self.conn.is_none() {
+            let now = Instant::now();
+            if let Some(last_failure) = self.last_failure {
+                if now.saturating_duration_since(last_failure) < self.delay {
+                    return Ok(());
+                }
+            }
+            match (self.connect)() {
+                Ok(conn) => {
+                    self.conn = Some(conn);
+                    self.delay = self.backoff.initial;
+                    self.last_failure = None;
+                }
+                Err(err) => {
+                    if self.last_failure.is_some() {
+                        self.delay = self.delay.saturating_mul(2).min(self.backoff.max);
+                    }
+                    self.last_failure = Some(now);
+                    return Err(err);
+                }
+            }
+        }
+
+        let mut result = Ok(());
+        while let Some(msg) = self.pending.front() {
+            let conn = self.conn.as_mut().unwrap();
+            if let Err(err) = conn.write_all(msg) {
+                if is_connection_lost(&err) {
+                    self.disconnect();
+                    return Error::push_err(result, Error::WriteRecord(err));
+                }
+                result = Error::push_err(result, Error::WriteRecord(err));
+            }
+            self.pending.pop_front();
+        }
+        result
+    }
+
+    fn disconnect(&mut self) {
+        self.conn = None;
+        // Reconnect immediately at the next time, the backoff starts if that fails.
+        self.last_failure = None;
+    }
+
+    fn enqueue(&mut self, msg: &[u8]) -> Result<()> {
+        if self.pending.len() < self.capacity {
+            self.pending.push_back(msg.to_vec());
+            Ok(())
+        } else {
+            Err(Error::WriteRecord(io::Error::new(
+                io::ErrorKind::NotConnected,
+                "not connected and the buffer is full, the record is dropped",
+            )))
+        }
+    }
+}
+
+// Whether the error means the connection is gone, so that writing the same
+// message through a new connection may succeed.
+#[must_use]
+fn is_connection_lost(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::BrokenPipe
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::NotConnected
+            | io::ErrorKind::TimedOut
+            | io::ErrorKind::UnexpectedEof
+            | io::ErrorKind::WriteZero
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize};
+
+    use super::*;
+    use crate::sync::*;
+
+    struct SharedWriter(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock_expect().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn buffer_and_backoff() {
+        let online = Arc::new(AtomicBool::new(false));
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let output = Arc::new(Mutex::new(vec![]));
+
+        let mut writer = {
+            let (online, attempts, output) = (online.clone(), attempts.clone(), output.clone());
+            ReconnectingWriter::new(
+                move || {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    if online.load(Ordering::SeqCst) {
+                        Ok(SharedWriter(output.clone()))
+                    } else {
+                        Err(Error::Connect(io::ErrorKind::ConnectionRefused.into()))
+                    }
+                },
+                Backoff {
+                    initial: Duration::from_secs(3600),
+                    max: Duration::from_secs(3600),
+                },
+                2,
+            )
+        };
+
+        assert!(matches!(writer.write(b"1"), Err(Error::Connect(_))));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        // In backoff, no attempts
+        assert!(writer.write(b"2").is_ok());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        // Buffer is full
+        assert!(matches!(writer.write(b"3"), Err(Error::WriteRecord(_))));
+
+        online.store(true, Ordering::SeqCst);
+        writer.last_failure = None;
+        writer.write(b"4").unwrap();
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(*output.lock_expect(), b"124");
+    }
+
+    // Fails writing messages starting with `fail` by the given error kind.
+    struct FailingWriter(Arc<Mutex<Vec<u8>>>, io::ErrorKind);
+
+    impl Write for FailingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if buf.starts_with(b"fail") {
+                return Err(io::Error::from(self.1));
+            }
+            self.0.lock_expect().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn drop_on_non_connection_error() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let output = Arc::new(Mutex::new(vec![]));
+
+        let mut writer = {
+            let (attempts, output) = (attempts.clone(), output.clone());
+            ReconnectingWriter::new(
+                move || {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    Ok(FailingWriter(output.clone(), io::ErrorKind::InvalidInput))
+                },
+                Backoff::default(),
+                2,
+            )
+        };
+
+        assert!(matches!(writer.write(b"fail1"), Err(Error::WriteRecord(_))));
+        writer.write(b"2").unwrap();
+        writer.flush().unwrap();
+        assert!(writer.pending.is_empty());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        assert_eq!(*output.lock_expect(), b"2");
+
+        // Dropped when writing buffered messages too.
+        writer.pending.push_back(b"fail3".to_vec());
+        writer.pending.push_back(b"4".to_vec());
+        assert!(matches!(writer.flush(), Err(Error::WriteRecord(_))));
+        assert!(writer.pending.is_empty());
+        assert_eq!(*output.lock_expect(), b"24");
+    }
+
+    #[test]
+    fn retry_on_connection_lost() {
+        let mut writer = ReconnectingWriter::new(
+            || {
+                Ok(FailingWriter(
+                    Arc::new(Mutex::new(vec![])),
+                    io::ErrorKind::BrokenPipe,
+                ))
+            },
+            Backoff::default(),
+            2,
+        );
+
+        assert!(matches!(writer.write(b"fail1"), Err(Error::WriteRecord(_))));
+        assert!(writer.conn.is_none());
+        assert_eq!(writer.pending.len(), 1);
+    }
+
+    #[test]
+    fn huge_backoff() {
+        let mut writer = ReconnectingWriter::<Vec<u8>>::new(
+            || Err(Error::Connect(io::ErrorKind::ConnectionRefused.into())),
+            Backoff {
+                initial: Duration::MAX,
+                max: Duration::MAX,
+            },
+            1,
+        );
+
+        assert!(matches!(writer.write(b"1"), Err(Error::Connect(_))));
+        // In backoff, no attempts
+        assert!(writer.write(b"2").is_err());
+        assert_eq!(writer.delay, Duration::MAX);
+    }
+}
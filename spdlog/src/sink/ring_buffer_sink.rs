@@ -0,0 +1,365 @@
+use std::{collections::VecDeque, convert::Infallible, sync::Arc};
+
+use crate::{
+    error::InvalidArgumentError,
+    sink::{helper, Sink, Sinks},
+    sync::*,
+    Error, LevelFilter, Record, RecordOwned, Result,
+};
+
+/// A [combined sink], keeps the most recent records in memory and dumps them
+/// to internal sinks on demand.
+///
+/// All records passed to this sink are stored in a ring buffer, when the
+/// buffer is full, the oldest record is discarded. Records are not forwarded
+/// to internal sinks until:
+/// - [`RingBufferSink::dump`] is called, or
+/// - a record matching the [dump filter] arrives, then the buffered records
+///   including the incoming one are dumped.
+///
+/// After dumping, the buffer is cleared. This works like the backtrace feature
+/// of C++ spdlog, which allows capturing detailed logs but only emitting them
+/// when something goes wrong.
+///
+/// The [formatter] of this sink is not used, records are formatted by the
+/// internal sinks.
+///
+/// # Example
+///
+/// ```
+/// use spdlog::{prelude::*, sink::RingBufferSink};
+/// # use std::sync::Arc;
+/// # use spdlog::{
+/// #     formatter::{pattern, PatternFormatter},
+/// #     sink::WriteSink,
+/// # };
+/// #
+/// # fn main() -> Result<(), spdlog::Error> {
+/// # let underlying_sink = Arc::new(
+/// #     WriteSink::builder()
+/// #         .formatter(Box::new(PatternFormatter::new(pattern!("{payload}\n"))))
+/// #         .target(Vec::new())
+/// #         .build()?
+/// # );
+///
+/// # let sink = {
+/// #     let underlying_sink = underlying_sink.clone();
+/// let sink = Arc::new(
+///     RingBufferSink::builder()
+///         .capacity(2)
+///         .dump_filter(LevelFilter::MoreSevereEqual(Level::Error))
+///         .sink(underlying_sink)
+///         .build()?
+/// );
+/// #     sink
+/// # };
+/// # let doctest = Logger::builder()
+/// #     .sink(sink)
+/// #     .level_filter(LevelFilter::All)
+/// #     .build()?;
+///
+/// // ... Add the `sink` to a logger
+///
+/// debug!(logger: doctest, "connecting");
+/// debug!(logger: doctest, "handshaking");
+/// debug!(logger: doctest, "sending request");
+/// // The buffered records will be dumped since the level is `Error`.
+/// error!(logger: doctest, "connection reset");
+///
+/// # assert_eq!(
+/// #     String::from_utf8(underlying_sink.clone_target()).unwrap(),
+/// /* Output of `underlying_sink` */
+/// r#"sending request
+/// connection reset
+/// "#
+/// # );
+/// # Ok(()) }
+/// ```
+///
+/// [combined sink]: index.html#combined-sink
+/// [dump filter]: RingBufferSinkBuilder::dump_filter
+/// [formatter]: RingBufferSinkBuilder::formatter
+pub struct RingBufferSink {
+    common_impl: helper::CommonImpl,
+    sinks: Sinks,
+    capacity: usize,
+    dump_filter: LevelFilter,
+    records: Mutex<VecDeque<RecordOwned>>,
+}
+
+impl RingBufferSink {
+    /// Gets a builder of `RingBufferSink` with default parameters:
+    ///
+    /// | Parameter       | Default Value           |
+    /// |-----------------|-------------------------|
+    /// | [level_filter]  | `All`                   |
+    /// | [formatter]     | `FullFormatter`         |
+    /// | [error_handler] | [default error handler] |
+    /// |                 |                         |
+    /// | [sinks]         | `[]`                    |
+    /// | [capacity]      | *must be specified*     |
+    /// | [dump_filter]   | `Off`                   |
+    ///
+    /// [level_filter]: RingBufferSinkBuilder::level_filter
+    /// [formatter]: RingBufferSinkBuilder::formatter
+    /// [error_handler]: RingBufferSinkBuilder::error_handler
+    /// [default error handler]: error/index.html#default-error-handler
+    /// [sinks]: RingBufferSinkBuilder::sink
+    /// [capacity]: RingBufferSinkBuilder::capacity
+    /// [dump_filter]: RingBufferSinkBuilder::dump_filter
+    #[must_use]
+    pub fn builder() -> RingBufferSinkBuilder<()> {
+        RingBufferSinkBuilder {
+            common_builder_impl: helper::CommonBuilderImpl::new(),
+            sinks: vec![],
+            capacity: (),
+            dump_filter: LevelFilter::Off,
+        }
+    }
+
+    /// Gets a reference to internal sinks in the combined sink.
+    #[must_use]
+    pub fn sinks(&self) -> &[Arc<dyn Sink>] {
+        &self.sinks
+    }
+
+    /// Gets a snapshot of the buffered records, from the oldest to the newest.
+    ///
+    /// The buffer is not modified.
+    #[must_use]
+    pub fn records(&self) -> Vec<RecordOwned> {
+        self.records.lock_expect().iter().cloned().collect()
+    }
+
+    /// Forwards the buffered records to internal sinks, from the oldest to the
+    /// newest, and then clears the buffer.
+    pub fn dump(&self) -> Result<()> {
+        let records = std::mem::take(&mut *self.records.lock_expect());
+        self.log_records(records)
+    }
+
+    /// Discards the buffered records.
+    pub fn clear(&self) {
+        self.records.lock_expect().clear();
+    }
+
+    fn log_records(&self, records: VecDeque<RecordOwned>) -> Result<()> {
+        #[allow(clippy::manual_try_fold)] // https://github.com/rust-lang/rust-clippy/issues/11554
+        records.iter().fold(Ok(()), |result, record| {
+            self.sinks.iter().fold(result, |result, sink| {
+                Error::push_result(result, sink.log(&record.as_ref()))
+            })
+        })
+    }
+}
+
+impl Sink for RingBufferSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        let dumped = {
+            let mut records = self.records.lock_expect();
+            if records.len() == self.capacity {
+                records.pop_front();
+            }
+            records.push_back(record.to_owned());
+
+            if self.dump_filter.test(record.level()) {
+                Some(std::mem::take(&mut *records))
+            } else {
+                None
+            }
+        };
+
+        // Forwards without holding the lock, as `dump` does.
+        match dumped {
+            Some(records) => self.log_records(records),
+            None => Ok(()),
+        }
+    }
+
+    fn flush(&self) -> Result<()> {
+        #[allow(clippy::manual_try_fold)] // https://github.com/rust-lang/rust-clippy/issues/11554
+        self.sinks.iter().fold(Ok(()), |result, sink| {
+            Error::push_result(result, sink.flush())
+        })
+    }
+
+    helper::common_impl!(@Sink: common_impl);
+}
+
+/// #
+#[doc = include_str!("../include/doc/generic-builder-note.md")]
+pub struct RingBufferSinkBuilder<ArgC> {
+    common_builder_impl: helper::CommonBuilderImpl,
+    sinks: Sinks,
+    capacity: ArgC,
+    dump_filter: LevelFilter,
+}
+
+impl<ArgC> RingBufferSinkBuilder<ArgC> {
+    /// Add a [`Sink`].
+    #[must_use]
+    pub fn sink(mut self, sink: Arc<dyn Sink>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Add multiple [`Sink`]s.
+    #[must_use]
+    pub fn sinks<I>(mut self, sinks: I) -> Self
+    where
+        I: IntoIterator<Item = Arc<dyn Sink>>,
+    {
+        self.sinks.append(&mut sinks.into_iter().collect());
+        self
+    }
+
+    /// The maximum number of records kept in the buffer.
+    ///
+    /// It cannot be `0`.
+    ///
+    /// This parameter is **required**.
+    #[must_use]
+    pub fn capacity(self, capacity: usize) -> RingBufferSinkBuilder<usize> {
+        RingBufferSinkBuilder {
+            common_builder_impl: self.common_builder_impl,
+            sinks: self.sinks,
+            capacity,
+            dump_filter: self.dump_filter,
+        }
+    }
+
+    /// Specifies the level filter of records that trigger dumping the buffer
+    /// automatically, e.g. `LevelFilter::MoreSevereEqual(Level::Error)`.
+    ///
+    /// The default `LevelFilter::Off` means the buffer is only dumped by
+    /// calling [`RingBufferSink::dump`].
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn dump_filter(mut self, filter: LevelFilter) -> Self {
+        self.dump_filter = filter;
+        self
+    }
+
+    helper::common_impl!(@SinkBuilder: common_builder_impl);
+}
+
+impl RingBufferSinkBuilder<()> {
+    #[doc(hidden)]
+    #[deprecated(note = "\n\n\
+        builder compile-time error:\n\
+        - missing required parameter `capacity`\n\n\
+    ")]
+    pub fn build(self, _: Infallible) {}
+}
+
+impl RingBufferSinkBuilder<usize> {
+    /// Builds a [`RingBufferSink`].
+    ///
+    /// # Error
+    ///
+    /// If the capacity is `0`, [`Error::InvalidArgument`] will be returned.
+    pub fn build(self) -> Result<RingBufferSink> {
+        if self.capacity == 0 {
+            return Err(Error::InvalidArgument(
+                InvalidArgumentError::RingBufferCapacity("cannot be 0".to_string()),
+            ));
+        }
+
+        Ok(RingBufferSink {
+            common_impl: helper::CommonImpl::from_builder(self.common_builder_impl),
+            sinks: self.sinks,
+            capacity: self.capacity,
+            dump_filter: self.dump_filter,
+            records: Mutex::new(VecDeque::with_capacity(self.capacity)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    fn payloads(records: &[RecordOwned]) -> Vec<String> {
+        records
+            .iter()
+            .map(|record| record.payload().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn dump_on_demand() {
+        let test_sink = Arc::new(TestSink::new());
+        let ring_buffer_sink = Arc::new(
+            RingBufferSink::builder()
+                .capacity(3)
+                .sink(test_sink.clone())
+                .build()
+                .unwrap(),
+        );
+        let test = build_test_logger(|b| b.sink(ring_buffer_sink.clone()));
+
+        for i in 0..5 {
+            info!(logger: test, "{}", i);
+        }
+        critical!(logger: test, "no trigger");
+
+        assert_eq!(test_sink.log_count(), 0);
+        assert_eq!(
+            payloads(&ring_buffer_sink.records()),
+            ["3", "4", "no trigger"]
+        );
+
+        ring_buffer_sink.dump().unwrap();
+        assert_eq!(payloads(&test_sink.records()), ["3", "4", "no trigger"]);
+        assert!(ring_buffer_sink.records().is_empty());
+
+        info!(logger: test, "cleared");
+        ring_buffer_sink.clear();
+        ring_buffer_sink.dump().unwrap();
+        assert_eq!(test_sink.log_count(), 3);
+    }
+
+    #[test]
+    fn dump_on_trigger() {
+        let test_sink = Arc::new(TestSink::new());
+        let ring_buffer_sink = Arc::new(
+            RingBufferSink::builder()
+                .capacity(2)
+                .dump_filter(LevelFilter::MoreSevereEqual(Level::Error))
+                .sink(test_sink.clone())
+                .build()
+                .unwrap(),
+        );
+        let test = build_test_logger(|b| {
+            b.sink(ring_buffer_sink.clone())
+                .level_filter(LevelFilter::All)
+        });
+
+        debug!(logger: test, "a");
+        debug!(logger: test, "b");
+        warn!(logger: test, "c");
+        assert_eq!(test_sink.log_count(), 0);
+
+        error!(logger: test, "d");
+        let records = test_sink.records();
+        assert_eq!(payloads(&records), ["c", "d"]);
+        assert_eq!(records[0].level(), Level::Warn);
+        assert_eq!(records[1].level(), Level::Error);
+        assert!(ring_buffer_sink.records().is_empty());
+
+        critical!(logger: test, "e");
+        assert_eq!(payloads(&test_sink.records()), ["c", "d", "e"]);
+    }
+
+    #[test]
+    fn invalid_capacity() {
+        assert!(matches!(
+            RingBufferSink::builder().capacity(0).build(),
+            Err(Error::InvalidArgument(
+                InvalidArgumentError::RingBufferCapacity(_)
+            ))
+        ));
+    }
+}
@@ -1,18 +1,19 @@
 //! Provides a rotating file sink.
 
 use std::{
-    collections::LinkedList,
     convert::Infallible,
     ffi::OsString,
     fs::{self, File},
     hash::Hash,
-    io::{BufWriter, Write},
+    io::{self, BufWriter, Write},
     path::{Path, PathBuf},
     result::Result as StdResult,
+    sync::mpsc,
+    thread::{self, JoinHandle},
     time::{Duration, SystemTime},
 };
 
-use chrono::prelude::*;
+use chrono::{prelude::*, LocalResult};
 
 use crate::{
     error::InvalidArgumentError,
@@ -48,8 +49,18 @@ use crate::{
 ///
 /// // Rotrating every duration
 /// RotationPolicy::Duration { hours: 1, minutes: 2, seconds: 3};
+///
+/// // Rotating every Monday at 00:00.
+/// RotationPolicy::Weekly { weekday: 1, hour: 0, minute: 0 };
+///
+/// // Rotating on the 1st of every month at 00:00.
+/// RotationPolicy::Monthly { day: 1, hour: 0, minute: 0 };
+///
+/// // Rotating every day at 00:00, and every 10 MB file within a day.
+/// RotationPolicy::DailyAndFileSize { hour: 0, minute: 0, max_size: 1024 * 1024 * 10 };
 /// ```
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
 pub enum RotationPolicy {
     /// Rotating to a new log file when the size of the current log file exceeds
     /// the given limit.
@@ -75,6 +86,117 @@ pub enum RotationPolicy {
         /// Seconds to the next rotation. Range: [0, 59].
         seconds: u32,
     },
+    /// Rotating to a new log file at a specified time point within a week.
+    ///
+    /// The files are named by the date of the time point starting the
+    /// current week, e.g. `app_2022-03-21.log`.
+    Weekly {
+        /// Day of the week of the time point, `0` is Sunday. Range: [0, 6].
+        weekday: u32,
+        /// Hour of the time point. Range: [0, 23].
+        hour: u32,
+        /// Minute of the time point. Range: [0, 59].
+        minute: u32,
+    },
+    /// Rotating to a new log file at a specified time point within a month.
+    ///
+    /// If a month has fewer days than `day`, the time point is on the last day
+    /// of the month. The files are named by the year and month of the time
+    /// point starting the current month, e.g. `app_2022-03.log`.
+    Monthly {
+        /// Day of the month of the time point. Range: [1, 31].
+        day: u32,
+        /// Hour of the time point. Range: [0, 23].
+        hour: u32,
+        /// Minute of the time point. Range: [0, 59].
+        minute: u32,
+    },
+    /// Rotating to a new log file at a specified time point within a day, and
+    /// additionally to a new log file within the day when the size of the
+    /// current log file exceeds the given limit.
+    ///
+    /// The files split within a day are suffixed with an increasing number,
+    /// e.g. `app_2022-03-23.log`, `app_2022-03-23.1.log`,
+    /// `app_2022-03-23.2.log`.
+    DailyAndFileSize {
+        /// Hour of the time point. Range: [0, 23].
+        hour: u32,
+        /// Minute of the time point. Range: [0, 59].
+        minute: u32,
+        /// Maximum file size (in bytes). Range: (0, u64::MAX].
+        max_size: u64,
+    },
+    /// Rotating to a new log file after given duration (greater then {0, 0, 0})
+    /// is passed, and additionally to a new log file within the duration when
+    /// the size of the current log file exceeds the given limit.
+    ///
+    /// The files are named the same as [`RotationPolicy::DailyAndFileSize`].
+    DurationAndFileSize {
+        /// Hours to the next rotation.. Range: [0, u32::MAX].
+        hours: u32,
+        /// Minutes to the next rotation. Range: [0, 59].
+        minutes: u32,
+        /// Seconds to the next rotation. Range: [0, 59].
+        seconds: u32,
+        /// Maximum file size (in bytes). Range: (0, u64::MAX].
+        max_size: u64,
+    },
+}
+
+/// Naming schemes of rotated files for [`RotationPolicy::FileSize`].
+///
+/// Supposes the base path is `/path/to/base_file.log`, the active file is
+/// always written to the base path, and the rotated files are named as:
+///
+/// | Naming      | Rotated file names                                 |
+/// |-------------|----------------------------------------------------|
+/// | `Index`     | `base_file_1.log` (newest), `base_file_2.log`, ... |
+/// | `Timestamp` | `base_file_2022-03-23_10-20-30.log`, ...           |
+/// | `Sequence`  | `base_file.1.log` (oldest), `base_file.2.log`, ... |
+///
+/// With `Index`, every rotation renames all existing rotated files to shift
+/// their indexes. With `Timestamp` and `Sequence`, only the active file is
+/// renamed on rotation, the rotated files keep their names. `Timestamp` uses
+/// the local time of the rotation, a sequence number is appended if there are
+/// multiple rotations within a second, e.g.
+/// `base_file_2022-03-23_10-20-30.1.log`.
+///
+/// For `Sequence`, the sequence number is recovered after restarts by scanning
+/// the directory for existing rotated files.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum FileSizeNaming {
+    /// Rotated files are named by indexes, the newest is `1`.
+    Index,
+    /// Rotated files are named by the local time of rotations.
+    Timestamp,
+    /// Rotated files are named by increasing sequence numbers, the oldest is
+    /// `1`.
+    Sequence,
+}
+
+/// Compression methods for rotated files of [`RotatingFileSink`].
+///
+/// Rotated files are compressed on a background thread, the extension of the
+/// compression method is appended to the compressed file names, e.g.
+/// `base_file_1.log.gz`.
+///
+/// See [`RotatingFileSinkBuilder::compression`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum Compression {
+    /// Rotated files are not compressed.
+    None,
+    /// Compressing rotated files with gzip, extension `.gz` is appended.
+    ///
+    /// This variant requires crate feature `gzip`.
+    #[cfg(feature = "gzip")]
+    Gzip,
+    /// Compressing rotated files with zstd, extension `.zst` is appended.
+    ///
+    /// This variant requires crate feature `zstd`.
+    #[cfg(feature = "zstd")]
+    Zstd,
 }
 
 trait Rotator {
@@ -94,19 +216,39 @@ enum RotatorKind {
 struct RotatorFileSize {
     base_path: PathBuf,
     max_size: u64,
-    max_files: usize,
+    retention: Retention,
+    naming: FileSizeNaming,
+    template: Option<FileNameTemplate>,
+    compressor: Option<Compressor>,
     inner: SpinMutex<RotatorFileSizeInner>,
 }
 
 struct RotatorFileSizeInner {
     file: Option<BufWriter<File>>,
+    // Always `base_path` if there is no template.
+    file_path: PathBuf,
     current_size: u64,
+    next_sequence: u64,
+}
+
+// Sorting key of rotated files found by scanning the directory.
+type RotatedFileKey = (u64, u64);
+
+// Limits of rotated files, the oldest files exceeding them are removed.
+#[derive(Copy, Clone, Default)]
+struct Retention {
+    max_files: usize,
+    max_age: Option<Duration>,
+    max_total_size: Option<u64>,
 }
 
 struct RotatorTimePoint {
     base_path: PathBuf,
     time_point: TimePoint,
-    max_files: usize,
+    max_size: Option<u64>,
+    retention: Retention,
+    template: Option<FileNameTemplate>,
+    compressor: Option<Compressor>,
     inner: SpinMutex<RotatorTimePointInner>,
 }
 
@@ -115,14 +257,50 @@ enum TimePoint {
     Daily { hour: u32, minute: u32 },
     Hourly,
     Duration { hours: u32, minutes: u32, seconds: u32 },
+    Weekly {
+        weekday: u32,
+        hour: u32,
+        minute: u32,
+    },
+    Monthly {
+        day: u32,
+        hour: u32,
+        minute: u32,
+    },
 }
 
 struct RotatorTimePointInner {
     file: BufWriter<File>,
+    file_path: PathBuf,
     rotation_time_point: SystemTime,
-    file_paths: Option<LinkedList<PathBuf>>,
+    // The time used to name the files of the current period.
+    period_time: SystemTime,
+    split_index: usize,
+    current_size: u64,
+}
+
+// Compresses rotated files on a background thread, and renames or removes them
+// in order with the compressions.
+struct Compressor {
+    compression: Compression,
+    shared: Arc<(Mutex<CompressorState>, Condvar)>,
+    worker: Option<JoinHandle<()>>,
+}
+
+// A file operation run by the worker of `Compressor`.
+type CompressorJob = Box<dyn FnOnce() -> Result<()> + Send>;
+
+struct CompressorState {
+    // `None` if the worker has stopped.
+    sender: Option<mpsc::Sender<CompressorJob>>,
+    pending: usize,
 }
 
+// Marks the worker as stopped when its thread exits, even if it panics, so that
+// `Compressor` falls back to running jobs on the caller thread and
+// `Compressor::wait_idle` never waits for a dead worker.
+struct WorkerExitGuard(Arc<(Mutex<CompressorState>, Condvar)>);
+
 /// A sink with a file as the target, split files according to the rotation
 /// policy.
 ///
@@ -141,7 +319,7 @@ struct RotatorTimePointInner {
 ///
 /// [./examples]: https://github.com/SpriteOvO/spdlog-rs/tree/main/spdlog/examples
 pub struct RotatingFileSink {
-    common_impl: helper::CommonImpl,
+    common_impl: Arc<helper::CommonImpl>,
     rotator: RotatorKind,
 }
 
@@ -152,31 +330,45 @@ pub struct RotatingFileSinkBuilder<ArgBP, ArgRP> {
     base_path: ArgBP,
     rotation_policy: ArgRP,
     max_files: usize,
+    max_age: Option<Duration>,
+    max_total_size: Option<u64>,
     rotate_on_open: bool,
+    compression: Compression,
+    file_size_naming: FileSizeNaming,
+    file_name_template: Option<String>,
 }
 
 impl RotatingFileSink {
     /// Gets a builder of `RotatingFileSink` with default parameters:
     ///
-    /// | Parameter         | Default Value           |
-    /// |-------------------|-------------------------|
-    /// | [level_filter]    | `All`                   |
-    /// | [formatter]       | `FullFormatter`         |
-    /// | [error_handler]   | [default error handler] |
-    /// |                   |                         |
-    /// | [base_path]       | *must be specified*     |
-    /// | [rotation_policy] | *must be specified*     |
-    /// | [max_files]       | `0`                     |
-    /// | [rotate_on_open]  | `false`                 |
+    /// | Parameter                           | Default Value           |
+    /// |-------------------------------------|-------------------------|
+    /// | [level_filter]                      | `All`                   |
+    /// | [formatter]                         | `FullFormatter`         |
+    /// | [error_handler]                     | [default error handler] |
+    /// |                                     |                         |
+    /// | [base_path] or [file_name_template] | *must be specified*     |
+    /// | [rotation_policy]                   | *must be specified*     |
+    /// | [max_files]                         | `0`                     |
+    /// | [max_age]                           | `None`                  |
+    /// | [max_total_size]                    | `None`                  |
+    /// | [rotate_on_open]                    | `false`                 |
+    /// | [compression]                       | `None`                  |
+    /// | [file_size_naming]                  | `Index`                 |
     ///
     /// [level_filter]: RotatingFileSinkBuilder::level_filter
     /// [formatter]: RotatingFileSinkBuilder::formatter
     /// [error_handler]: RotatingFileSinkBuilder::error_handler
     /// [default error handler]: error/index.html#default-error-handler
     /// [base_path]: RotatingFileSinkBuilder::base_path
+    /// [file_name_template]: RotatingFileSinkBuilder::file_name_template
     /// [rotation_policy]: RotatingFileSinkBuilder::rotation_policy
     /// [max_files]: RotatingFileSinkBuilder::max_files
+    /// [max_age]: RotatingFileSinkBuilder::max_age
+    /// [max_total_size]: RotatingFileSinkBuilder::max_total_size
     /// [rotate_on_open]: RotatingFileSinkBuilder::rotate_on_open
+    /// [compression]: RotatingFileSinkBuilder::compression
+    /// [file_size_naming]: RotatingFileSinkBuilder::file_size_naming
     #[must_use]
     pub fn builder() -> RotatingFileSinkBuilder<(), ()> {
         RotatingFileSinkBuilder {
@@ -184,7 +376,12 @@ impl RotatingFileSink {
             base_path: (),
             rotation_policy: (),
             max_files: 0,
+            max_age: None,
+            max_total_size: None,
             rotate_on_open: false,
+            compression: Compression::None,
+            file_size_naming: FileSizeNaming::Index,
+            file_name_template: None,
         }
     }
 
@@ -303,6 +500,52 @@ impl RotationPolicy {
                     ));
                 }
             }
+            Self::Weekly {
+                weekday,
+                hour,
+                minute,
+            } => {
+                if *weekday > 6 || *hour > 23 || *minute > 59 {
+                    return Err(format!(
+                        "policy 'weekly' expect `(weekday, hour, minute)` to be ([0, 6], [0, 23], [0, 59]) but got ({}, {}, {})",
+                        *weekday, *hour, *minute
+                    ));
+                }
+            }
+            Self::Monthly { day, hour, minute } => {
+                if *day == 0 || *day > 31 || *hour > 23 || *minute > 59 {
+                    return Err(format!(
+                        "policy 'monthly' expect `(day, hour, minute)` to be ([1, 31], [0, 23], [0, 59]) but got ({}, {}, {})",
+                        *day, *hour, *minute
+                    ));
+                }
+            }
+            Self::DailyAndFileSize {
+                hour,
+                minute,
+                max_size,
+            } => {
+                Self::Daily {
+                    hour: *hour,
+                    minute: *minute,
+                }
+                .validate()?;
+                Self::FileSize(*max_size).validate()?;
+            }
+            Self::DurationAndFileSize {
+                hours,
+                minutes,
+                seconds,
+                max_size,
+            } => {
+                Self::Duration {
+                    hours: *hours,
+                    minutes: *minutes,
+                    seconds: *seconds,
+                }
+                .validate()?;
+                Self::FileSize(*max_size).validate()?;
+            }
         }
         Ok(())
     }
@@ -335,19 +578,62 @@ impl RotatorFileSize {
     fn new(
         base_path: PathBuf,
         max_size: u64,
-        max_files: usize,
+        retention: Retention,
         rotate_on_open: bool,
+        naming: FileSizeNaming,
+        template: Option<FileNameTemplate>,
+        compressor: Option<Compressor>,
     ) -> Result<Self> {
-        let file = utils::open_file(&base_path, false)?;
+        let mut file_path = base_path.clone();
+        let mut next_sequence = 1;
+
+        if let Some(template) = &template {
+            // Continues writing to the newest file if it's not rotated.
+            let compression = compressor.as_ref().map(|compressor| compressor.compression);
+            let files = template.find_files(compression)?;
+            next_sequence = files
+                .iter()
+                .map(|((_, index), _)| index + 1)
+                .max()
+                .unwrap_or(0);
+            file_path = match files.last() {
+                Some((_, path)) if path.exists() => path.clone(),
+                _ => {
+                    next_sequence += 1;
+                    template.format(SystemTime::now(), next_sequence - 1)
+                }
+            };
+        }
+
+        let file = utils::open_file(&file_path, false)?;
         let current_size = file.metadata().map_err(Error::QueryFileMetadata)?.len();
 
-        let res = Self {
+        let mut res = Self {
             base_path,
             max_size,
-            max_files,
-            inner: SpinMutex::new(RotatorFileSizeInner::new(file, current_size)),
+            retention,
+            naming,
+            template,
+            compressor,
+            inner: SpinMutex::new(RotatorFileSizeInner {
+                file: Some(BufWriter::new(file)),
+                file_path,
+                current_size,
+                next_sequence,
+            }),
         };
 
+        if res.template.is_none() && naming == FileSizeNaming::Sequence {
+            let last_sequence = res
+                .find_rotated_files()?
+                .last()
+                .map_or(0, |((sequence, _), _)| *sequence);
+            res.inner.get_mut().next_sequence = last_sequence + 1;
+        }
+
+        let active = res.inner.get_mut().file_path.clone();
+        res.remove_old_files(&active)?;
+
         if rotate_on_open && current_size > 0 {
             res.rotate(&mut res.inner.lock())?;
             res.inner.lock().current_size = 0;
@@ -356,39 +642,149 @@ impl RotatorFileSize {
         Ok(res)
     }
 
-    fn reopen(&self) -> Result<File> {
+    fn reopen(&self, file_path: &Path) -> Result<File> {
         // always truncate
-        utils::open_file(&self.base_path, true)
+        utils::open_file(file_path, true)
     }
 
     fn rotate(&self, opened_file: &mut SpinMutexGuard<RotatorFileSizeInner>) -> Result<()> {
-        let inner = || {
-            for i in (1..self.max_files).rev() {
-                let src = Self::calc_file_path(&self.base_path, i - 1);
-                if !src.exists() {
-                    continue;
-                }
+        opened_file.file = None;
 
-                let dst = Self::calc_file_path(&self.base_path, i);
-                if dst.exists() {
-                    fs::remove_file(&dst).map_err(Error::RemoveFile)?;
-                }
+        let res = match (&self.template, self.naming) {
+            (Some(template), _) => self
+                .switch_to_new_file(template, opened_file)
+                .and_then(|_| self.remove_old_files(&opened_file.file_path)),
+            // Applies the retention limits after shifting by itself.
+            (None, FileSizeNaming::Index) => self.shift_rotated_files(),
+            (None, FileSizeNaming::Timestamp | FileSizeNaming::Sequence) => self
+                .rename_to_new_rotated_file(opened_file)
+                .and_then(|_| self.remove_old_files(&opened_file.file_path)),
+        };
+        if res.is_err() {
+            opened_file.current_size = 0;
+        }
+
+        opened_file.file = Some(BufWriter::new(self.reopen(&opened_file.file_path)?));
+
+        res
+    }
 
-                fs::rename(src, dst).map_err(Error::RenameFile)?;
+    // The previous file keeps its name, switches to a new file generated by the
+    // template.
+    fn switch_to_new_file(
+        &self,
+        template: &FileNameTemplate,
+        opened_file: &mut RotatorFileSizeInner,
+    ) -> Result<()> {
+        let now = SystemTime::now();
+        let file_path = loop {
+            let path = template.format(now, opened_file.next_sequence);
+            opened_file.next_sequence += 1;
+            if !path.exists() {
+                break path;
             }
-            Ok(())
         };
 
-        opened_file.file = None;
+        let previous = std::mem::replace(&mut opened_file.file_path, file_path);
+        if let Some(compressor) = &self.compressor {
+            compressor.compress(previous)?;
+        }
+        Ok(())
+    }
 
-        let res = inner();
-        if res.is_err() {
-            opened_file.current_size = 0;
+    fn shift_rotated_files(&self) -> Result<()> {
+        // Only the active file is kept, it's truncated when reopened.
+        if self.retention.max_files == 1 {
+            return Ok(());
         }
 
-        opened_file.file = Some(BufWriter::new(self.reopen()?));
+        let compressor = match &self.compressor {
+            Some(compressor) => compressor,
+            None => {
+                Self::shift_index_files(&self.base_path, self.retention.max_files, None)?;
+                if self.base_path.exists() {
+                    fs::rename(&self.base_path, Self::calc_file_path(&self.base_path, 1))
+                        .map_err(Error::RenameFile)?;
+                }
+                return self.remove_old_files(&self.base_path);
+            }
+        };
+
+        // Files being compressed cannot be renamed, so the active file is moved
+        // aside, and the worker shifts the rotated files after the pending
+        // compressions, without blocking the logging thread.
+        let staging = Self::staging_file_path(&self.base_path);
+        fs::rename(&self.base_path, &staging).map_err(Error::RenameFile)?;
+
+        let (base_path, retention, compression) = (
+            self.base_path.clone(),
+            self.retention,
+            compressor.compression,
+        );
+        compressor.run(Box::new(move || {
+            Self::shift_index_files(&base_path, retention.max_files, Some(compression))?;
+            let rotated = Self::calc_file_path(&base_path, 1);
+            fs::rename(&staging, &rotated).map_err(Error::RenameFile)?;
+            compression.compress_file(&rotated)?;
+
+            let rotated_files = Self::find_index_files(&base_path, Some(compression))?;
+            remove_files(
+                &retention.old_files(&rotated_files, Some(compression))?,
+                Some(compression),
+            )
+        }))
+    }
 
-        res
+    // Shifts the rotated files by one index to make room for a new `_1`, the
+    // file shifted beyond `max_files` is removed.
+    fn shift_index_files(
+        base_path: &Path,
+        max_files: usize,
+        compression: Option<Compression>,
+    ) -> Result<()> {
+        // Without the limit on the number of files, all the existing rotated files
+        // are shifted, so that the other retention limits can still apply to them.
+        let count = match max_files {
+            0 => {
+                let oldest_index = Self::find_index_files(base_path, compression)?
+                    .first()
+                    .map_or(0, |((key, _), _)| u64::MAX - key);
+                oldest_index as usize + 2
+            }
+            max_files => max_files,
+        };
+
+        for i in (1..count).rev() {
+            let dsts = Self::rotated_file_paths(base_path, i, compression);
+            for dst in &dsts {
+                if dst.exists() {
+                    fs::remove_file(dst).map_err(Error::RemoveFile)?;
+                }
+            }
+
+            if i > 1 {
+                let srcs = Self::rotated_file_paths(base_path, i - 1, compression);
+                for (src, dst) in srcs.into_iter().zip(dsts) {
+                    if src.exists() {
+                        fs::rename(src, dst).map_err(Error::RenameFile)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // A temporary path the active file is moved to until it's shifted to `_1`.
+    #[must_use]
+    fn staging_file_path(base_path: &Path) -> PathBuf {
+        (0..)
+            .map(|n| {
+                let mut path = base_path.as_os_str().to_owned();
+                path.push(format!(".{}.tmp", n));
+                PathBuf::from(path)
+            })
+            .find(|path| !path.exists())
+            .unwrap()
     }
 
     #[must_use]
@@ -418,11 +814,169 @@ impl RotatorFileSize {
         path
     }
 
+    // Renames the active file to a new name, the rotated files keep their names.
+    fn rename_to_new_rotated_file(&self, opened_file: &mut RotatorFileSizeInner) -> Result<()> {
+        let exists = |path: &Path| {
+            path.exists()
+                || self.compressor.as_ref().map_or(false, |compressor| {
+                    compressor.compression.compressed_path(path).exists()
+                })
+        };
+
+        let rotated_file_path = match self.naming {
+            FileSizeNaming::Index => unreachable!(),
+            FileSizeNaming::Timestamp => {
+                let local_time: DateTime<Local> = SystemTime::now().into();
+                (0..)
+                    .map(|sequence| self.timestamp_file_path(&local_time, sequence))
+                    .find(|path| !exists(path))
+                    .unwrap()
+            }
+            FileSizeNaming::Sequence => loop {
+                let path = self.sequence_file_path(opened_file.next_sequence);
+                opened_file.next_sequence += 1;
+                if !exists(&path) {
+                    break path;
+                }
+            },
+        };
+        fs::rename(&self.base_path, &rotated_file_path).map_err(Error::RenameFile)?;
+
+        if let Some(compressor) = &self.compressor {
+            compressor.compress(rotated_file_path)?;
+        }
+        Ok(())
+    }
+
+    fn remove_old_files(&self, active: &Path) -> Result<()> {
+        if self.retention.is_unlimited() {
+            return Ok(());
+        }
+        let mut rotated_files = self.find_rotated_files()?;
+        rotated_files.retain(|(_, path)| path != active);
+        let old_files = self
+            .retention
+            .old_files(&rotated_files, self.compression())?;
+        remove_files_in_order(old_files, self.compressor.as_ref())
+    }
+
+    #[must_use]
+    fn compression(&self) -> Option<Compression> {
+        self.compressor
+            .as_ref()
+            .map(|compressor| compressor.compression)
+    }
+
+    #[must_use]
+    fn base_file_name_parts(base_path: &Path) -> (String, String) {
+        let stem = base_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let extension = base_path
+            .extension()
+            .map(|s| format!(".{}", s.to_string_lossy()))
+            .unwrap_or_default();
+        (stem, extension)
+    }
+
+    #[must_use]
+    fn timestamp_file_path(&self, local_time: &DateTime<Local>, sequence: u64) -> PathBuf {
+        let (stem, extension) = Self::base_file_name_parts(&self.base_path);
+        let sequence = if sequence == 0 {
+            String::new()
+        } else {
+            format!(".{}", sequence)
+        };
+        self.base_path.with_file_name(format!(
+            "{}_{}{}{}",
+            stem,
+            local_time.format("%Y-%m-%d_%H-%M-%S"),
+            sequence,
+            extension
+        ))
+    }
+
+    #[must_use]
+    fn sequence_file_path(&self, sequence: u64) -> PathBuf {
+        let (stem, extension) = Self::base_file_name_parts(&self.base_path);
+        self.base_path
+            .with_file_name(format!("{}.{}{}", stem, sequence, extension))
+    }
+
+    // Parses a file name of the current naming scheme.
+    #[must_use]
+    fn parse_rotated_file_name(&self, file_name: &str) -> Option<RotatedFileKey> {
+        let (stem, extension) = Self::base_file_name_parts(&self.base_path);
+        let middle = file_name.strip_prefix(&stem)?.strip_suffix(&extension)?;
+
+        match self.naming {
+            FileSizeNaming::Index => Self::parse_index(middle),
+            FileSizeNaming::Timestamp => {
+                // `_%Y-%m-%d_%H-%M-%S` with an optional `.{sequence}`
+                let middle = middle.strip_prefix('_')?;
+                let (time, sequence) = match middle.find('.') {
+                    Some(index) => (&middle[..index], parse_number(&middle[index + 1..])?),
+                    None => (middle, 0),
+                };
+                Some((parse_digits(time, "0000-00-00_00-00-00")?, sequence))
+            }
+            FileSizeNaming::Sequence => Some((parse_number(middle.strip_prefix('.')?)?, 0)),
+        }
+    }
+
+    // Parses the part of an index file name between the stem and the extension.
+    #[must_use]
+    fn parse_index(middle: &str) -> Option<RotatedFileKey> {
+        // The larger the index, the older the file.
+        let index = parse_number(middle.strip_prefix('_')?)?;
+        (index > 0).then(|| (u64::MAX - index, 0))
+    }
+
+    fn find_rotated_files(&self) -> Result<Vec<(RotatedFileKey, PathBuf)>> {
+        match &self.template {
+            Some(template) => template.find_files(self.compression()),
+            None => find_rotated_files(&self.base_path, self.compression(), |file_name| {
+                self.parse_rotated_file_name(file_name)
+            }),
+        }
+    }
+
+    // Same as `find_rotated_files` for `FileSizeNaming::Index` without a
+    // template, but doesn't borrow the rotator.
+    fn find_index_files(
+        base_path: &Path,
+        compression: Option<Compression>,
+    ) -> Result<Vec<(RotatedFileKey, PathBuf)>> {
+        let (stem, extension) = Self::base_file_name_parts(base_path);
+        find_rotated_files(base_path, compression, |file_name| {
+            Self::parse_index(file_name.strip_prefix(&stem)?.strip_suffix(&extension)?)
+        })
+    }
+
+    // Returns the possible paths of the file at `index`, uncompressed and
+    // compressed.
+    #[must_use]
+    fn rotated_file_paths(
+        base_path: &Path,
+        index: usize,
+        compression: Option<Compression>,
+    ) -> Vec<PathBuf> {
+        let file_path = Self::calc_file_path(base_path, index);
+        match compression {
+            Some(compression) => {
+                let compressed = compression.compressed_path(&file_path);
+                vec![file_path, compressed]
+            }
+            None => vec![file_path],
+        }
+    }
+
     // if `self.inner.file` is `None`, try to reopen the file.
-    fn lock_inner(&self) -> Result<SpinMutexGuard<RotatorFileSizeInner>> {
+    fn lock_inner(&self) -> Result<SpinMutexGuard<'_, RotatorFileSizeInner>> {
         let mut inner = self.inner.lock();
         if inner.file.is_none() {
-            inner.file = Some(BufWriter::new(self.reopen()?));
+            inner.file = Some(BufWriter::new(self.reopen(&inner.file_path)?));
         }
         Ok(inner)
     }
@@ -465,63 +1019,148 @@ impl Rotator for RotatorFileSize {
     }
 }
 
-impl RotatorFileSizeInner {
-    #[must_use]
-    fn new(file: File, current_size: u64) -> Self {
-        Self {
-            file: Some(BufWriter::new(file)),
-            current_size,
-        }
-    }
-}
-
 impl RotatorTimePoint {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         override_now: Option<SystemTime>,
         base_path: PathBuf,
         time_point: TimePoint,
-        max_files: usize,
+        max_size: Option<u64>,
+        retention: Retention,
         truncate: bool,
+        template: Option<FileNameTemplate>,
+        compressor: Option<Compressor>,
     ) -> Result<Self> {
         let now = override_now.unwrap_or_else(SystemTime::now);
-        let file_path = Self::calc_file_path(base_path.as_path(), time_point, now);
-        let file = utils::open_file(file_path, truncate)?;
+
+        // Continues writing to the last file split within the current period.
+        // The index is recovered from the existing files rather than probing
+        // from 0, since the retention limits may have removed the earlier ones.
+        let mut split_index = 0;
+        if max_size.is_some() {
+            let path = |index| {
+                Self::calc_period_file_path(&base_path, template.as_ref(), time_point, now, index)
+            };
+            let compression = compressor.as_ref().map(|compressor| compressor.compression);
+            let files = match &template {
+                Some(template) => template.find_files(compression)?,
+                None => find_rotated_files(&base_path, compression, |file_name| {
+                    Self::parse_split_file_name(&base_path, time_point, file_name)
+                })?,
+            };
+            let last_index = files
+                .iter()
+                .map(|((_, index), file_path)| (*index as usize, file_path))
+                .filter(|(index, file_path)| path(*index) == **file_path)
+                .map(|(index, _)| index)
+                .max();
+            if let Some(last_index) = last_index {
+                split_index = last_index;
+                // The last file has been rotated and compressed, or is being
+                // compressed.
+                let compressed = compressor.as_ref().map_or(false, |compressor| {
+                    compressor
+                        .compression
+                        .compressed_path(&path(last_index))
+                        .exists()
+                });
+                if compressed {
+                    split_index += 1;
+                }
+            }
+        }
+
+        let file_path = Self::calc_period_file_path(
+            &base_path,
+            template.as_ref(),
+            time_point,
+            now,
+            split_index,
+        );
+        let file = utils::open_file(&file_path, truncate)?;
+        let current_size = file.metadata().map_err(Error::QueryFileMetadata)?.len();
 
         let inner = RotatorTimePointInner {
             file: BufWriter::new(file),
+            file_path,
             rotation_time_point: Self::next_rotation_time_point(time_point, now),
-            file_paths: None,
+            period_time: now,
+            split_index,
+            current_size,
         };
 
-        let mut res = Self {
+        let res = Self {
             base_path,
             time_point,
-            max_files,
+            max_size,
+            retention,
+            template,
+            compressor,
             inner: SpinMutex::new(inner),
         };
 
-        res.init_previous_file_paths(max_files, now);
+        res.remove_old_files(&res.inner.lock().file_path)?;
 
         Ok(res)
     }
 
-    fn init_previous_file_paths(&mut self, max_files: usize, mut now: SystemTime) {
-        if max_files > 0 {
-            let mut file_paths = LinkedList::new();
-
-            for _ in 0..max_files {
-                let file_path = Self::calc_file_path(&self.base_path, self.time_point, now);
-
-                if !file_path.exists() {
-                    break;
-                }
+    // Removes the old files exceeding the retention limits, `active` is the
+    // file being written.
+    fn remove_old_files(&self, active: &Path) -> Result<()> {
+        if self.retention.is_unlimited() {
+            return Ok(());
+        }
+        let compression = self
+            .compressor
+            .as_ref()
+            .map(|compressor| compressor.compression);
+        let mut rotated_files = match &self.template {
+            Some(template) => template.find_files(compression)?,
+            None => find_rotated_files(&self.base_path, compression, |file_name| {
+                self.parse_rotated_file_name(file_name)
+            })?,
+        };
+        rotated_files.retain(|(_, path)| path != active);
+        let old_files = self.retention.old_files(&rotated_files, compression)?;
+        remove_files_in_order(old_files, self.compressor.as_ref())
+    }
 
-                file_paths.push_front(file_path);
-                now = now.checked_sub(self.time_point.delta_std()).unwrap()
-            }
+    // Parses a file name generated by `calc_split_file_path`.
+    #[must_use]
+    fn parse_rotated_file_name(&self, file_name: &str) -> Option<RotatedFileKey> {
+        Self::parse_split_file_name(&self.base_path, self.time_point, file_name)
+    }
 
-            self.inner.get_mut().file_paths = Some(file_paths);
-        }
+    #[must_use]
+    fn parse_split_file_name(
+        base_path: &Path,
+        time_point: TimePoint,
+        file_name: &str,
+    ) -> Option<RotatedFileKey> {
+        let stem = base_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let extension = base_path
+            .extension()
+            .map(|s| format!(".{}", s.to_string_lossy()))
+            .unwrap_or_default();
+        let middle = file_name
+            .strip_prefix(&stem)?
+            .strip_suffix(&extension)?
+            .strip_prefix('_')?;
+
+        let (time, index) = match middle.find('.') {
+            Some(index) => (&middle[..index], parse_number(&middle[index + 1..])?),
+            None => (middle, 0),
+        };
+        let pattern = match time_point {
+            TimePoint::Daily { .. } | TimePoint::Weekly { .. } => "0000-00-00",
+            TimePoint::Hourly => "0000-00-00_00",
+            TimePoint::Duration { .. } => "0000-00-00_00-00-00",
+            TimePoint::Monthly { .. } => "0000-00",
+        };
+        Some((parse_digits(time, pattern)?, index))
     }
 
     // a little expensive, should only be called when rotation is needed or in
@@ -529,56 +1168,89 @@ impl RotatorTimePoint {
     #[must_use]
     fn next_rotation_time_point(time_point: TimePoint, now: SystemTime) -> SystemTime {
         let now: DateTime<Local> = now.into();
-        let mut rotation_time = now;
+        let today = now.naive_local().date();
 
-        match time_point {
-            TimePoint::Daily { hour, minute } => {
-                rotation_time = rotation_time
-                    .with_hour(hour)
-                    .unwrap()
-                    .with_minute(minute)
-                    .unwrap()
-                    .with_second(0)
-                    .unwrap()
-                    .with_nanosecond(0)
+        let time = match time_point {
+            TimePoint::Daily { hour, minute } => today.and_hms_opt(hour, minute, 0).unwrap(),
+            TimePoint::Hourly => today.and_hms_opt(now.hour(), 0, 0).unwrap(),
+            TimePoint::Duration { .. } => {
+                return now
+                    .checked_add_signed(time_point.delta_chrono())
                     .unwrap()
+                    .into();
             }
-            TimePoint::Hourly => {
-                rotation_time = rotation_time
-                    .with_minute(0)
-                    .unwrap()
-                    .with_second(0)
-                    .unwrap()
-                    .with_nanosecond(0)
-                    .unwrap()
+            TimePoint::Weekly { .. } | TimePoint::Monthly { .. } => {
+                let mut rotation_time = Self::calendar_time_point(time_point, now, 0);
+                if rotation_time <= now {
+                    rotation_time = Self::calendar_time_point(time_point, now, 1);
+                }
+                return rotation_time.into();
             }
-            TimePoint::Duration {..} => {}
         };
 
+        // Steps in local time, so that the time point stays at the same local
+        // time across DST transitions.
+        let mut rotation_time = local_date_time(time);
         if rotation_time <= now {
-            rotation_time = rotation_time
-                .checked_add_signed(time_point.delta_chrono())
-                .unwrap();
+            rotation_time = local_date_time(time + time_point.delta_chrono());
         }
         rotation_time.into()
     }
 
-    fn push_new_remove_old(
-        &self,
-        new: PathBuf,
-        inner: &mut SpinMutexGuard<RotatorTimePointInner>,
-    ) -> Result<()> {
-        let file_paths = inner.file_paths.as_mut().unwrap();
-
-        while file_paths.len() >= self.max_files {
-            let old = file_paths.pop_front().unwrap();
-            if old.exists() {
-                fs::remove_file(old).map_err(Error::RemoveFile)?;
+    // Gets the time point starting the period containing `time`, for naming
+    // files of calendar-based time points, other time points are named by
+    // `time` itself.
+    #[must_use]
+    fn period_start_time(time_point: TimePoint, time: SystemTime) -> SystemTime {
+        match time_point {
+            TimePoint::Weekly { .. } | TimePoint::Monthly { .. } => {
+                let now: DateTime<Local> = time.into();
+                let mut start_time = Self::calendar_time_point(time_point, now, 0);
+                if start_time > now {
+                    start_time = Self::calendar_time_point(time_point, now, -1);
+                }
+                start_time.into()
             }
+            TimePoint::Daily { .. } | TimePoint::Hourly | TimePoint::Duration { .. } => time,
         }
-        file_paths.push_back(new);
+    }
 
-        Ok(())
+    // Calculates the time point within the week or month containing `now`,
+    // shifted by `offset` weeks or months. The result may be later than `now`.
+    //
+    // Weeks start on Sunday. If the month has fewer days than the specified day,
+    // the last day of the month is used.
+    #[must_use]
+    fn calendar_time_point(
+        time_point: TimePoint,
+        now: DateTime<Local>,
+        offset: i32,
+    ) -> DateTime<Local> {
+        let today = now.naive_local().date();
+        let (date, hour, minute) = match time_point {
+            TimePoint::Weekly {
+                weekday,
+                hour,
+                minute,
+            } => {
+                let days = weekday as i64 - today.weekday().num_days_from_sunday() as i64
+                    + offset as i64 * 7;
+                (today + chrono::Duration::days(days), hour, minute)
+            }
+            TimePoint::Monthly { day, hour, minute } => {
+                let months = today.year() * 12 + today.month0() as i32 + offset;
+                let (year, month) = (months.div_euclid(12), months.rem_euclid(12) as u32 + 1);
+                let date = (1..=day)
+                    .rev()
+                    .find_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+                    .unwrap();
+                (date, hour, minute)
+            }
+            TimePoint::Daily { .. } | TimePoint::Hourly | TimePoint::Duration { .. } => {
+                unreachable!()
+            }
+        };
+        local_date_time(date.and_hms_opt(hour, minute, 0).unwrap())
     }
 
     #[must_use]
@@ -598,7 +1270,7 @@ impl RotatorTimePoint {
         let externsion = base_path.extension();
 
         match time_point {
-            TimePoint::Daily { .. } => {
+            TimePoint::Daily { .. } | TimePoint::Weekly { .. } => {
                 // append y-m-d
                 file_name.push(format!(
                     "_{}-{:02}-{:02}",
@@ -629,6 +1301,10 @@ impl RotatorTimePoint {
                     local_time.second()
                 ));
             }
+            TimePoint::Monthly { .. } => {
+                // append y-m
+                file_name.push(format!("_{}-{:02}", local_time.year(), local_time.month()));
+            }
         }
 
         let mut path = base_path.to_owned();
@@ -639,34 +1315,102 @@ impl RotatorTimePoint {
 
         path
     }
-}
 
-impl Rotator for RotatorTimePoint {
-    fn log(&self, record: &Record, string_buf: &StringBuf) -> Result<()> {
+    // Inserts `.{index}` before the extension of the file path of the time
+    // point, if `index` is not 0.
+    #[must_use]
+    fn calc_split_file_path(
+        base_path: impl AsRef<Path>,
+        time_point: TimePoint,
+        system_time: SystemTime,
+        index: usize,
+    ) -> PathBuf {
+        let path = Self::calc_file_path(base_path, time_point, system_time);
+        if index == 0 {
+            return path;
+        }
+
+        let mut file_name = path
+            .file_stem()
+            .map(|s| s.to_owned())
+            .unwrap_or_else(|| OsString::from(""));
+        file_name.push(format!(".{}", index));
+        if let Some(externsion) = path.extension() {
+            file_name.push(".");
+            file_name.push(externsion);
+        }
+        path.with_file_name(file_name)
+    }
+
+    // Generates the file path by the template if specified, otherwise the same
+    // as `calc_split_file_path`.
+    #[must_use]
+    fn calc_period_file_path(
+        base_path: impl AsRef<Path>,
+        template: Option<&FileNameTemplate>,
+        time_point: TimePoint,
+        system_time: SystemTime,
+        index: usize,
+    ) -> PathBuf {
+        let system_time = Self::period_start_time(time_point, system_time);
+        match template {
+            Some(template) => template.format(system_time, index as u64),
+            None => Self::calc_split_file_path(base_path, time_point, system_time, index),
+        }
+    }
+}
+
+impl Rotator for RotatorTimePoint {
+    fn log(&self, record: &Record, string_buf: &StringBuf) -> Result<()> {
         let mut inner = self.inner.lock();
 
-        let mut file_path = None;
         let record_time = record.time();
         let should_rotate = record_time >= inner.rotation_time_point;
+        let should_split = !should_rotate
+            && self.max_size.map_or(false, |max_size| {
+                inner.current_size > 0 && inner.current_size + string_buf.len() as u64 > max_size
+            });
 
-        if should_rotate {
-            file_path = Some(Self::calc_file_path(
+        let mut rotated_file_path = None;
+        if should_rotate || should_split {
+            if should_rotate {
+                inner.period_time = record_time;
+                inner.split_index = 0;
+                inner.rotation_time_point =
+                    Self::next_rotation_time_point(self.time_point, record_time);
+            } else {
+                inner.split_index += 1;
+            }
+            let file_path = Self::calc_period_file_path(
                 &self.base_path,
+                self.template.as_ref(),
                 self.time_point,
-                record_time,
-            ));
-            inner.file = BufWriter::new(utils::open_file(file_path.as_ref().unwrap(), true)?);
-            inner.rotation_time_point =
-                Self::next_rotation_time_point(self.time_point, record_time);
+                inner.period_time,
+                inner.split_index,
+            );
+            // A split file is always new, never truncates it in case it exists.
+            inner.file = BufWriter::new(utils::open_file(&file_path, should_rotate)?);
+            inner.current_size = 0;
+            let previous = std::mem::replace(&mut inner.file_path, file_path);
+            if previous != inner.file_path {
+                rotated_file_path = Some(previous);
+            }
         }
 
         inner
             .file
             .write_all(string_buf.as_bytes())
             .map_err(Error::WriteRecord)?;
+        inner.current_size += string_buf.len() as u64;
+
+        // Compresses before applying the retention limits, so that removing the
+        // rotated file is queued after its compression.
+        if let (Some(compressor), Some(rotated_file_path)) = (&self.compressor, rotated_file_path) {
+            compressor.compress(rotated_file_path)?;
+        }
 
-        if should_rotate && inner.file_paths.is_some() {
-            self.push_new_remove_old(file_path.unwrap(), &mut inner)?;
+        if should_rotate || should_split {
+            self.remove_old_files(&inner.file_path)?;
         }
 
         Ok(())
@@ -677,28 +1421,670 @@ impl Rotator for RotatorTimePoint {
     }
 }
 
-impl TimePoint {
+impl Compression {
     #[must_use]
-    fn delta_std(&self) -> Duration {
-        const SECONDS_PER_MINUTE: u64 = 60;
-        const SECONDS_PER_HOUR: u64 = 60 * 60;
-        const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
-        const HOUR_1: Duration = Duration::from_secs(SECONDS_PER_HOUR);
-        const DAY_1: Duration = Duration::from_secs(SECONDS_PER_DAY);
+    fn extension(&self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            #[cfg(feature = "gzip")]
+            Self::Gzip => Some("gz"),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => Some("zst"),
+        }
+    }
+
+    #[must_use]
+    fn compressed_path(&self, path: &Path) -> PathBuf {
+        let mut compressed = path.as_os_str().to_owned();
+        if let Some(extension) = self.extension() {
+            compressed.push(".");
+            compressed.push(extension);
+        }
+        PathBuf::from(compressed)
+    }
+
+    fn compress_file(&self, path: &Path) -> Result<()> {
+        let compressed = self.compressed_path(path);
+        if compressed == path {
+            return Ok(());
+        }
+
+        // Writes to a temporary file first, so that an interrupted compression
+        // never leaves a truncated file with the final name.
+        let mut temp = compressed.clone().into_os_string();
+        temp.push(".tmp");
+        let temp = PathBuf::from(temp);
 
         match self {
-            Self::Daily { .. } => DAY_1,
-            Self::Hourly { .. } => HOUR_1,
-            Self::Duration {
-                hours,
-                minutes,
-                seconds,
-            } => Duration::from_secs(
-                (*hours as u64) * SECONDS_PER_HOUR + (*minutes as u64) * SECONDS_PER_MINUTE + *seconds as u64,
-            ),
+            Self::None => {}
+            #[cfg(feature = "gzip")]
+            Self::Gzip => {
+                gzip_file(path, &temp).map_err(|err| {
+                    let _ = fs::remove_file(&temp);
+                    Error::CompressFile(err)
+                })?;
+            }
+            #[cfg(feature = "zstd")]
+            Self::Zstd => {
+                zstd_file(path, &temp).map_err(|err| {
+                    let _ = fs::remove_file(&temp);
+                    Error::CompressFile(err)
+                })?;
+            }
+        }
+
+        fs::rename(temp, compressed).map_err(Error::RenameFile)?;
+        fs::remove_file(path).map_err(Error::RemoveFile)
+    }
+}
+
+#[cfg(feature = "gzip")]
+fn gzip_file(src: &Path, dst: &Path) -> std::io::Result<()> {
+    let mut src = File::open(src)?;
+    let mut encoder = libflate::gzip::Encoder::new(BufWriter::new(File::create(dst)?))?;
+    std::io::copy(&mut src, &mut encoder)?;
+    encoder.finish().into_result()?.flush()
+}
+
+#[cfg(feature = "zstd")]
+fn zstd_file(src: &Path, dst: &Path) -> std::io::Result<()> {
+    let mut dst = BufWriter::new(File::create(dst)?);
+    // Level 0 means the default level of zstd.
+    zstd::stream::copy_encode(File::open(src)?, &mut dst, 0)?;
+    dst.flush()
+}
+
+impl Compressor {
+    #[must_use]
+    fn new(compression: Compression, common_impl: Arc<helper::CommonImpl>) -> Option<Self> {
+        if compression == Compression::None {
+            return None;
+        }
+
+        let (sender, receiver) = mpsc::channel::<CompressorJob>();
+        let shared = Arc::new((
+            Mutex::new(CompressorState {
+                sender: Some(sender),
+                pending: 0,
+            }),
+            Condvar::new(),
+        ));
+        let worker = {
+            let shared = shared.clone();
+            thread::spawn(move || {
+                let _exit_guard = WorkerExitGuard(shared.clone());
+                for job in receiver {
+                    if let Err(err) = job() {
+                        common_impl.non_returnable_error("RotatingFileSink", err);
+                    }
+                    let (state, cond) = &*shared;
+                    state.lock_expect().pending -= 1;
+                    cond.notify_all();
+                }
+            })
+        };
+
+        Some(Self {
+            compression,
+            shared,
+            worker: Some(worker),
+        })
+    }
+
+    fn compress(&self, path: PathBuf) -> Result<()> {
+        let compression = self.compression;
+        self.run(Box::new(move || compression.compress_file(&path)))
+    }
+
+    // Runs `job` on the worker after the jobs queued before it. Renaming and
+    // removing rotated files go through here too, so that they never race with
+    // a pending compression.
+    fn run(&self, job: CompressorJob) -> Result<()> {
+        let mut state = self.shared.0.lock_expect();
+        let job = match &state.sender {
+            Some(sender) => match sender.send(job) {
+                Ok(()) => {
+                    state.pending += 1;
+                    return Ok(());
+                }
+                Err(mpsc::SendError(job)) => job,
+            },
+            None => job,
+        };
+        drop(state);
+
+        // The worker has stopped unexpectedly, runs on the current thread.
+        job()
+    }
+
+    // Blocks until all jobs queued are finished.
+    #[cfg(all(test, feature = "gzip"))]
+    fn wait_idle(&self) {
+        let (state, cond) = &*self.shared;
+        let mut state = state.lock_expect();
+        while state.pending != 0 {
+            state = cond.wait(state).expect("lock is poisoned");
+        }
+    }
+}
+
+impl Drop for Compressor {
+    fn drop(&mut self) {
+        // Finishes the pending jobs.
+        self.shared.0.lock_expect().sender = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for WorkerExitGuard {
+    fn drop(&mut self) {
+        let (state, cond) = &*self.0;
+        let mut state = state.lock_expect();
+        // Jobs queued but not run are dropped, their files are left as is.
+        state.sender = None;
+        state.pending = 0;
+        cond.notify_all();
+    }
+}
+
+impl Retention {
+    #[must_use]
+    fn is_unlimited(&self) -> bool {
+        self.max_files == 0 && self.max_age.is_none() && self.max_total_size.is_none()
+    }
+
+    // Returns the oldest files exceeding the limits. `rotated_files` are sorted
+    // from the oldest to the newest, the active file is not included but
+    // counted by `max_files`.
+    fn old_files(
+        &self,
+        rotated_files: &[(RotatedFileKey, PathBuf)],
+        compression: Option<Compression>,
+    ) -> Result<Vec<PathBuf>> {
+        let mut remove_count = if self.max_files > 0 {
+            (rotated_files.len() + 1).saturating_sub(self.max_files)
+        } else {
+            0
+        };
+
+        if self.max_age.is_some() || self.max_total_size.is_some() {
+            let now = SystemTime::now();
+            let mut total_size = 0;
+
+            for (index, (_, path)) in rotated_files.iter().enumerate().rev() {
+                if index < remove_count {
+                    break;
+                }
+
+                let mut size = 0;
+                let mut modified = None;
+                for variant in file_variants(path, compression) {
+                    let metadata = match fs::metadata(&variant) {
+                        Ok(metadata) => metadata,
+                        // The file may have been compressed.
+                        Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                        Err(err) => return Err(Error::QueryFileMetadata(err)),
+                    };
+                    size += metadata.len();
+                    let time = metadata.modified().map_err(Error::QueryFileMetadata)?;
+                    modified = modified.max(Some(time));
+                }
+                total_size += size;
+
+                let expired = match (self.max_age, modified) {
+                    (Some(max_age), Some(modified)) => now
+                        .duration_since(modified)
+                        .map_or(false, |age| age > max_age),
+                    _ => false,
+                };
+                let oversized = self
+                    .max_total_size
+                    .map_or(false, |max_total_size| total_size > max_total_size);
+                if expired || oversized {
+                    // This file and all older files are removed.
+                    remove_count = index + 1;
+                    break;
+                }
+            }
+        }
+
+        Ok(rotated_files[..remove_count]
+            .iter()
+            .map(|(_, path)| path.clone())
+            .collect())
+    }
+}
+
+// Returns the possible paths of a rotated file, uncompressed and compressed.
+#[must_use]
+fn file_variants(path: &Path, compression: Option<Compression>) -> Vec<PathBuf> {
+    let mut variants = vec![path.to_owned()];
+    if let Some(compression) = compression {
+        variants.push(compression.compressed_path(path));
+    }
+    variants
+}
+
+// Removes the rotated files `paths` in whichever variants exist.
+fn remove_files(paths: &[PathBuf], compression: Option<Compression>) -> Result<()> {
+    for path in paths {
+        for variant in file_variants(path, compression) {
+            if variant.exists() {
+                fs::remove_file(variant).map_err(Error::RemoveFile)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Removes the rotated files `paths`. With a compressor, the removal is queued
+// on its worker, since the files may be being compressed.
+fn remove_files_in_order(paths: Vec<PathBuf>, compressor: Option<&Compressor>) -> Result<()> {
+    match compressor {
+        _ if paths.is_empty() => Ok(()),
+        Some(compressor) => {
+            let compression = compressor.compression;
+            compressor.run(Box::new(move || remove_files(&paths, Some(compression))))
+        }
+        None => remove_files(&paths, None),
+    }
+}
+
+// Finds the rotated files in the directory of `base_path` whose names can be
+// parsed by `parse`, sorted from the oldest to the newest.
+//
+// The compressed extension is stripped before parsing, and the uncompressed
+// paths are returned.
+fn find_rotated_files<F>(
+    base_path: &Path,
+    compression: Option<Compression>,
+    parse: F,
+) -> Result<Vec<(RotatedFileKey, PathBuf)>>
+where
+    F: Fn(&str) -> Option<RotatedFileKey>,
+{
+    let dir = match base_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let compressed_extension = compression
+        .and_then(|compression| compression.extension())
+        .map(|extension| format!(".{}", extension));
+
+    let mut files = vec![];
+    for entry in fs::read_dir(dir).map_err(Error::ReadDirectory)? {
+        let file_name = entry.map_err(Error::ReadDirectory)?.file_name();
+        let file_name = match file_name.to_str() {
+            Some(file_name) => file_name,
+            None => continue,
+        };
+        let file_name = compressed_extension
+            .as_ref()
+            .and_then(|extension| file_name.strip_suffix(extension.as_str()))
+            .unwrap_or(file_name);
+        if let Some(key) = parse(file_name) {
+            files.push((key, base_path.with_file_name(file_name)));
+        }
+    }
+    files.sort();
+    // A file may exist both uncompressed and compressed during compression.
+    files.dedup();
+    Ok(files)
+}
+
+// Converts a local date and time to `DateTime<Local>`. If the time is skipped
+// by a DST transition, the first valid time after it is used. If the time is
+// ambiguous, the earlier one is used.
+#[must_use]
+fn local_date_time(naive: NaiveDateTime) -> DateTime<Local> {
+    (0..=24 * 60)
+        .find_map(|minutes| {
+            match Local.from_local_datetime(&(naive + chrono::Duration::minutes(minutes))) {
+                LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => Some(time),
+                LocalResult::None => None,
+            }
+        })
+        .unwrap()
+}
+
+#[must_use]
+fn parse_number(digits: &str) -> Option<u64> {
+    if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+        digits.parse().ok()
+    } else {
+        None
+    }
+}
+
+// Parses the digits of `input` as a number if it matches `pattern`, where `0`
+// matches a digit and other characters match themselves, e.g. `2022-03-23`
+// matches `0000-00-00` and `20220323` is returned.
+#[must_use]
+fn parse_digits(input: &str, pattern: &str) -> Option<u64> {
+    if input.len() != pattern.len() {
+        return None;
+    }
+    let mut digits = String::with_capacity(input.len());
+    for (b, p) in input.bytes().zip(pattern.bytes()) {
+        match p {
+            b'0' if b.is_ascii_digit() => digits.push(b as char),
+            p if p == b && p != b'0' => {}
+            _ => return None,
+        }
+    }
+    parse_number(&digits)
+}
+
+// A parsed file name template, see
+// `RotatingFileSinkBuilder::file_name_template`.
+#[derive(Clone, Debug)]
+struct FileNameTemplate {
+    // The leading directories without specifiers, where files are scanned.
+    root: PathBuf,
+    // The remaining path components, the last one is the file name.
+    components: Vec<Vec<TemplateToken>>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum TemplateToken {
+    Literal(String),
+    Field(TemplateField),
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum TemplateField {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    Index,
+}
+
+impl FileNameTemplate {
+    fn parse(template: &str) -> StdResult<Self, String> {
+        let mut root = PathBuf::new();
+        let mut components = vec![];
+
+        for component in Path::new(template).components() {
+            let component = component.as_os_str().to_str().unwrap();
+            if components.is_empty() && !component.contains('%') {
+                root.push(component);
+                continue;
+            }
+            if component == ".." {
+                return Err("'..' is not allowed after specifiers".to_string());
+            }
+            components.push(Self::parse_component(component)?);
+        }
+
+        let has_field = components
+            .iter()
+            .flatten()
+            .any(|token| matches!(token, TemplateToken::Field(_)));
+        if !has_field {
+            return Err(format!("no specifiers in template '{}'", template));
+        }
+        Ok(Self { root, components })
+    }
+
+    fn parse_component(component: &str) -> StdResult<Vec<TemplateToken>, String> {
+        let mut tokens = vec![];
+        let mut literal = String::new();
+        let mut chars = component.chars();
+
+        while let Some(ch) = chars.next() {
+            if ch != '%' {
+                literal.push(ch);
+                continue;
+            }
+            let field = match chars.next() {
+                Some('%') => {
+                    literal.push('%');
+                    continue;
+                }
+                Some('Y') => TemplateField::Year,
+                Some('m') => TemplateField::Month,
+                Some('d') => TemplateField::Day,
+                Some('H') => TemplateField::Hour,
+                Some('M') => TemplateField::Minute,
+                Some('S') => TemplateField::Second,
+                Some('i') => TemplateField::Index,
+                Some(ch) => return Err(format!("unknown specifier '%{}'", ch)),
+                None => return Err("incomplete specifier '%' at the end".to_string()),
+            };
+            if !literal.is_empty() {
+                tokens.push(TemplateToken::Literal(std::mem::take(&mut literal)));
+            }
+            tokens.push(TemplateToken::Field(field));
+        }
+        if !literal.is_empty() {
+            tokens.push(TemplateToken::Literal(literal));
+        }
+        Ok(tokens)
+    }
+
+    fn validate(&self, rotation_policy: &RotationPolicy) -> StdResult<(), String> {
+        use TemplateField::*;
+
+        let required: &[TemplateField] = match rotation_policy {
+            RotationPolicy::FileSize(_) => &[Index],
+            RotationPolicy::Daily { .. } => &[Year, Month, Day],
+            RotationPolicy::Hourly => &[Year, Month, Day, Hour],
+            RotationPolicy::Duration { .. } => &[Year, Month, Day, Hour, Minute, Second],
+            RotationPolicy::Weekly { .. } => &[Year, Month, Day],
+            RotationPolicy::Monthly { .. } => &[Year, Month],
+            RotationPolicy::DailyAndFileSize { .. } => &[Year, Month, Day, Index],
+            RotationPolicy::DurationAndFileSize { .. } => {
+                &[Year, Month, Day, Hour, Minute, Second, Index]
+            }
+        };
+        let missing = required
+            .iter()
+            .filter(|field| !self.contains(**field))
+            .map(|field| field.specifier())
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(format!(
+                "specifiers {} are required by the rotation policy",
+                missing.join(", ")
+            ));
+        }
+        Ok(())
+    }
+
+    #[must_use]
+    fn contains(&self, field: TemplateField) -> bool {
+        self.components
+            .iter()
+            .flatten()
+            .any(|token| *token == TemplateToken::Field(field))
+    }
+
+    #[must_use]
+    fn format(&self, time: SystemTime, index: u64) -> PathBuf {
+        let local_time: DateTime<Local> = time.into();
+
+        let mut path = self.root.clone();
+        for component in &self.components {
+            let mut name = String::new();
+            for token in component {
+                match token {
+                    TemplateToken::Literal(literal) => name.push_str(literal),
+                    TemplateToken::Field(field) => {
+                        let value = match field {
+                            TemplateField::Year => local_time.year() as u32,
+                            TemplateField::Month => local_time.month(),
+                            TemplateField::Day => local_time.day(),
+                            TemplateField::Hour => local_time.hour(),
+                            TemplateField::Minute => local_time.minute(),
+                            TemplateField::Second => local_time.second(),
+                            TemplateField::Index => {
+                                name.push_str(&index.to_string());
+                                continue;
+                            }
+                        };
+                        name.push_str(&format!("{:0width$}", value, width = field.width()));
+                    }
+                }
+            }
+            path.push(name);
+        }
+        path
+    }
+
+    // Finds the files matching the template, sorted from the oldest to the
+    // newest.
+    //
+    // The compressed extension is stripped before matching, and the
+    // uncompressed paths are returned.
+    fn find_files(
+        &self,
+        compression: Option<Compression>,
+    ) -> Result<Vec<(RotatedFileKey, PathBuf)>> {
+        let compressed_extension = compression
+            .and_then(|compression| compression.extension())
+            .map(|extension| format!(".{}", extension));
+
+        let mut candidates = vec![(self.root.clone(), TemplateValues::default())];
+        let mut files = vec![];
+
+        for (depth, component) in self.components.iter().enumerate() {
+            let is_file_name = depth + 1 == self.components.len();
+            let mut next = vec![];
+
+            for (dir, values) in candidates {
+                let read_dir = if dir.as_os_str().is_empty() {
+                    fs::read_dir(".")
+                } else {
+                    fs::read_dir(&dir)
+                };
+                let entries = match read_dir {
+                    Ok(entries) => entries,
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                    Err(err) => return Err(Error::ReadDirectory(err)),
+                };
+                for entry in entries {
+                    let entry = entry.map_err(Error::ReadDirectory)?;
+                    let name = entry.file_name();
+                    let mut name = match name.to_str() {
+                        Some(name) => name,
+                        None => continue,
+                    };
+                    if is_file_name {
+                        name = compressed_extension
+                            .as_ref()
+                            .and_then(|extension| name.strip_suffix(extension.as_str()))
+                            .unwrap_or(name);
+                    }
+                    let mut values = values;
+                    if !values.match_tokens(component, name) {
+                        continue;
+                    }
+                    if is_file_name {
+                        files.push((values.key(), dir.join(name)));
+                    } else if entry.path().is_dir() {
+                        next.push((dir.join(name), values));
+                    }
+                }
+            }
+            candidates = next;
+        }
+
+        files.sort();
+        // A file may exist both uncompressed and compressed during compression.
+        files.dedup();
+        Ok(files)
+    }
+}
+
+impl TemplateField {
+    #[must_use]
+    fn specifier(&self) -> &'static str {
+        match self {
+            Self::Year => "%Y",
+            Self::Month => "%m",
+            Self::Day => "%d",
+            Self::Hour => "%H",
+            Self::Minute => "%M",
+            Self::Second => "%S",
+            Self::Index => "%i",
+        }
+    }
+
+    // The number of digits of the field, `0` means variable.
+    #[must_use]
+    fn width(&self) -> usize {
+        match self {
+            Self::Year => 4,
+            Self::Index => 0,
+            _ => 2,
         }
     }
+}
+
+// Values of the fields matched from file names.
+#[derive(Copy, Clone, Default)]
+struct TemplateValues([Option<u64>; 7]);
+
+impl TemplateValues {
+    // Matches `input` against `tokens` and records the values of fields,
+    // returns `false` if not matched.
+    #[must_use]
+    fn match_tokens(&mut self, tokens: &[TemplateToken], input: &str) -> bool {
+        let (token, rest_tokens) = match tokens.split_first() {
+            Some(split) => split,
+            None => return input.is_empty(),
+        };
+
+        match token {
+            TemplateToken::Literal(literal) => match input.strip_prefix(literal.as_str()) {
+                Some(rest) => self.match_tokens(rest_tokens, rest),
+                None => false,
+            },
+            TemplateToken::Field(field) => {
+                let digits = input.bytes().take_while(|b| b.is_ascii_digit()).count();
+                let lengths = match field.width() {
+                    0 => (1..=digits).rev().collect::<Vec<_>>(),
+                    width if width <= digits => vec![width],
+                    _ => vec![],
+                };
+                for len in lengths {
+                    let value = match input[..len].parse::<u64>() {
+                        Ok(value) => value,
+                        Err(_) => continue,
+                    };
+                    // The same field may appear multiple times.
+                    if self.0[*field as usize].map_or(false, |existing| existing != value) {
+                        continue;
+                    }
+                    let mut values = *self;
+                    values.0[*field as usize] = Some(value);
+                    if values.match_tokens(rest_tokens, &input[len..]) {
+                        *self = values;
+                        return true;
+                    }
+                }
+                false
+            }
+        }
+    }
+
+    // The time fields are combined as the first element, so that files are
+    // sorted by time and then index.
+    #[must_use]
+    fn key(&self) -> RotatedFileKey {
+        let [year, month, day, hour, minute, second, index] = self.0.map(|v| v.unwrap_or(0));
+        let time =
+            ((((year * 100 + month) * 100 + day) * 100 + hour) * 100 + minute) * 100 + second;
+        (time, index)
+    }
+}
 
+impl TimePoint {
     #[must_use]
     fn delta_chrono(&self) -> chrono::Duration {
         match self {
@@ -711,6 +2097,8 @@ impl TimePoint {
             } => chrono::Duration::hours(*hours as i64)
                 + chrono::Duration::minutes(*minutes as i64)
                 + chrono::Duration::seconds(*seconds as i64),
+            // Calculated by `RotatorTimePoint::calendar_time_point` instead.
+            Self::Weekly { .. } | Self::Monthly { .. } => unreachable!(),
         }
     }
 }
@@ -732,10 +2120,14 @@ impl<ArgBP, ArgRP> RotatingFileSinkBuilder<ArgBP, ArgRP> {
     /// - `/path/to/base_file_2.log`
     /// - `/path/to/base_file_2022-03-23.log`
     /// - `/path/to/base_file_2022-03-24.log`
+    /// - `/path/to/base_file_2022-03-24.1.log`
     /// - `/path/to/base_file_2022-03-23_03.log`
     /// - `/path/to/base_file_2022-03-23_04.log`
+    /// - `/path/to/base_file_2022-03.log`
     ///
-    /// This parameter is **required**.
+    /// This parameter or [`file_name_template`] is **required**.
+    ///
+    /// [`file_name_template`]: RotatingFileSinkBuilder::file_name_template
     #[must_use]
     pub fn base_path<P>(self, base_path: P) -> RotatingFileSinkBuilder<PathBuf, ArgRP>
     where
@@ -746,7 +2138,82 @@ impl<ArgBP, ArgRP> RotatingFileSinkBuilder<ArgBP, ArgRP> {
             base_path: base_path.into(),
             rotation_policy: self.rotation_policy,
             max_files: self.max_files,
+            max_age: self.max_age,
+            max_total_size: self.max_total_size,
+            rotate_on_open: self.rotate_on_open,
+            compression: self.compression,
+            file_size_naming: self.file_size_naming,
+            file_name_template: None,
+        }
+    }
+
+    /// Specifies a template of the log file paths, instead of the
+    /// [`base_path`].
+    ///
+    /// Each file is written with the path generated by the template, files are
+    /// never renamed on rotation. Directories in the path are created as
+    /// needed, so the template can group files into date-based directories.
+    /// Time values are in local time.
+    ///
+    /// | Specifier | Description                          | Example |
+    /// |-----------|--------------------------------------|---------|
+    /// | `%Y`      | Year with 4 digits                   | `2022`  |
+    /// | `%m`      | Month with 2 digits                  | `03`    |
+    /// | `%d`      | Day of the month with 2 digits       | `23`    |
+    /// | `%H`      | Hour (24-hour clock) with 2 digits   | `04`    |
+    /// | `%M`      | Minute with 2 digits                 | `05`    |
+    /// | `%S`      | Second with 2 digits                 | `06`    |
+    /// | `%i`      | Index of the file, starting from `0` | `7`     |
+    /// | `%%`      | A literal `%`                        | `%`     |
+    ///
+    /// The template must contain the specifiers required by the rotation
+    /// policy, so that each file has a distinct path:
+    ///
+    /// | Rotation Policy                         | Required Specifiers                |
+    /// |-----------------------------------------|------------------------------------|
+    /// | [`RotationPolicy::FileSize`]            | `%i`                               |
+    /// | [`RotationPolicy::Daily`]               | `%Y` `%m` `%d`                     |
+    /// | [`RotationPolicy::Hourly`]              | `%Y` `%m` `%d` `%H`                |
+    /// | [`RotationPolicy::Duration`]            | `%Y` `%m` `%d` `%H` `%M` `%S`      |
+    /// | [`RotationPolicy::Weekly`]              | `%Y` `%m` `%d`                     |
+    /// | [`RotationPolicy::Monthly`]             | `%Y` `%m`                          |
+    /// | [`RotationPolicy::DailyAndFileSize`]    | `%Y` `%m` `%d` `%i`                |
+    /// | [`RotationPolicy::DurationAndFileSize`] | `%Y` `%m` `%d` `%H` `%M` `%S` `%i` |
+    ///
+    /// Specifiers can only be used after the last `..` component. Existing
+    /// files are found by matching the template, for the retention limits
+    /// and resuming writing.
+    ///
+    /// For example, with template `logs/%Y/%m/app-%d.%i.log` and
+    /// [`RotationPolicy::DailyAndFileSize`], the file paths may look like the
+    /// following:
+    ///
+    /// - `logs/2022/03/app-31.0.log`
+    /// - `logs/2022/03/app-31.1.log`
+    /// - `logs/2022/04/app-01.0.log`
+    ///
+    /// The template is validated when building the sink.
+    ///
+    /// This parameter or [`base_path`] is **required**.
+    ///
+    /// [`base_path`]: RotatingFileSinkBuilder::base_path
+    #[must_use]
+    pub fn file_name_template<S>(self, template: S) -> RotatingFileSinkBuilder<PathBuf, ArgRP>
+    where
+        S: Into<String>,
+    {
+        let template = template.into();
+        RotatingFileSinkBuilder {
+            common_builder_impl: self.common_builder_impl,
+            base_path: PathBuf::from(&template),
+            rotation_policy: self.rotation_policy,
+            max_files: self.max_files,
+            max_age: self.max_age,
+            max_total_size: self.max_total_size,
             rotate_on_open: self.rotate_on_open,
+            compression: self.compression,
+            file_size_naming: self.file_size_naming,
+            file_name_template: Some(template),
         }
     }
 
@@ -763,7 +2230,12 @@ impl<ArgBP, ArgRP> RotatingFileSinkBuilder<ArgBP, ArgRP> {
             base_path: self.base_path,
             rotation_policy,
             max_files: self.max_files,
+            max_age: self.max_age,
+            max_total_size: self.max_total_size,
             rotate_on_open: self.rotate_on_open,
+            compression: self.compression,
+            file_size_naming: self.file_size_naming,
+            file_name_template: self.file_name_template,
         }
     }
 
@@ -774,6 +2246,10 @@ impl<ArgBP, ArgRP> RotatingFileSinkBuilder<ArgBP, ArgRP> {
     ///
     /// Specify `0` for no limit.
     ///
+    /// Existing files are found by scanning the directory for file names of
+    /// the rotation policy, the limit is also applied to them when the sink is
+    /// built.
+    ///
     /// This parameter is **optional**.
     #[must_use]
     pub fn max_files(mut self, max_files: usize) -> Self {
@@ -781,13 +2257,42 @@ impl<ArgBP, ArgRP> RotatingFileSinkBuilder<ArgBP, ArgRP> {
         self
     }
 
+    /// Specifies the maximum age of rotated files.
+    ///
+    /// Rotated files last modified earlier than the given duration ago, and
+    /// all older files, will be deleted on the next rotation and when the sink
+    /// is built.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn max_age(mut self, max_age: Option<Duration>) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Specifies the maximum total size (in bytes) of rotated files.
+    ///
+    /// If the total size of rotated files exceeds this parameter, the oldest
+    /// files will be deleted on the next rotation and when the sink is built.
+    /// The file being written is not counted.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn max_total_size(mut self, max_total_size: Option<u64>) -> Self {
+        self.max_total_size = max_total_size;
+        self
+    }
+
     /// Specifies whether to rotate files once when constructing
     /// `RotatingFileSink`.
     ///
-    /// For the [`RotationPolicy::Daily`], [`RotationPolicy::Hourly`], and [`RotationPolicy::Duration`]
-    /// rotation policies, it may truncate the contents of the existing file if
-    /// the parameter is `true`, since the file name is a time point and not an
-    /// index.
+    /// For the time-based rotation policies, i.e. [`RotationPolicy::Daily`],
+    /// [`RotationPolicy::Hourly`], [`RotationPolicy::Duration`],
+    /// [`RotationPolicy::Weekly`], [`RotationPolicy::Monthly`],
+    /// [`RotationPolicy::DailyAndFileSize`] and
+    /// [`RotationPolicy::DurationAndFileSize`], it may truncate the contents of
+    /// the existing file if the parameter is `true`, since the file name is a
+    /// time point and not an index.
     ///
     /// This parameter is **optional**.
     #[must_use]
@@ -796,6 +2301,38 @@ impl<ArgBP, ArgRP> RotatingFileSinkBuilder<ArgBP, ArgRP> {
         self
     }
 
+    /// Specifies the compression of rotated files.
+    ///
+    /// Rotated files are compressed on a background thread. The compressed
+    /// files are still counted and removed by [`max_files`] and other retention
+    /// limits. Shifting and removing rotated files are done on the same thread
+    /// after the pending compressions, so logging never waits for them. The
+    /// sink waits for the pending work to finish when it's dropped.
+    ///
+    /// This parameter is **optional**.
+    ///
+    /// [`max_files`]: RotatingFileSinkBuilder::max_files
+    #[must_use]
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Specifies the naming scheme of rotated files for
+    /// [`RotationPolicy::FileSize`].
+    ///
+    /// It's invalid to specify a naming other than [`FileSizeNaming::Index`]
+    /// for other rotation policies, or with a [`file_name_template`].
+    ///
+    /// This parameter is **optional**.
+    ///
+    /// [`file_name_template`]: RotatingFileSinkBuilder::file_name_template
+    #[must_use]
+    pub fn file_size_naming(mut self, naming: FileSizeNaming) -> Self {
+        self.file_size_naming = naming;
+        self
+    }
+
     helper::common_impl!(@SinkBuilder: common_builder_impl);
 }
 
@@ -803,7 +2340,7 @@ impl<ArgRP> RotatingFileSinkBuilder<(), ArgRP> {
     #[doc(hidden)]
     #[deprecated(note = "\n\n\
         builder compile-time error:\n\
-        - missing required parameter `base_path`\n\n\
+        - missing required parameter `base_path` or `file_name_template`\n\n\
     ")]
     pub fn build(self, _: Infallible) {}
 }
@@ -822,40 +2359,89 @@ impl RotatingFileSinkBuilder<PathBuf, RotationPolicy> {
     ///
     /// # Error
     ///
-    /// If the argument `rotation_policy` is invalid, or an error occurs opening
+    /// If the argument `rotation_policy` or `file_name_template` is invalid,
+    /// [`Error::InvalidArgument`] will be returned. If an error occurs opening
     /// the file, [`Error::CreateDirectory`] or [`Error::OpenFile`] will be
     /// returned.
     pub fn build(self) -> Result<RotatingFileSink> {
         self.build_with_initial_time(None)
     }
 
-    fn build_with_initial_time(self, override_now: Option<SystemTime>) -> Result<RotatingFileSink> {
+    // Checks the arguments without creating any file.
+    #[cfg(feature = "config")]
+    pub(crate) fn validate(&self) -> Result<()> {
+        self.parse_file_name_template().map(|_| ())
+    }
+
+    fn parse_file_name_template(&self) -> Result<Option<FileNameTemplate>> {
         self.rotation_policy
             .validate()
             .map_err(|err| Error::InvalidArgument(InvalidArgumentError::RotationPolicy(err)))?;
+        if self.file_size_naming != FileSizeNaming::Index
+            && !matches!(self.rotation_policy, RotationPolicy::FileSize(_))
+        {
+            return Err(Error::InvalidArgument(
+                InvalidArgumentError::RotationPolicy(
+                    "file size naming is only supported by policy 'file size'".to_string(),
+                ),
+            ));
+        }
+        self.file_name_template
+            .as_deref()
+            .map(|template| {
+                let template = FileNameTemplate::parse(template)?;
+                template.validate(&self.rotation_policy)?;
+                if self.file_size_naming != FileSizeNaming::Index {
+                    return Err("cannot be used with file size naming".to_string());
+                }
+                Ok(template)
+            })
+            .transpose()
+            .map_err(|err| Error::InvalidArgument(InvalidArgumentError::FileNameTemplate(err)))
+    }
+
+    fn build_with_initial_time(self, override_now: Option<SystemTime>) -> Result<RotatingFileSink> {
+        let template = self.parse_file_name_template()?;
+
+        let common_impl = Arc::new(helper::CommonImpl::from_builder(self.common_builder_impl));
+        let compressor = Compressor::new(self.compression, common_impl.clone());
+        let retention = Retention {
+            max_files: self.max_files,
+            max_age: self.max_age,
+            max_total_size: self.max_total_size,
+        };
 
         let rotator = match self.rotation_policy {
             RotationPolicy::FileSize(max_size) => RotatorKind::FileSize(RotatorFileSize::new(
                 self.base_path,
                 max_size,
-                self.max_files,
+                retention,
                 self.rotate_on_open,
+                self.file_size_naming,
+                template,
+                compressor,
             )?),
             RotationPolicy::Daily { hour, minute } => {
                 RotatorKind::TimePoint(RotatorTimePoint::new(
                     override_now,
                     self.base_path,
                     TimePoint::Daily { hour, minute },
-                    self.max_files,
+                    None,
+                    retention,
                     self.rotate_on_open,
+                    template,
+                    compressor,
                 )?)
             }
             RotationPolicy::Hourly => RotatorKind::TimePoint(RotatorTimePoint::new(
                 override_now,
                 self.base_path,
                 TimePoint::Hourly,
-                self.max_files,
+                None,
+                retention,
                 self.rotate_on_open,
+                template,
+                compressor,
             )?),
             RotationPolicy::Duration {
                 hours,
@@ -869,13 +2455,79 @@ impl RotatingFileSinkBuilder<PathBuf, RotationPolicy> {
                     minutes,
                     seconds,
                 },
-                self.max_files,
+                None,
+                retention,
+                self.rotate_on_open,
+                template,
+                compressor,
+            )?),
+            RotationPolicy::Weekly {
+                weekday,
+                hour,
+                minute,
+            } => RotatorKind::TimePoint(RotatorTimePoint::new(
+                override_now,
+                self.base_path,
+                TimePoint::Weekly {
+                    weekday,
+                    hour,
+                    minute,
+                },
+                None,
+                retention,
+                self.rotate_on_open,
+                template,
+                compressor,
+            )?),
+            RotationPolicy::Monthly { day, hour, minute } => {
+                RotatorKind::TimePoint(RotatorTimePoint::new(
+                    override_now,
+                    self.base_path,
+                    TimePoint::Monthly { day, hour, minute },
+                    None,
+                    retention,
+                    self.rotate_on_open,
+                    template,
+                    compressor,
+                )?)
+            }
+            RotationPolicy::DailyAndFileSize {
+                hour,
+                minute,
+                max_size,
+            } => RotatorKind::TimePoint(RotatorTimePoint::new(
+                override_now,
+                self.base_path,
+                TimePoint::Daily { hour, minute },
+                Some(max_size),
+                retention,
+                self.rotate_on_open,
+                template,
+                compressor,
+            )?),
+            RotationPolicy::DurationAndFileSize {
+                hours,
+                minutes,
+                seconds,
+                max_size,
+            } => RotatorKind::TimePoint(RotatorTimePoint::new(
+                override_now,
+                self.base_path,
+                TimePoint::Duration {
+                    hours,
+                    minutes,
+                    seconds,
+                },
+                Some(max_size),
+                retention,
                 self.rotate_on_open,
+                template,
+                compressor,
             )?),
         };
 
         let res = RotatingFileSink {
-            common_impl: helper::CommonImpl::from_builder(self.common_builder_impl),
+            common_impl,
             rotator,
         };
 
@@ -896,6 +2548,21 @@ mod tests {
         path
     });
 
+    // Logs `payload` as if it's logged at `time`, and flushes it to the file.
+    #[track_caller]
+    fn log_at(sink: &RotatingFileSink, time: SystemTime, payload: &str) {
+        let mut record = Record::new(Level::Info, payload);
+        record.set_time(time);
+        sink.log(&record).unwrap();
+        sink.flush().unwrap();
+    }
+
+    // Reads a log file, `None` if it doesn't exist.
+    #[must_use]
+    fn read_log(path: impl AsRef<Path>) -> Option<String> {
+        fs::read_to_string(path).ok()
+    }
+
     mod policy_file_size {
         use super::*;
 
@@ -1112,6 +2779,167 @@ mod tests {
                 )
             );
         }
+
+        #[track_caller]
+        fn read_dir_sorted(dir: impl AsRef<Path>) -> Vec<(String, String)> {
+            let mut files = fs::read_dir(dir)
+                .unwrap()
+                .map(|entry| {
+                    let path = entry.unwrap().path();
+                    (
+                        path.file_name().unwrap().to_string_lossy().into_owned(),
+                        fs::read_to_string(&path).unwrap(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            files.sort();
+            files
+        }
+
+        #[test]
+        fn sequence_naming() {
+            let dir = LOGS_PATH.join("sequence_naming");
+            _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+
+            let build = || {
+                RotatingFileSink::builder()
+                    .base_path(dir.join("app.log"))
+                    .rotation_policy(RotationPolicy::FileSize(4))
+                    .max_files(3)
+                    .file_size_naming(FileSizeNaming::Sequence)
+                    .formatter(Box::new(NoModFormatter::new()))
+                    .build()
+                    .unwrap()
+            };
+
+            {
+                let sink = build();
+                for payload in ["aaaa", "bbbb", "cccc"] {
+                    sink.log(&Record::new(Level::Info, payload)).unwrap();
+                }
+            }
+            assert_eq!(
+                read_dir_sorted(&dir),
+                [
+                    ("app.1.log".to_string(), "aaaa".to_string()),
+                    ("app.2.log".to_string(), "bbbb".to_string()),
+                    ("app.log".to_string(), "cccc".to_string()),
+                ]
+            );
+
+            // The sequence number is recovered after restarts.
+            {
+                let sink = build();
+                sink.log(&Record::new(Level::Info, "dddd")).unwrap();
+            }
+            assert_eq!(
+                read_dir_sorted(&dir),
+                [
+                    ("app.2.log".to_string(), "bbbb".to_string()),
+                    ("app.3.log".to_string(), "cccc".to_string()),
+                    ("app.log".to_string(), "dddd".to_string()),
+                ]
+            );
+        }
+
+        #[test]
+        fn timestamp_naming() {
+            let dir = LOGS_PATH.join("timestamp_naming");
+            _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+
+            // Files not matching the naming scheme are kept.
+            fs::write(dir.join("app_unrelated.log"), "").unwrap();
+            fs::write(dir.join("app.1.log"), "").unwrap();
+
+            let sink = RotatingFileSink::builder()
+                .base_path(dir.join("app.log"))
+                .rotation_policy(RotationPolicy::FileSize(4))
+                .max_files(3)
+                .file_size_naming(FileSizeNaming::Timestamp)
+                .formatter(Box::new(NoModFormatter::new()))
+                .build()
+                .unwrap();
+            for payload in ["aaaa", "bbbb", "cccc", "dddd"] {
+                sink.log(&Record::new(Level::Info, payload)).unwrap();
+            }
+            sink.flush().unwrap();
+
+            let rotator = match &sink.rotator {
+                RotatorKind::FileSize(rotator) => rotator,
+                RotatorKind::TimePoint(_) => unreachable!(),
+            };
+            let files = read_dir_sorted(&dir);
+            let rotated = files
+                .iter()
+                .filter(|(name, _)| rotator.parse_rotated_file_name(name).is_some())
+                .collect::<Vec<_>>();
+            assert_eq!(rotated.len(), 2);
+            let mut contents = rotated
+                .iter()
+                .map(|(_, content)| content.as_str())
+                .collect::<Vec<_>>();
+            contents.sort_unstable();
+            assert_eq!(contents, ["bbbb", "cccc"]);
+            assert_eq!(fs::read_to_string(dir.join("app.log")).unwrap(), "dddd");
+            assert!(dir.join("app_unrelated.log").exists());
+            assert!(dir.join("app.1.log").exists());
+        }
+
+        #[test]
+        fn parse_rotated_file_name() {
+            let rotator = |naming| {
+                let dir = LOGS_PATH.join("parse_rotated_file_name");
+                fs::create_dir_all(&dir).unwrap();
+                RotatorFileSize::new(
+                    dir.join("app.log"),
+                    4,
+                    Retention::default(),
+                    false,
+                    naming,
+                    None,
+                    None,
+                )
+                .unwrap()
+            };
+
+            let timestamp = rotator(FileSizeNaming::Timestamp);
+            assert_eq!(
+                timestamp.parse_rotated_file_name("app_2022-03-23_10-20-30.log"),
+                Some((20220323102030, 0))
+            );
+            assert_eq!(
+                timestamp.parse_rotated_file_name("app_2022-03-23_10-20-30.2.log"),
+                Some((20220323102030, 2))
+            );
+            assert_eq!(timestamp.parse_rotated_file_name("app.log"), None);
+            assert_eq!(
+                timestamp.parse_rotated_file_name("app_2022-03-23.log"),
+                None
+            );
+            assert_eq!(
+                timestamp.parse_rotated_file_name("app_2022-03-23_10-20-30.x.log"),
+                None
+            );
+
+            let index = rotator(FileSizeNaming::Index);
+            assert_eq!(
+                index.parse_rotated_file_name("app_2.log"),
+                Some((u64::MAX - 2, 0))
+            );
+            assert_eq!(index.parse_rotated_file_name("app_0.log"), None);
+            assert_eq!(index.parse_rotated_file_name("app.log"), None);
+
+            let sequence = rotator(FileSizeNaming::Sequence);
+            assert_eq!(
+                sequence.parse_rotated_file_name("app.12.log"),
+                Some((12, 0))
+            );
+            assert_eq!(sequence.parse_rotated_file_name("app.log"), None);
+            assert_eq!(sequence.parse_rotated_file_name("app.-1.log"), None);
+            assert_eq!(sequence.parse_rotated_file_name("app_1.log"), None);
+        }
     }
 
     mod policy_time_point {
@@ -1182,6 +3010,21 @@ mod tests {
                 .to_string()
             };
 
+            let calc_monthly = |base_path| {
+                RotatorTimePoint::calc_file_path(
+                    base_path,
+                    TimePoint::Monthly {
+                        day: 1,
+                        hour: 0,
+                        minute: 0,
+                    },
+                    system_time,
+                )
+                .to_str()
+                .unwrap()
+                .to_string()
+            };
+
             #[cfg(not(windows))]
             let run = || {
                 assert_eq!(calc_daily("/tmp/test.log"), "/tmp/test_2012-03-04.log");
@@ -1192,6 +3035,9 @@ mod tests {
 
                 assert_eq!(calc_duration("/tmp/test.log"), "/tmp/test_2012-03-04_05-06-07.log");
                 assert_eq!(calc_duration("/tmp/test"), "/tmp/test_2012-03-04_05-06-07");
+
+                assert_eq!(calc_monthly("/tmp/test.log"), "/tmp/test_2012-03.log");
+                assert_eq!(calc_monthly("/tmp/test"), "/tmp/test_2012-03");
             };
 
             #[cfg(windows)]
@@ -1205,6 +3051,9 @@ mod tests {
 
                 assert_eq!(calc_duration("D:\\tmp\\test.txt"), "D:\\tmp\\test_2012-03-04_05-06-07.txt");
                 assert_eq!(calc_duration("D:\\tmp\\test"), "D:\\tmp\\test_2012-03-04_05-06-07");
+
+                assert_eq!(calc_monthly("D:\\tmp\\test.txt"), "D:\\tmp\\test_2012-03.txt");
+                assert_eq!(calc_monthly("D:\\tmp\\test"), "D:\\tmp\\test_2012-03");
             };
 
             run();
@@ -1255,41 +3104,383 @@ mod tests {
                 let mut record = Record::new(Level::Info, "test log message");
                 let initial_time = record.time();
 
-                assert_files_count("hourly", 1);
-                assert_files_count("duration", 1);
-                assert_files_count("daily", 1);
+                assert_files_count("hourly", 1);
+                assert_files_count("duration", 1);
+                assert_files_count("daily", 1);
+
+                logger.log(&record);
+                assert_files_count("hourly", 1);
+                assert_files_count("duration", 1);
+                assert_files_count("daily", 1);
+
+                record.set_time(record.time() + HOUR_1 + SECOND_1);
+                logger.log(&record);
+                assert_files_count("hourly", 2);
+                assert_files_count("duration", 1);
+                assert_files_count("daily", 1);
+
+                record.set_time(record.time() + HOUR_1 + SECOND_1);
+                logger.log(&record);
+                assert_files_count("hourly", 3);
+                assert_files_count("duration", 2);
+                assert_files_count("daily", 1);
+
+                record.set_time(record.time() + SECOND_1);
+                logger.log(&record);
+                assert_files_count("hourly", 3);
+                assert_files_count("duration", 2);
+                assert_files_count("daily", 1);
+
+                record.set_time(initial_time + DAY_1 + SECOND_1);
+                logger.log(&record);
+                assert_files_count("hourly", 4);
+                assert_files_count("duration", 3);
+                assert_files_count("daily", 2);
+            }
+        }
+
+        #[test]
+        fn daily_and_file_size() {
+            let base_path = LOGS_PATH.join("split_daily.log");
+            let initial_time: SystemTime = Local
+                .with_ymd_and_hms(2024, 8, 29, 12, 0, 0)
+                .unwrap()
+                .into();
+            let path = |time, index| {
+                RotatorTimePoint::calc_split_file_path(
+                    &base_path,
+                    TimePoint::Daily { hour: 0, minute: 0 },
+                    time,
+                    index,
+                )
+            };
+            assert_eq!(
+                path(initial_time, 1).file_name().unwrap(),
+                "split_daily_2024-08-29.1.log"
+            );
+
+            let build = |initial_time| {
+                RotatingFileSink::builder()
+                    .base_path(&base_path)
+                    .rotation_policy(RotationPolicy::DailyAndFileSize {
+                        hour: 0,
+                        minute: 0,
+                        max_size: 8,
+                    })
+                    .max_files(3)
+                    .formatter(Box::new(NoModFormatter::new()))
+                    .build_with_initial_time(Some(initial_time))
+                    .unwrap()
+            };
+            let read = |time, index| read_log(path(time, index));
+
+            {
+                let sink = build(initial_time);
+                for _ in 0..3 {
+                    log_at(&sink, initial_time, "aaaa");
+                }
+                assert_eq!(read(initial_time, 0).as_deref(), Some("aaaaaaaa"));
+                assert_eq!(read(initial_time, 1).as_deref(), Some("aaaa"));
+
+                log_at(&sink, initial_time + DAY_1, "bbbb");
+                assert_eq!(read(initial_time, 0).as_deref(), Some("aaaaaaaa"));
+                assert_eq!(read(initial_time + DAY_1, 0).as_deref(), Some("bbbb"));
+            }
+
+            // Continues writing to the last file of the day after restarts.
+            {
+                let sink = build(initial_time + DAY_1);
+                log_at(&sink, initial_time + DAY_1, "cccc");
+                assert_eq!(read(initial_time + DAY_1, 0).as_deref(), Some("bbbbcccc"));
+
+                // `max_files` is applied across days and the split files.
+                log_at(&sink, initial_time + DAY_1, "dddd");
+                assert_eq!(read(initial_time, 0), None);
+                assert_eq!(read(initial_time, 1).as_deref(), Some("aaaa"));
+                assert_eq!(read(initial_time + DAY_1, 1).as_deref(), Some("dddd"));
+            }
+            {
+                let sink = build(initial_time + DAY_1);
+                log_at(&sink, initial_time + DAY_1, "eeeeeeee");
+                assert_eq!(read(initial_time, 1), None);
+                assert_eq!(read(initial_time + DAY_1, 0).as_deref(), Some("bbbbcccc"));
+                assert_eq!(read(initial_time + DAY_1, 1).as_deref(), Some("dddd"));
+                assert_eq!(read(initial_time + DAY_1, 2).as_deref(), Some("eeeeeeee"));
+            }
+            assert_files_count("split_daily", 3);
+        }
+
+        #[test]
+        fn daily_and_file_size_restart_with_gap() {
+            let dir = LOGS_PATH.join("split_daily_gap");
+            _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            let base_path = dir.join("app.log");
+
+            let initial_time: SystemTime = Local
+                .with_ymd_and_hms(2024, 8, 29, 12, 0, 0)
+                .unwrap()
+                .into();
+            let path = |index| {
+                RotatorTimePoint::calc_split_file_path(
+                    &base_path,
+                    TimePoint::Daily { hour: 0, minute: 0 },
+                    initial_time,
+                    index,
+                )
+            };
+            let read = |index| read_log(path(index));
+            let build = || {
+                RotatingFileSink::builder()
+                    .base_path(&base_path)
+                    .rotation_policy(RotationPolicy::DailyAndFileSize {
+                        hour: 0,
+                        minute: 0,
+                        max_size: 8,
+                    })
+                    .max_files(2)
+                    .formatter(Box::new(NoModFormatter::new()))
+                    .build_with_initial_time(Some(initial_time))
+                    .unwrap()
+            };
+
+            // The earlier files of the day have been removed by the retention.
+            fs::write(path(2), "aaaa").unwrap();
+            fs::write(path(3), "bbbb").unwrap();
+
+            {
+                let sink = build();
+                log_at(&sink, initial_time, "cccc");
+                log_at(&sink, initial_time, "dddd");
+            }
+            assert_eq!(read(0), None);
+            assert_eq!(read(1), None);
+            assert_eq!(read(2), None);
+            assert_eq!(read(3).as_deref(), Some("bbbbcccc"));
+            assert_eq!(read(4).as_deref(), Some("dddd"));
+
+            {
+                let sink = build();
+                log_at(&sink, initial_time, "eeee");
+                log_at(&sink, initial_time, "ffff");
+            }
+            assert_eq!(read(3), None);
+            assert_eq!(read(4).as_deref(), Some("ddddeeee"));
+            assert_eq!(read(5).as_deref(), Some("ffff"));
+            assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+        }
+
+        #[test]
+        fn calendar_time_point() {
+            let local = |year, month, day, hour, minute| -> SystemTime {
+                Local
+                    .with_ymd_and_hms(year, month, day, hour, minute, 0)
+                    .unwrap()
+                    .into()
+            };
+            let next = RotatorTimePoint::next_rotation_time_point;
+            let start = RotatorTimePoint::period_start_time;
+
+            // 2024-08-29 is a Thursday.
+            let weekly = TimePoint::Weekly {
+                weekday: 1,
+                hour: 8,
+                minute: 30,
+            };
+            assert_eq!(
+                next(weekly, local(2024, 8, 29, 12, 0)),
+                local(2024, 9, 2, 8, 30)
+            );
+            assert_eq!(
+                next(weekly, local(2024, 9, 2, 8, 29)),
+                local(2024, 9, 2, 8, 30)
+            );
+            assert_eq!(
+                next(weekly, local(2024, 9, 2, 8, 30)),
+                local(2024, 9, 9, 8, 30)
+            );
+            assert_eq!(
+                start(weekly, local(2024, 8, 29, 12, 0)),
+                local(2024, 8, 26, 8, 30)
+            );
+            assert_eq!(
+                start(weekly, local(2024, 9, 2, 8, 29)),
+                local(2024, 8, 26, 8, 30)
+            );
+            // Across a DST transition in some time zones.
+            assert_eq!(
+                next(weekly, local(2024, 3, 4, 12, 0)),
+                local(2024, 3, 11, 8, 30)
+            );
+            assert_eq!(
+                next(weekly, local(2024, 10, 28, 12, 0)),
+                local(2024, 11, 4, 8, 30)
+            );
+
+            let sunday = TimePoint::Weekly {
+                weekday: 0,
+                hour: 0,
+                minute: 0,
+            };
+            assert_eq!(
+                next(sunday, local(2024, 8, 31, 23, 59)),
+                local(2024, 9, 1, 0, 0)
+            );
+            assert_eq!(
+                next(sunday, local(2024, 9, 1, 0, 0)),
+                local(2024, 9, 8, 0, 0)
+            );
+
+            // The last day is used if the month has fewer days.
+            let monthly = TimePoint::Monthly {
+                day: 31,
+                hour: 0,
+                minute: 0,
+            };
+            assert_eq!(
+                next(monthly, local(2024, 1, 31, 0, 0)),
+                local(2024, 2, 29, 0, 0)
+            );
+            assert_eq!(
+                next(monthly, local(2024, 2, 29, 0, 0)),
+                local(2024, 3, 31, 0, 0)
+            );
+            assert_eq!(
+                next(monthly, local(2024, 12, 31, 1, 0)),
+                local(2025, 1, 31, 0, 0)
+            );
+            assert_eq!(
+                start(monthly, local(2024, 3, 15, 0, 0)),
+                local(2024, 2, 29, 0, 0)
+            );
 
-                logger.log(&record);
-                assert_files_count("hourly", 1);
-                assert_files_count("duration", 1);
-                assert_files_count("daily", 1);
+            let monthly = TimePoint::Monthly {
+                day: 15,
+                hour: 12,
+                minute: 0,
+            };
+            assert_eq!(
+                next(monthly, local(2024, 1, 15, 11, 59)),
+                local(2024, 1, 15, 12, 0)
+            );
+            assert_eq!(
+                start(monthly, local(2024, 1, 1, 0, 0)),
+                local(2023, 12, 15, 12, 0)
+            );
+        }
 
-                record.set_time(record.time() + HOUR_1 + SECOND_1);
-                logger.log(&record);
-                assert_files_count("hourly", 2);
-                assert_files_count("duration", 1);
-                assert_files_count("daily", 1);
+        #[test]
+        fn daily_and_hourly_time_point() {
+            // Some of the times may be skipped or repeated by DST transitions.
+            let local = |date: NaiveDate, hour, minute| -> SystemTime {
+                local_date_time(date.and_hms_opt(hour, minute, 0).unwrap()).into()
+            };
+            let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+            let next = RotatorTimePoint::next_rotation_time_point;
+            // Days of DST transitions in some time zones.
+            let transition_dates = [date(3, 10), date(3, 31), date(10, 27), date(11, 3)];
 
-                record.set_time(record.time() + HOUR_1 + SECOND_1);
-                logger.log(&record);
-                assert_files_count("hourly", 3);
-                assert_files_count("duration", 2);
-                assert_files_count("daily", 1);
+            let daily = TimePoint::Daily { hour: 0, minute: 0 };
+            assert_eq!(
+                next(daily, local(date(8, 29), 12, 0)),
+                local(date(8, 30), 0, 0)
+            );
+            for date in transition_dates {
+                assert_eq!(
+                    next(daily, local(date, 0, 0)),
+                    local(date.succ_opt().unwrap(), 0, 0)
+                );
+            }
 
-                record.set_time(record.time() + SECOND_1);
-                logger.log(&record);
-                assert_files_count("hourly", 3);
-                assert_files_count("duration", 2);
-                assert_files_count("daily", 1);
+            // Skipped by the spring transitions in some time zones.
+            let daily = TimePoint::Daily {
+                hour: 2,
+                minute: 30,
+            };
+            for date in transition_dates {
+                assert_eq!(
+                    next(daily, local(date.pred_opt().unwrap(), 12, 0)),
+                    local(date, 2, 30)
+                );
+                assert_eq!(
+                    next(daily, local(date, 12, 0)),
+                    local(date.succ_opt().unwrap(), 2, 30)
+                );
+            }
 
-                record.set_time(initial_time + DAY_1 + SECOND_1);
-                logger.log(&record);
-                assert_files_count("hourly", 4);
-                assert_files_count("duration", 3);
-                assert_files_count("daily", 2);
+            let hourly = TimePoint::Hourly;
+            assert_eq!(
+                next(hourly, local(date(8, 29), 12, 30)),
+                local(date(8, 29), 13, 0)
+            );
+            for date in transition_dates {
+                for hour in 0..4 {
+                    let now = local(date, hour, 30);
+                    let rotation_time = next(hourly, now);
+                    // The next hour may be skipped.
+                    assert!(rotation_time > now && rotation_time <= local(date, hour + 2, 0));
+                    assert_eq!(DateTime::<Local>::from(rotation_time).minute(), 0);
+                }
             }
         }
 
+        #[test]
+        fn weekly_and_monthly() {
+            let initial_time = |month, day| -> SystemTime {
+                Local
+                    .with_ymd_and_hms(2024, month, day, 12, 0, 0)
+                    .unwrap()
+                    .into()
+            };
+            let build = |name, policy, initial_time| {
+                RotatingFileSink::builder()
+                    .base_path(LOGS_PATH.join(name))
+                    .rotation_policy(policy)
+                    .formatter(Box::new(NoModFormatter::new()))
+                    .build_with_initial_time(Some(initial_time))
+                    .unwrap()
+            };
+            let read = |name| read_log(LOGS_PATH.join(name));
+
+            // Files are named by the time point starting the period.
+            let sink = build(
+                "calendar_weekly.log",
+                RotationPolicy::Weekly {
+                    weekday: 1,
+                    hour: 0,
+                    minute: 0,
+                },
+                initial_time(8, 29),
+            );
+            log_at(&sink, initial_time(8, 29), "a");
+            log_at(&sink, initial_time(9, 1), "b");
+            log_at(&sink, initial_time(9, 2), "c");
+            assert_eq!(
+                read("calendar_weekly_2024-08-26.log").as_deref(),
+                Some("ab")
+            );
+            assert_eq!(read("calendar_weekly_2024-09-02.log").as_deref(), Some("c"));
+            assert_files_count("calendar_weekly", 2);
+
+            let sink = build(
+                "calendar_monthly.log",
+                RotationPolicy::Monthly {
+                    day: 15,
+                    hour: 0,
+                    minute: 0,
+                },
+                initial_time(1, 20),
+            );
+            log_at(&sink, initial_time(1, 20), "a");
+            log_at(&sink, initial_time(2, 14), "b");
+            log_at(&sink, initial_time(2, 15), "c");
+            log_at(&sink, initial_time(4, 1), "d");
+            assert_eq!(read("calendar_monthly_2024-01.log").as_deref(), Some("ab"));
+            assert_eq!(read("calendar_monthly_2024-02.log").as_deref(), Some("c"));
+            assert_eq!(read("calendar_monthly_2024-03.log").as_deref(), Some("d"));
+            assert_files_count("calendar_monthly", 3);
+        }
+
         // This test may only detect issues if the system time zone is not UTC.
         #[test]
         fn respect_local_tz() {
@@ -1383,6 +3574,530 @@ mod tests {
         }
     }
 
+    mod retention {
+        use super::*;
+
+        static LOGS_PATH: Lazy<PathBuf> = Lazy::new(|| {
+            let path = BASE_LOGS_PATH.join("retention");
+            _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            path
+        });
+
+        #[track_caller]
+        fn file_names(dir: impl AsRef<Path>) -> Vec<String> {
+            let mut names = fs::read_dir(dir)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        }
+
+        #[test]
+        fn max_total_size() {
+            let dir = LOGS_PATH.join("max_total_size");
+            fs::create_dir_all(&dir).unwrap();
+
+            let sink = RotatingFileSink::builder()
+                .base_path(dir.join("app.log"))
+                .rotation_policy(RotationPolicy::FileSize(4))
+                .file_size_naming(FileSizeNaming::Sequence)
+                .max_total_size(Some(8))
+                .formatter(Box::new(NoModFormatter::new()))
+                .build()
+                .unwrap();
+            for payload in ["aaaa", "bbbb", "cccc", "dddd", "eeee"] {
+                sink.log(&Record::new(Level::Info, payload)).unwrap();
+            }
+            sink.flush().unwrap();
+
+            assert_eq!(file_names(&dir), ["app.3.log", "app.4.log", "app.log"]);
+            assert_eq!(fs::read_to_string(dir.join("app.4.log")).unwrap(), "dddd");
+        }
+
+        #[test]
+        fn index_naming_without_max_files() {
+            let dir = LOGS_PATH.join("index_naming_without_max_files");
+            fs::create_dir_all(&dir).unwrap();
+
+            let sink = RotatingFileSink::builder()
+                .base_path(dir.join("app.log"))
+                .rotation_policy(RotationPolicy::FileSize(4))
+                .max_total_size(Some(8))
+                .formatter(Box::new(NoModFormatter::new()))
+                .build()
+                .unwrap();
+            for payload in ["aaaa", "bbbb", "cccc", "dddd"] {
+                sink.log(&Record::new(Level::Info, payload)).unwrap();
+            }
+            sink.flush().unwrap();
+
+            assert_eq!(file_names(&dir), ["app.log", "app_1.log", "app_2.log"]);
+            assert_eq!(fs::read_to_string(dir.join("app_1.log")).unwrap(), "cccc");
+            assert_eq!(fs::read_to_string(dir.join("app_2.log")).unwrap(), "bbbb");
+
+            // With only `max_age`, no rotated file is lost.
+            let dir = LOGS_PATH.join("index_naming_max_age_only");
+            fs::create_dir_all(&dir).unwrap();
+
+            let sink = RotatingFileSink::builder()
+                .base_path(dir.join("app.log"))
+                .rotation_policy(RotationPolicy::FileSize(4))
+                .max_age(Some(Duration::from_secs(60 * 60)))
+                .formatter(Box::new(NoModFormatter::new()))
+                .build()
+                .unwrap();
+            for payload in ["aaaa", "bbbb", "cccc", "dddd"] {
+                sink.log(&Record::new(Level::Info, payload)).unwrap();
+            }
+            sink.flush().unwrap();
+
+            assert_eq!(
+                file_names(&dir),
+                ["app.log", "app_1.log", "app_2.log", "app_3.log"]
+            );
+            assert_eq!(fs::read_to_string(dir.join("app_3.log")).unwrap(), "aaaa");
+        }
+
+        #[test]
+        fn max_age() {
+            let dir = LOGS_PATH.join("max_age");
+            fs::create_dir_all(&dir).unwrap();
+
+            for name in ["app.1.log", "app.2.log", "other.log"] {
+                fs::write(dir.join(name), "old").unwrap();
+            }
+            std::thread::sleep(Duration::from_millis(1100));
+
+            // Expired files are removed on startup.
+            let sink = RotatingFileSink::builder()
+                .base_path(dir.join("app.log"))
+                .rotation_policy(RotationPolicy::FileSize(4))
+                .file_size_naming(FileSizeNaming::Sequence)
+                .max_age(Some(Duration::from_secs(1)))
+                .formatter(Box::new(NoModFormatter::new()))
+                .build()
+                .unwrap();
+            assert_eq!(file_names(&dir), ["app.log", "other.log"]);
+
+            for payload in ["aaaa", "bbbb"] {
+                sink.log(&Record::new(Level::Info, payload)).unwrap();
+            }
+            sink.flush().unwrap();
+            assert_eq!(file_names(&dir), ["app.3.log", "app.log", "other.log"]);
+        }
+
+        #[test]
+        fn scan_on_startup() {
+            const DAY_1: Duration = Duration::from_secs(60 * 60 * 24);
+
+            let dir = LOGS_PATH.join("scan_on_startup");
+            fs::create_dir_all(&dir).unwrap();
+            let base_path = dir.join("app.log");
+
+            let initial_time: SystemTime = Local
+                .with_ymd_and_hms(2024, 8, 29, 12, 0, 0)
+                .unwrap()
+                .into();
+            let path = |time, index| {
+                RotatorTimePoint::calc_split_file_path(
+                    &base_path,
+                    TimePoint::Daily { hour: 0, minute: 0 },
+                    time,
+                    index,
+                )
+            };
+            for (time, index) in [
+                (initial_time, 0),
+                (initial_time, 1),
+                (initial_time + DAY_1, 0),
+                (initial_time + DAY_1 * 2, 0),
+            ] {
+                fs::write(path(time, index), "old").unwrap();
+            }
+
+            let _sink = RotatingFileSink::builder()
+                .base_path(&base_path)
+                .rotation_policy(RotationPolicy::DailyAndFileSize {
+                    hour: 0,
+                    minute: 0,
+                    max_size: 8,
+                })
+                .max_files(3)
+                .build_with_initial_time(Some(initial_time + DAY_1 * 3))
+                .unwrap();
+
+            assert_eq!(
+                file_names(&dir),
+                [
+                    "app_2024-08-30.log",
+                    "app_2024-08-31.log",
+                    "app_2024-09-01.log"
+                ]
+            );
+        }
+    }
+
+    mod file_name_template {
+        use super::*;
+
+        static LOGS_PATH: Lazy<PathBuf> = Lazy::new(|| {
+            let path = BASE_LOGS_PATH.join("file_name_template");
+            _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            path
+        });
+
+        #[test]
+        fn parse() {
+            let template = FileNameTemplate::parse("logs/%Y/%m/app-%d.%i.log").unwrap();
+            assert_eq!(template.root, Path::new("logs"));
+            assert_eq!(template.components.len(), 3);
+
+            let mut values = TemplateValues::default();
+            assert!(values.match_tokens(&template.components[2], "app-23.12.log"));
+            assert_eq!(values.key(), (23000000, 12));
+            assert!(!TemplateValues::default().match_tokens(&template.components[2], "app-2.0.log"));
+            assert!(!TemplateValues::default().match_tokens(&template.components[2], "app-23.log"));
+
+            let time: SystemTime = Local.with_ymd_and_hms(2022, 3, 23, 4, 5, 6).unwrap().into();
+            assert_eq!(
+                template.format(time, 7),
+                Path::new("logs/2022/03/app-23.7.log")
+            );
+            assert_eq!(
+                FileNameTemplate::parse("%H-%M-%S_%%.log")
+                    .unwrap()
+                    .format(time, 0),
+                Path::new("04-05-06_%.log")
+            );
+
+            assert!(FileNameTemplate::parse("logs/app.log").is_err());
+            assert!(FileNameTemplate::parse("logs/app-%x.log").is_err());
+            assert!(FileNameTemplate::parse("logs/app-%").is_err());
+            assert!(FileNameTemplate::parse("logs/%Y/../app-%i.log").is_err());
+        }
+
+        #[test]
+        fn invalid() {
+            let build = |template: &str, policy| {
+                RotatingFileSink::builder()
+                    .file_name_template(LOGS_PATH.join(template).to_str().unwrap())
+                    .rotation_policy(policy)
+                    .build()
+            };
+            let is_invalid = |res: Result<RotatingFileSink>| {
+                matches!(
+                    res,
+                    Err(Error::InvalidArgument(
+                        InvalidArgumentError::FileNameTemplate(_)
+                    ))
+                )
+            };
+
+            assert!(is_invalid(build("app-%x.log", RotationPolicy::FileSize(1))));
+            assert!(is_invalid(build(
+                "app-%Y%m%d.log",
+                RotationPolicy::FileSize(1)
+            )));
+            assert!(is_invalid(build(
+                "app-%Y%m.log",
+                RotationPolicy::Daily { hour: 0, minute: 0 }
+            )));
+            assert!(is_invalid(build("app-%Y%m%d.log", RotationPolicy::Hourly)));
+            assert!(is_invalid(build(
+                "app-%Y%m%d.log",
+                RotationPolicy::DailyAndFileSize {
+                    hour: 0,
+                    minute: 0,
+                    max_size: 1
+                }
+            )));
+            assert!(is_invalid(
+                RotatingFileSink::builder()
+                    .file_name_template(LOGS_PATH.join("app-%i.log").to_str().unwrap())
+                    .rotation_policy(RotationPolicy::FileSize(1))
+                    .file_size_naming(FileSizeNaming::Sequence)
+                    .build()
+            ));
+        }
+
+        #[test]
+        fn policy_file_size() {
+            let dir = LOGS_PATH.join("policy_file_size");
+            let template = dir.join("app-%i.log");
+
+            let build = || {
+                RotatingFileSink::builder()
+                    .file_name_template(template.to_str().unwrap())
+                    .rotation_policy(RotationPolicy::FileSize(4))
+                    .max_files(3)
+                    .formatter(Box::new(NoModFormatter::new()))
+                    .build()
+                    .unwrap()
+            };
+            let read = |index| read_log(dir.join(format!("app-{}.log", index)));
+
+            {
+                let sink = build();
+                for payload in ["aaaa", "bbbb", "cc"] {
+                    sink.log(&Record::new(Level::Info, payload)).unwrap();
+                }
+                sink.flush().unwrap();
+                assert_eq!(read(0).as_deref(), Some("aaaa"));
+                assert_eq!(read(1).as_deref(), Some("bbbb"));
+                assert_eq!(read(2).as_deref(), Some("cc"));
+            }
+            // Continues writing to the newest file after restarts.
+            {
+                let sink = build();
+                for payload in ["dd", "eeee"] {
+                    sink.log(&Record::new(Level::Info, payload)).unwrap();
+                }
+                sink.flush().unwrap();
+                assert_eq!(read(0), None);
+                assert_eq!(read(1).as_deref(), Some("bbbb"));
+                assert_eq!(read(2).as_deref(), Some("ccdd"));
+                assert_eq!(read(3).as_deref(), Some("eeee"));
+            }
+        }
+
+        #[test]
+        fn policy_daily_and_file_size() {
+            const DAY_1: Duration = Duration::from_secs(60 * 60 * 24);
+
+            let dir = LOGS_PATH.join("policy_daily_and_file_size");
+            let template = dir.join("%Y/%m/app-%d.%i.log");
+
+            let initial_time: SystemTime = Local
+                .with_ymd_and_hms(2022, 3, 31, 12, 0, 0)
+                .unwrap()
+                .into();
+            let build = |initial_time| {
+                RotatingFileSink::builder()
+                    .file_name_template(template.to_str().unwrap())
+                    .rotation_policy(RotationPolicy::DailyAndFileSize {
+                        hour: 0,
+                        minute: 0,
+                        max_size: 4,
+                    })
+                    .max_files(3)
+                    .formatter(Box::new(NoModFormatter::new()))
+                    .build_with_initial_time(Some(initial_time))
+                    .unwrap()
+            };
+            let read = |path: &str| read_log(dir.join(path));
+
+            {
+                let sink = build(initial_time);
+                log_at(&sink, initial_time, "aaaa");
+                log_at(&sink, initial_time, "bbbb");
+                log_at(&sink, initial_time + DAY_1, "cc");
+                assert_eq!(read("2022/03/app-31.0.log").as_deref(), Some("aaaa"));
+                assert_eq!(read("2022/03/app-31.1.log").as_deref(), Some("bbbb"));
+                assert_eq!(read("2022/04/app-01.0.log").as_deref(), Some("cc"));
+            }
+            // `max_files` is applied to the files in all directories.
+            {
+                let sink = build(initial_time + DAY_1);
+                log_at(&sink, initial_time + DAY_1, "dd");
+                log_at(&sink, initial_time + DAY_1, "eeee");
+                assert_eq!(read("2022/03/app-31.0.log"), None);
+                assert_eq!(read("2022/03/app-31.1.log").as_deref(), Some("bbbb"));
+                assert_eq!(read("2022/04/app-01.0.log").as_deref(), Some("ccdd"));
+                assert_eq!(read("2022/04/app-01.1.log").as_deref(), Some("eeee"));
+            }
+        }
+    }
+
+    #[cfg(feature = "gzip")]
+    mod compression {
+        use std::io::Read;
+
+        use super::*;
+
+        static LOGS_PATH: Lazy<PathBuf> = Lazy::new(|| {
+            let path = BASE_LOGS_PATH.join("compression");
+            _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            path
+        });
+
+        #[track_caller]
+        fn read_gzip(path: impl AsRef<Path>) -> String {
+            let mut content = String::new();
+            libflate::gzip::Decoder::new(File::open(path).unwrap())
+                .unwrap()
+                .read_to_string(&mut content)
+                .unwrap();
+            content
+        }
+
+        #[test]
+        fn policy_file_size() {
+            let base_path = LOGS_PATH.join("file_size.log");
+            let path = |index| RotatorFileSize::calc_file_path(&base_path, index);
+            let gz_path = |index| Compression::Gzip.compressed_path(&path(index));
+
+            {
+                let sink = RotatingFileSink::builder()
+                    .base_path(&base_path)
+                    .rotation_policy(RotationPolicy::FileSize(4))
+                    .max_files(3)
+                    .compression(Compression::Gzip)
+                    .formatter(Box::new(NoModFormatter::new()))
+                    .build()
+                    .unwrap();
+
+                for payload in ["aaaa", "bbbb", "cccc", "dddd"] {
+                    sink.log(&Record::new(Level::Info, payload)).unwrap();
+                }
+            }
+
+            assert_eq!(fs::read_to_string(path(0)).unwrap(), "dddd");
+            assert!(!path(1).exists() && !path(2).exists());
+            assert_eq!(read_gzip(gz_path(1)), "cccc");
+            assert_eq!(read_gzip(gz_path(2)), "bbbb");
+            assert!(!path(3).exists() && !gz_path(3).exists());
+        }
+
+        #[test]
+        fn policy_time_point() {
+            const HOUR_1: Duration = Duration::from_secs(60 * 60);
+
+            let base_path = LOGS_PATH.join("hourly.log");
+            let initial_time = SystemTime::now();
+            let path = |time| RotatorTimePoint::calc_file_path(&base_path, TimePoint::Hourly, time);
+            let gz_path = |time| Compression::Gzip.compressed_path(&path(time));
+
+            {
+                let sink = RotatingFileSink::builder()
+                    .base_path(&base_path)
+                    .rotation_policy(RotationPolicy::Hourly)
+                    .max_files(2)
+                    .compression(Compression::Gzip)
+                    .formatter(Box::new(NoModFormatter::new()))
+                    .build_with_initial_time(Some(initial_time))
+                    .unwrap();
+
+                let mut record = Record::new(Level::Info, "a");
+                for i in 0..3 {
+                    record.set_time(initial_time + HOUR_1 * i);
+                    sink.log(&record).unwrap();
+                }
+            }
+
+            assert!(!path(initial_time).exists() && !gz_path(initial_time).exists());
+            assert!(!path(initial_time + HOUR_1).exists());
+            assert_eq!(read_gzip(gz_path(initial_time + HOUR_1)), "a");
+            assert_eq!(
+                fs::read_to_string(path(initial_time + HOUR_1 * 2)).unwrap(),
+                "a"
+            );
+        }
+
+        #[test]
+        fn busy_worker() {
+            let base_path = LOGS_PATH.join("busy_worker.log");
+            let path = |index| RotatorFileSize::calc_file_path(&base_path, index);
+            let gz_path = |index| Compression::Gzip.compressed_path(&path(index));
+
+            {
+                let sink = RotatingFileSink::builder()
+                    .base_path(&base_path)
+                    .rotation_policy(RotationPolicy::FileSize(4))
+                    .max_files(3)
+                    .compression(Compression::Gzip)
+                    .formatter(Box::new(NoModFormatter::new()))
+                    .build()
+                    .unwrap();
+                let compressor = match &sink.rotator {
+                    RotatorKind::FileSize(rotator) => rotator.compressor.as_ref().unwrap(),
+                    RotatorKind::TimePoint(_) => unreachable!(),
+                };
+
+                // Rotating doesn't wait for the worker.
+                let (release, blocked) = mpsc::channel::<()>();
+                compressor
+                    .run(Box::new(move || {
+                        _ = blocked.recv();
+                        Ok(())
+                    }))
+                    .unwrap();
+                for payload in ["aaaa", "bbbb", "cccc", "dddd"] {
+                    sink.log(&Record::new(Level::Info, payload)).unwrap();
+                }
+                sink.flush().unwrap();
+                assert_eq!(fs::read_to_string(path(0)).unwrap(), "dddd");
+                assert!(!gz_path(1).exists());
+
+                release.send(()).unwrap();
+            }
+
+            assert!(!path(1).exists() && !path(2).exists());
+            assert_eq!(read_gzip(gz_path(1)), "cccc");
+            assert_eq!(read_gzip(gz_path(2)), "bbbb");
+            assert!(!path(3).exists() && !gz_path(3).exists());
+            assert_eq!(fs::read_dir(&*LOGS_PATH).unwrap().count(), 3);
+        }
+
+        #[test]
+        fn dead_worker() {
+            let common_impl = helper::CommonImpl::with_formatter(Box::new(NoModFormatter::new()));
+            common_impl
+                .error_handler
+                .store(Some(|_| panic!("error handler panics")), Ordering::Relaxed);
+            let compressor = Compressor::new(Compression::Gzip, Arc::new(common_impl)).unwrap();
+
+            // Kills the worker by the panicking error handler.
+            compressor
+                .compress(LOGS_PATH.join("nonexistent.log"))
+                .unwrap();
+            compressor.wait_idle();
+
+            let path = LOGS_PATH.join("dead_worker.log");
+            fs::write(&path, "abc").unwrap();
+            compressor.compress(path.clone()).unwrap();
+            assert!(!path.exists());
+            assert_eq!(read_gzip(Compression::Gzip.compressed_path(&path)), "abc");
+
+            assert!(compressor
+                .compress(LOGS_PATH.join("nonexistent.log"))
+                .is_err());
+        }
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn compression_zstd() {
+        let base_path = BASE_LOGS_PATH.join("compression_zstd").join("zstd.log");
+        _ = fs::remove_dir_all(base_path.parent().unwrap());
+        let path = |index| RotatorFileSize::calc_file_path(&base_path, index);
+        let zst_path = |index| Compression::Zstd.compressed_path(&path(index));
+
+        {
+            let sink = RotatingFileSink::builder()
+                .base_path(&base_path)
+                .rotation_policy(RotationPolicy::FileSize(4))
+                .max_files(2)
+                .compression(Compression::Zstd)
+                .formatter(Box::new(NoModFormatter::new()))
+                .build()
+                .unwrap();
+
+            for payload in ["aaaa", "bbbb", "cccc"] {
+                sink.log(&Record::new(Level::Info, payload)).unwrap();
+            }
+        }
+
+        assert_eq!(fs::read_to_string(path(0)).unwrap(), "cccc");
+        assert!(zst_path(1).to_string_lossy().ends_with("zstd_1.log.zst"));
+        let decoded = zstd::stream::decode_all(File::open(zst_path(1)).unwrap()).unwrap();
+        assert_eq!(decoded, b"bbbb");
+        assert!(!path(1).exists() && !zst_path(2).exists());
+    }
+
     #[test]
     fn test_builder_optional_params() {
         // workaround for the missing `no_run` attribute
@@ -1431,6 +4146,16 @@ mod tests {
                 seconds,
             }
         }
+        fn weekly(weekday: u32, hour: u32, minute: u32) -> RotationPolicy {
+            Weekly {
+                weekday,
+                hour,
+                minute,
+            }
+        }
+        fn monthly(day: u32, hour: u32, minute: u32) -> RotationPolicy {
+            Monthly { day, hour, minute }
+        }
 
         assert!(FileSize(1).validate().is_ok());
         assert!(FileSize(1024).validate().is_ok());
@@ -1454,5 +4179,51 @@ mod tests {
         assert!(duration(1, 60, 60).validate().is_err());
         assert!(duration(60, 1, 1).validate().is_ok());
 
+        assert!(DailyAndFileSize {
+            hour: 23,
+            minute: 59,
+            max_size: 1
+        }
+        .validate()
+        .is_ok());
+        assert!(DailyAndFileSize {
+            hour: 24,
+            minute: 0,
+            max_size: 1
+        }
+        .validate()
+        .is_err());
+        assert!(DurationAndFileSize {
+            hours: 1,
+            minutes: 0,
+            seconds: 0,
+            max_size: 0
+        }
+        .validate()
+        .is_err());
+
+        assert!(weekly(0, 0, 0).validate().is_ok());
+        assert!(weekly(6, 23, 59).validate().is_ok());
+        assert!(weekly(7, 0, 0).validate().is_err());
+        assert!(weekly(0, 24, 0).validate().is_err());
+        assert!(weekly(0, 0, 60).validate().is_err());
+
+        assert!(monthly(1, 0, 0).validate().is_ok());
+        assert!(monthly(31, 23, 59).validate().is_ok());
+        assert!(monthly(0, 0, 0).validate().is_err());
+        assert!(monthly(32, 0, 0).validate().is_err());
+        assert!(monthly(1, 24, 0).validate().is_err());
+        assert!(monthly(1, 0, 60).validate().is_err());
+
+        assert!(matches!(
+            RotatingFileSink::builder()
+                .base_path(BASE_LOGS_PATH.join("invalid_naming.log"))
+                .rotation_policy(Hourly)
+                .file_size_naming(FileSizeNaming::Sequence)
+                .build(),
+            Err(Error::InvalidArgument(
+                InvalidArgumentError::RotationPolicy(_)
+            ))
+        ));
     }
 }
@@ -0,0 +1,350 @@
+use crate::{
+    formatter::{Formatter, FormatterContext},
+    sink::helper,
+    Error, ErrorHandler, LevelFilter, Record, Result, StringBuf,
+};
+
+/// Common properties of a sink: the level filter, the formatter and the error
+/// handler.
+///
+/// It helps to implement custom sinks with the same behavior as built-in
+/// sinks. Put it in the sink struct, and use macro [`impl_sink_base!`] to
+/// implement the property accessors of [`Sink`] trait. The builder side is
+/// covered by [`SinkBuilderBase`].
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Mutex;
+///
+/// use spdlog::{
+///     prelude::*,
+///     sink::{Sink, SinkBase, SinkBuilderBase},
+///     Record, StringBuf,
+/// };
+///
+/// struct CollectSink {
+///     base: SinkBase,
+///     collected: Mutex<Vec<String>>,
+/// }
+///
+/// impl Sink for CollectSink {
+///     fn log(&self, record: &Record) -> spdlog::Result<()> {
+///         let mut string_buf = StringBuf::new();
+///         self.base.format(record, &mut string_buf)?;
+///         self.collected.lock().unwrap().push(string_buf.to_string());
+///         Ok(())
+///     }
+///
+///     fn flush(&self) -> spdlog::Result<()> {
+///         Ok(())
+///     }
+///
+///     spdlog::impl_sink_base!(@Sink: base);
+/// }
+///
+/// struct CollectSinkBuilder {
+///     base: SinkBuilderBase,
+/// }
+///
+/// impl CollectSinkBuilder {
+///     spdlog::impl_sink_base!(@SinkBuilder: base);
+///
+///     fn build(self) -> CollectSink {
+///         CollectSink {
+///             base: self.base.build(),
+///             collected: Mutex::new(vec![]),
+///         }
+///     }
+/// }
+///
+/// let sink = CollectSinkBuilder {
+///     base: SinkBuilderBase::new(),
+/// }
+/// .level_filter(LevelFilter::MoreSevereEqual(Level::Warn))
+/// .build();
+/// assert_eq!(sink.level_filter(), LevelFilter::MoreSevereEqual(Level::Warn));
+/// ```
+///
+/// [`impl_sink_base!`]: crate::impl_sink_base
+/// [`Sink`]: crate::sink::Sink
+pub struct SinkBase {
+    common_impl: helper::CommonImpl,
+}
+
+impl SinkBase {
+    /// Constructs a `SinkBase` with default properties.
+    ///
+    /// It's equivalent to `SinkBuilderBase::new().build()`.
+    #[must_use]
+    pub fn new() -> Self {
+        SinkBuilderBase::new().build()
+    }
+
+    /// Gets the log level filter.
+    #[must_use]
+    pub fn level_filter(&self) -> LevelFilter {
+        self.common_impl.level_filter()
+    }
+
+    /// Sets the log level filter.
+    pub fn set_level_filter(&self, level_filter: LevelFilter) {
+        self.common_impl.set_level_filter(level_filter)
+    }
+
+    /// Sets the formatter.
+    pub fn set_formatter(&self, formatter: Box<dyn Formatter>) {
+        self.common_impl.set_formatter(formatter)
+    }
+
+    /// Sets the error handler.
+    pub fn set_error_handler(&self, handler: Option<ErrorHandler>) {
+        self.common_impl.set_error_handler(handler)
+    }
+
+    /// Formats a record into `dest` with the formatter.
+    pub fn format(&self, record: &Record, dest: &mut StringBuf) -> Result<()> {
+        let mut ctx = FormatterContext::new();
+        self.common_impl
+            .formatter
+            .read()
+            .format(record, dest, &mut ctx)
+    }
+
+    /// Calls the given function with a reference to the formatter.
+    ///
+    /// The formatter cannot be replaced while the function is running.
+    pub fn with_formatter<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&dyn Formatter) -> R,
+    {
+        callback(&**self.common_impl.formatter.read())
+    }
+
+    /// Calls the error handler with an error that cannot be returned to the
+    /// caller, e.g. errors occurred in a background thread or in `Drop`.
+    ///
+    /// If no handler is set, [default error handler] will be used, `from` is
+    /// the name of the sink used in the output.
+    ///
+    /// [default error handler]: ../error/index.html#default-error-handler
+    pub fn call_error_handler(&self, from: impl AsRef<str>, err: Error) {
+        self.common_impl.non_returnable_error(from, err)
+    }
+}
+
+impl Default for SinkBase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Common parameters of a sink builder: the level filter, the formatter and
+/// the error handler.
+///
+/// Put it in the builder struct of a custom sink, and use macro
+/// [`impl_sink_base!`] to implement the builder methods. See the documentation
+/// of [`SinkBase`] for an example.
+///
+/// The defaults are the same as built-in sinks:
+///
+/// | Parameter       | Default Value           |
+/// |-----------------|-------------------------|
+/// | level_filter    | `All`                   |
+/// | formatter       | `FullFormatter`         |
+/// | error_handler   | [default error handler] |
+///
+/// [`impl_sink_base!`]: crate::impl_sink_base
+/// [default error handler]: ../error/index.html#default-error-handler
+pub struct SinkBuilderBase {
+    common_builder_impl: helper::CommonBuilderImpl,
+}
+
+impl SinkBuilderBase {
+    /// Constructs a `SinkBuilderBase` with default parameters.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            common_builder_impl: helper::CommonBuilderImpl::new(),
+        }
+    }
+
+    /// Sets the log level filter.
+    pub fn set_level_filter(&mut self, level_filter: LevelFilter) {
+        self.common_builder_impl.set_level_filter(level_filter)
+    }
+
+    /// Sets the formatter.
+    pub fn set_formatter(&mut self, formatter: Box<dyn Formatter>) {
+        self.common_builder_impl.set_formatter(formatter)
+    }
+
+    /// Sets the error handler.
+    pub fn set_error_handler(&mut self, handler: ErrorHandler) {
+        self.common_builder_impl.set_error_handler(handler)
+    }
+
+    /// Builds a [`SinkBase`].
+    #[must_use]
+    pub fn build(self) -> SinkBase {
+        SinkBase {
+            common_impl: helper::CommonImpl::from_builder(self.common_builder_impl),
+        }
+    }
+}
+
+impl Default for SinkBuilderBase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Implements common methods for custom sinks and their builders with
+/// [`SinkBase`] and [`SinkBuilderBase`].
+///
+/// - `impl_sink_base!(@Sink: field)` implements methods `level_filter`,
+///   `set_level_filter`, `set_formatter` and `set_error_handler` of [`Sink`]
+///   trait, where `field` is a [`SinkBase`].
+///
+/// - `impl_sink_base!(@SinkBuilder: field)` implements builder methods
+///   `level_filter`, `formatter` and `error_handler`, where `field` is a
+///   [`SinkBuilderBase`].
+///
+/// `field` can also be a path to a nested field, e.g. `inner.base`.
+///
+/// Built-in sinks are implemented by this macro as well.
+///
+/// See the documentation of [`SinkBase`] for an example.
+///
+/// [`SinkBase`]: crate::sink::SinkBase
+/// [`SinkBuilderBase`]: crate::sink::SinkBuilderBase
+/// [`Sink`]: crate::sink::Sink
+#[macro_export]
+macro_rules! impl_sink_base {
+    ( @Sink: $($field:ident).+ ) => {
+        fn level_filter(&self) -> $crate::LevelFilter {
+            self.$($field).+.level_filter()
+        }
+
+        fn set_level_filter(&self, level_filter: $crate::LevelFilter) {
+            self.$($field).+.set_level_filter(level_filter)
+        }
+
+        fn set_formatter(&self, formatter: Box<dyn $crate::formatter::Formatter>) {
+            self.$($field).+.set_formatter(formatter)
+        }
+
+        fn set_error_handler(&self, handler: Option<$crate::ErrorHandler>) {
+            self.$($field).+.set_error_handler(handler)
+        }
+    };
+    ( @SinkBuilder: $($field:ident).+ ) => {
+        /// Specifies a log level filter.
+        ///
+        /// This parameter is **optional**.
+        #[must_use]
+        pub fn level_filter(mut self, level_filter: $crate::LevelFilter) -> Self {
+            self.$($field).+.set_level_filter(level_filter);
+            self
+        }
+
+        /// Specifies a formatter.
+        ///
+        /// This parameter is **optional**.
+        #[must_use]
+        pub fn formatter(mut self, formatter: Box<dyn $crate::formatter::Formatter>) -> Self {
+            self.$($field).+.set_formatter(formatter);
+            self
+        }
+
+        /// Specifies an error handler.
+        ///
+        /// This parameter is **optional**.
+        #[must_use]
+        pub fn error_handler(mut self, handler: $crate::ErrorHandler) -> Self {
+            self.$($field).+.set_error_handler(handler);
+            self
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+
+    use super::*;
+    use crate::{prelude::*, sink::Sink, sync::*, test_utils::*};
+
+    struct CustomSink {
+        inner: CustomSinkInner,
+        collected: Mutex<Vec<String>>,
+    }
+
+    struct CustomSinkInner {
+        base: SinkBase,
+    }
+
+    impl Sink for CustomSink {
+        fn log(&self, record: &Record) -> Result<()> {
+            let mut string_buf = StringBuf::new();
+            self.inner.base.format(record, &mut string_buf)?;
+            self.collected.lock_expect().push(string_buf.to_string());
+            Ok(())
+        }
+
+        fn flush(&self) -> Result<()> {
+            self.inner
+                .base
+                .call_error_handler("CustomSink", Error::__ForInternalTestsUseOnly(1));
+            Ok(())
+        }
+
+        crate::impl_sink_base!(@Sink: inner.base);
+    }
+
+    struct CustomSinkBuilder {
+        base: SinkBuilderBase,
+    }
+
+    impl CustomSinkBuilder {
+        crate::impl_sink_base!(@SinkBuilder: base);
+
+        fn build(self) -> CustomSink {
+            CustomSink {
+                inner: CustomSinkInner {
+                    base: self.base.build(),
+                },
+                collected: Mutex::new(vec![]),
+            }
+        }
+    }
+
+    #[test]
+    fn custom_sink() {
+        static CALLED: AtomicBool = AtomicBool::new(false);
+
+        let sink = Arc::new(
+            CustomSinkBuilder {
+                base: SinkBuilderBase::new(),
+            }
+            .level_filter(LevelFilter::MoreSevereEqual(Level::Warn))
+            .formatter(Box::new(NoModFormatter::new()))
+            .error_handler(|err| {
+                assert!(matches!(err, Error::__ForInternalTestsUseOnly(1)));
+                CALLED.store(true, Ordering::SeqCst);
+            })
+            .build(),
+        );
+        let test = build_test_logger(|b| b.sink(sink.clone()));
+
+        info!(logger: test, "filtered");
+        warn!(logger: test, "hello");
+        assert_eq!(*sink.collected.lock_expect(), ["hello"]);
+
+        sink.set_level_filter(LevelFilter::All);
+        assert_eq!(sink.level_filter(), LevelFilter::All);
+
+        test.flush();
+        assert!(CALLED.load(Ordering::SeqCst));
+    }
+}
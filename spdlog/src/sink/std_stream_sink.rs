@@ -177,11 +177,11 @@ impl Sink for StdStreamSink {
                 then {
                     let style = self.level_styles.style(record.level());
 
-                    dest.write_all(string_buf[..style_range.start].as_bytes())?;
+                    dest.write_all(&string_buf.as_bytes()[..style_range.start])?;
                     style.write_start(&mut dest)?;
-                    dest.write_all(string_buf[style_range.start..style_range.end].as_bytes())?;
+                    dest.write_all(&string_buf.as_bytes()[style_range.start..style_range.end])?;
                     style.write_end(&mut dest)?;
-                    dest.write_all(string_buf[style_range.end..].as_bytes())?;
+                    dest.write_all(&string_buf.as_bytes()[style_range.end..])?;
                 } else {
                     dest.write_all(string_buf.as_bytes())?;
                 }
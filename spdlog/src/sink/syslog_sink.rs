@@ -0,0 +1,864 @@
+//! Provides a syslog sink.
+
+use std::{
+    convert::Infallible,
+    fmt::Write as _,
+    net::{SocketAddr, TcpStream, UdpSocket},
+    time::Duration,
+};
+#[cfg(unix)]
+use std::{
+    os::{raw::c_char, unix::net::UnixDatagram},
+    path::PathBuf,
+};
+
+use chrono::{DateTime, Local, Utc};
+
+use crate::{
+    error::InvalidArgumentError,
+    formatter::{Formatter, FormatterContext},
+    sink::{
+        helper,
+        reconnect::{Backoff, ReconnectingWriter},
+        tcp_sink, Sink,
+    },
+    sync::*,
+    Error, Level, Record, Result, StringBuf,
+};
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub(crate) enum SyslogLevel {
+    _Emerg = 0,
+    _Alert = 1,
+    Crit = 2,
+    Err = 3,
+    Warning = 4,
+    _Notice = 5,
+    Info = 6,
+    Debug = 7,
+}
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub(crate) struct SyslogLevels([SyslogLevel; Level::count()]);
+
+impl SyslogLevels {
+    #[must_use]
+    pub(crate) const fn new() -> Self {
+        Self([
+            SyslogLevel::Crit,    // Critical
+            SyslogLevel::Err,     // Error
+            SyslogLevel::Warning, // Warn
+            SyslogLevel::Info,    // Info
+            SyslogLevel::Debug,   // Debug
+            SyslogLevel::Debug,   // Trace
+        ])
+    }
+
+    #[must_use]
+    pub(crate) fn level(&self, level: Level) -> SyslogLevel {
+        self.0[level as usize]
+    }
+}
+
+impl Default for SyslogLevels {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Syslog facilities.
+#[allow(missing_docs)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum SyslogFacility {
+    Kern = 0,
+    User = 1,
+    Mail = 2,
+    Daemon = 3,
+    Auth = 4,
+    Syslog = 5,
+    Lpr = 6,
+    News = 7,
+    Uucp = 8,
+    Cron = 9,
+    AuthPriv = 10,
+    Ftp = 11,
+    Local0 = 16,
+    Local1 = 17,
+    Local2 = 18,
+    Local3 = 19,
+    Local4 = 20,
+    Local5 = 21,
+    Local6 = 22,
+    Local7 = 23,
+}
+
+/// Syslog message formats.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum SyslogFormat {
+    /// The format defined in [RFC 5424].
+    ///
+    /// [RFC 5424]: https://www.rfc-editor.org/rfc/rfc5424
+    Rfc5424,
+    /// The legacy BSD format described in [RFC 3164].
+    ///
+    /// Only the facility, the hostname and the app-name are used, as the
+    /// format has no msgid or structured data.
+    ///
+    /// [RFC 3164]: https://www.rfc-editor.org/rfc/rfc3164
+    Rfc3164,
+}
+
+/// Transports that [`SyslogSink`] sends messages through.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum SyslogTransport {
+    /// A local Unix datagram socket, usually `/dev/log`.
+    #[cfg(unix)]
+    Unix(PathBuf),
+    /// A UDP address in the form of `host:port`, one message per datagram.
+    Udp(String),
+    /// A TCP address in the form of `host:port`, messages are framed by octet
+    /// counting as described in [RFC 6587].
+    ///
+    /// The connection is established when the first record is logged, and is
+    /// reestablished with exponential backoff if it drops. See
+    /// [`SyslogSinkBuilder::connect_timeout`],
+    /// [`SyslogSinkBuilder::reconnect_backoff`] and
+    /// [`SyslogSinkBuilder::buffer_capacity`].
+    ///
+    /// [RFC 6587]: https://www.rfc-editor.org/rfc/rfc6587#section-3.4.1
+    Tcp(String),
+}
+
+enum Transport {
+    #[cfg(unix)]
+    Unix {
+        socket: UnixDatagram,
+        path: PathBuf,
+    },
+    Udp {
+        socket: UdpSocket,
+        addr: SocketAddr,
+    },
+    Tcp(Mutex<ReconnectingWriter<TcpStream>>),
+}
+
+/// A sink with a syslog daemon as the target.
+///
+/// Each record is formatted by the formatter of the sink, and the result
+/// becomes the MSG part of a syslog message in the [`SyslogFormat`] specified.
+///
+/// # Log Level Mapping
+///
+/// | spdlog-rs  | syslog    |
+/// |------------|-----------|
+/// | `Critical` | `crit`    |
+/// | `Error`    | `err`     |
+/// | `Warn`     | `warning` |
+/// | `Info`     | `info`    |
+/// | `Debug`    | `debug`   |
+/// | `Trace`    | `debug`   |
+///
+/// # Errors
+///
+/// Errors occurred in sending through a Unix socket or UDP are returned from
+/// [`Sink::log`]. For TCP, connection failures and records dropped while
+/// disconnected are reported to the [error handler] of the sink instead, the
+/// same as [`TcpSink`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use spdlog::{
+///     prelude::*,
+///     sink::{SyslogFacility, SyslogSink, SyslogTransport},
+/// };
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// let sink = SyslogSink::builder()
+///     .transport(SyslogTransport::Udp("127.0.0.1:514".into()))
+///     .facility(SyslogFacility::Local0)
+///     .app_name("my-app")
+///     .structured_data("origin", [("software", "my-app"), ("swVersion", "1.0")])
+///     .build()?;
+/// let logger = Logger::builder().sink(Arc::new(sink)).build()?;
+///
+/// info!(logger: logger, "hello, syslog");
+/// # Ok(()) }
+/// ```
+///
+/// [error handler]: Sink::set_error_handler
+/// [`TcpSink`]: crate::sink::TcpSink
+pub struct SyslogSink {
+    common_impl: helper::CommonImpl,
+    format: SyslogFormat,
+    facility: SyslogFacility,
+    hostname: String,
+    app_name: String,
+    procid: u32,
+    msgid: String,
+    structured_data: String,
+    transport: Transport,
+}
+
+impl SyslogSink {
+    const SYSLOG_LEVELS: SyslogLevels = SyslogLevels::new();
+
+    /// Gets a builder of `SyslogSink` with default parameters:
+    ///
+    /// | Parameter           | Default Value                   |
+    /// |---------------------|---------------------------------|
+    /// | [level_filter]      | `All`                           |
+    /// | [formatter]         | `[logger_name] payload`         |
+    /// | [error_handler]     | [default error handler]         |
+    /// |                     |                                 |
+    /// | [transport]         | *must be specified*             |
+    /// | [format]            | `Rfc5424`                       |
+    /// | [facility]          | `User`                          |
+    /// | [hostname]          | the hostname of the local host  |
+    /// | [app_name]          | the file name of the executable |
+    /// | [msgid]             | `-`                             |
+    /// | [structured_data]   | *empty*                         |
+    /// | [connect_timeout]   | `Some(5s)`                      |
+    /// | [reconnect_backoff] | `100ms`, `30s`                  |
+    /// | [buffer_capacity]   | `0`                             |
+    ///
+    /// [level_filter]: SyslogSinkBuilder::level_filter
+    /// [formatter]: SyslogSinkBuilder::formatter
+    /// [error_handler]: SyslogSinkBuilder::error_handler
+    /// [default error handler]: error/index.html#default-error-handler
+    /// [transport]: SyslogSinkBuilder::transport
+    /// [format]: SyslogSinkBuilder::format
+    /// [facility]: SyslogSinkBuilder::facility
+    /// [hostname]: SyslogSinkBuilder::hostname
+    /// [app_name]: SyslogSinkBuilder::app_name
+    /// [msgid]: SyslogSinkBuilder::msgid
+    /// [structured_data]: SyslogSinkBuilder::structured_data
+    /// [connect_timeout]: SyslogSinkBuilder::connect_timeout
+    /// [reconnect_backoff]: SyslogSinkBuilder::reconnect_backoff
+    /// [buffer_capacity]: SyslogSinkBuilder::buffer_capacity
+    #[must_use]
+    pub fn builder() -> SyslogSinkBuilder<()> {
+        SyslogSinkBuilder {
+            common_builder_impl: helper::CommonBuilderImpl::new(),
+            transport: (),
+            format: SyslogFormat::Rfc5424,
+            facility: SyslogFacility::User,
+            hostname: None,
+            app_name: None,
+            msgid: None,
+            structured_data: vec![],
+            connect_timeout: Some(tcp_sink::DEFAULT_CONNECT_TIMEOUT),
+            backoff: Backoff::default(),
+            buffer_capacity: 0,
+        }
+    }
+
+    fn write_message(&self, record: &Record, msg: &str, dest: &mut String) -> Result<()> {
+        let pri = self.facility as u32 * 8 + Self::SYSLOG_LEVELS.level(record.level()) as u32;
+
+        match self.format {
+            SyslogFormat::Rfc5424 => write!(
+                dest,
+                "<{}>1 {} {} {} {} {} {} {}",
+                pri,
+                DateTime::<Utc>::from(record.time()).format("%Y-%m-%dT%H:%M:%S%.6fZ"),
+                self.hostname,
+                self.app_name,
+                self.procid,
+                self.msgid,
+                self.structured_data,
+                msg
+            ),
+            SyslogFormat::Rfc3164 => write!(
+                dest,
+                "<{}>{} {} {}[{}]: {}",
+                pri,
+                DateTime::<Local>::from(record.time()).format("%b %e %H:%M:%S"),
+                self.hostname,
+                self.app_name,
+                self.procid,
+                msg
+            ),
+        }
+        .map_err(Error::FormatRecord)
+    }
+}
+
+impl Sink for SyslogSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        let mut string_buf = StringBuf::new();
+        let mut ctx = FormatterContext::new();
+        self.common_impl
+            .formatter
+            .read()
+            .format(record, &mut string_buf, &mut ctx)?;
+
+        let mut message = String::new();
+        self.write_message(
+            record,
+            string_buf.trim_end_matches(['\r', '\n']),
+            &mut message,
+        )?;
+
+        match &self.transport {
+            #[cfg(unix)]
+            Transport::Unix { socket, path } => socket
+                .send_to(message.as_bytes(), path)
+                .map(|_| ())
+                .map_err(Error::WriteRecord),
+            Transport::Udp { socket, addr } => socket
+                .send_to(message.as_bytes(), addr)
+                .map(|_| ())
+                .map_err(Error::WriteRecord),
+            Transport::Tcp(writer) => {
+                let framed = format!("{} {}", message.len(), message);
+                if let Err(err) = writer.lock_expect().write(framed.as_bytes()) {
+                    self.common_impl.non_returnable_error("SyslogSink", err);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn flush(&self) -> Result<()> {
+        if let Transport::Tcp(writer) = &self.transport {
+            if let Err(err) = writer.lock_expect().flush() {
+                self.common_impl.non_returnable_error("SyslogSink", err);
+            }
+        }
+        Ok(())
+    }
+
+    helper::common_impl!(@Sink: common_impl);
+}
+
+// The default formatter, the level and the time are already in the header.
+#[derive(Clone)]
+struct SyslogFormatter;
+
+impl Formatter for SyslogFormatter {
+    fn format(
+        &self,
+        record: &Record,
+        dest: &mut StringBuf,
+        _ctx: &mut FormatterContext,
+    ) -> Result<()> {
+        (|| {
+            if let Some(logger_name) = record.logger_name() {
+                dest.write_str("[")?;
+                dest.write_str(logger_name)?;
+                dest.write_str("] ")?;
+            }
+            dest.write_str(record.payload())
+        })()
+        .map_err(Error::FormatRecord)
+    }
+}
+
+// --------------------------------------------------
+
+/// #
+#[doc = include_str!("../include/doc/generic-builder-note.md")]
+pub struct SyslogSinkBuilder<ArgTransport> {
+    common_builder_impl: helper::CommonBuilderImpl,
+    transport: ArgTransport,
+    format: SyslogFormat,
+    facility: SyslogFacility,
+    hostname: Option<String>,
+    app_name: Option<String>,
+    msgid: Option<String>,
+    structured_data: Vec<(String, Vec<(String, String)>)>,
+    connect_timeout: Option<Duration>,
+    backoff: Backoff,
+    buffer_capacity: usize,
+}
+
+impl<ArgTransport> SyslogSinkBuilder<ArgTransport> {
+    /// Specifies the transport to send messages through.
+    ///
+    /// This parameter is **required**.
+    #[must_use]
+    pub fn transport(self, transport: SyslogTransport) -> SyslogSinkBuilder<SyslogTransport> {
+        SyslogSinkBuilder {
+            common_builder_impl: self.common_builder_impl,
+            transport,
+            format: self.format,
+            facility: self.facility,
+            hostname: self.hostname,
+            app_name: self.app_name,
+            msgid: self.msgid,
+            structured_data: self.structured_data,
+            connect_timeout: self.connect_timeout,
+            backoff: self.backoff,
+            buffer_capacity: self.buffer_capacity,
+        }
+    }
+
+    /// Specifies the message format.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn format(mut self, format: SyslogFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Specifies the facility.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn facility(mut self, facility: SyslogFacility) -> Self {
+        self.facility = facility;
+        self
+    }
+
+    /// Specifies the HOSTNAME field.
+    ///
+    /// It must consist of 1 to 255 printable ASCII characters without spaces.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn hostname<S>(mut self, hostname: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.hostname = Some(hostname.into());
+        self
+    }
+
+    /// Specifies the APP-NAME field, or the TAG field for RFC 3164.
+    ///
+    /// It must consist of 1 to 48 printable ASCII characters without spaces.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn app_name<S>(mut self, app_name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.app_name = Some(app_name.into());
+        self
+    }
+
+    /// Specifies the MSGID field.
+    ///
+    /// It must consist of 1 to 32 printable ASCII characters without spaces.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn msgid<S>(mut self, msgid: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.msgid = Some(msgid.into());
+        self
+    }
+
+    /// Adds a static STRUCTURED-DATA element, which is attached to every
+    /// message.
+    ///
+    /// The SD-ID and the parameter names must consist of 1 to 32 printable
+    /// ASCII characters except spaces, `=`, `]` and `"`. Parameter values can
+    /// be any UTF-8 string, and are escaped as needed.
+    ///
+    /// This parameter is **optional**, and can be called multiple times.
+    #[must_use]
+    pub fn structured_data<I, K, V>(mut self, id: impl Into<String>, params: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.structured_data.push((
+            id.into(),
+            params
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        ));
+        self
+    }
+
+    /// Specifies the timeout of each connection attempt, only used by
+    /// [`SyslogTransport::Tcp`].
+    ///
+    /// Connecting blocks the thread logging a record, so the timeout should be
+    /// short. If it's `None`, the timeout of the operating system is used,
+    /// which may take minutes for an unreachable host.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn connect_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Specifies the initial and the maximum delay between reconnection
+    /// attempts, only used by [`SyslogTransport::Tcp`].
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn reconnect_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.backoff = Backoff { initial, max };
+        self
+    }
+
+    /// Specifies the maximum number of records buffered while disconnected,
+    /// only used by [`SyslogTransport::Tcp`].
+    ///
+    /// If it's `0`, records logged while disconnected are dropped.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn buffer_capacity(mut self, capacity: usize) -> Self {
+        self.buffer_capacity = capacity;
+        self
+    }
+
+    helper::common_impl!(@SinkBuilder: common_builder_impl);
+}
+
+impl SyslogSinkBuilder<()> {
+    #[doc(hidden)]
+    #[deprecated(note = "\n\n\
+        builder compile-time error:\n\
+        - missing required parameter `transport`\n\n\
+    ")]
+    pub fn build(self, _: Infallible) {}
+}
+
+impl SyslogSinkBuilder<SyslogTransport> {
+    /// Builds a [`SyslogSink`].
+    ///
+    /// # Error
+    ///
+    /// If a header field or structured data is invalid,
+    /// [`Error::InvalidArgument`] will be returned. If an error occurs
+    /// resolving the address, [`Error::ResolveAddress`] will be returned. If
+    /// an error occurs creating or binding a local socket,
+    /// [`Error::BindSocket`] will be returned.
+    pub fn build(self) -> Result<SyslogSink> {
+        let hostname = match self.hostname {
+            Some(hostname) => check_field("hostname", hostname, 255)?,
+            None => local_hostname()
+                .and_then(|hostname| check_field("hostname", hostname, 255).ok())
+                .unwrap_or_else(|| "-".to_string()),
+        };
+        let app_name = match self.app_name {
+            Some(app_name) => check_field("app_name", app_name, 48)?,
+            None => executable_name()
+                .and_then(|name| check_field("app_name", name, 48).ok())
+                .unwrap_or_else(|| "-".to_string()),
+        };
+        let msgid = match self.msgid {
+            Some(msgid) => check_field("msgid", msgid, 32)?,
+            None => "-".to_string(),
+        };
+        let structured_data = render_structured_data(self.structured_data)?;
+
+        let transport = match self.transport {
+            #[cfg(unix)]
+            SyslogTransport::Unix(path) => Transport::Unix {
+                socket: UnixDatagram::unbound().map_err(Error::BindSocket)?,
+                path,
+            },
+            SyslogTransport::Udp(addr) => {
+                let addr = tcp_sink::resolve(&addr).map_err(Error::ResolveAddress)?[0];
+                let local: SocketAddr = if addr.is_ipv4() {
+                    ([0, 0, 0, 0], 0).into()
+                } else {
+                    ([0, 0, 0, 0, 0, 0, 0, 0], 0).into()
+                };
+                Transport::Udp {
+                    socket: UdpSocket::bind(local).map_err(Error::BindSocket)?,
+                    addr,
+                }
+            }
+            SyslogTransport::Tcp(addr) => {
+                let timeout = self.connect_timeout;
+                Transport::Tcp(Mutex::new(ReconnectingWriter::new(
+                    move || tcp_sink::connect(&addr, timeout),
+                    self.backoff,
+                    self.buffer_capacity,
+                )))
+            }
+        };
+
+        Ok(SyslogSink {
+            common_impl: helper::CommonImpl::from_builder_with_formatter(
+                self.common_builder_impl,
+                || Box::new(SyslogFormatter),
+            ),
+            format: self.format,
+            facility: self.facility,
+            hostname,
+            app_name,
+            procid: std::process::id(),
+            msgid,
+            structured_data,
+            transport,
+        })
+    }
+}
+
+fn check_field(name: &str, value: String, max_len: usize) -> Result<String> {
+    if value.is_empty() || value.len() > max_len || !value.bytes().all(|b| b.is_ascii_graphic()) {
+        return Err(Error::InvalidArgument(InvalidArgumentError::Syslog(
+            format!(
+                "{} '{}' must be 1 to {} printable ASCII characters without spaces",
+                name, value, max_len
+            ),
+        )));
+    }
+    Ok(value)
+}
+
+fn check_sd_name(name: String) -> Result<String> {
+    let name = check_field("structured data name", name, 32)?;
+    if name.contains(['=', ']', '"']) {
+        return Err(Error::InvalidArgument(InvalidArgumentError::Syslog(
+            format!("structured data name '{}' contains '=', ']' or '\"'", name),
+        )));
+    }
+    Ok(name)
+}
+
+fn render_structured_data(elements: Vec<(String, Vec<(String, String)>)>) -> Result<String> {
+    if elements.is_empty() {
+        return Ok("-".to_string());
+    }
+
+    let mut rendered = String::new();
+    for (id, params) in elements {
+        rendered.push('[');
+        rendered.push_str(&check_sd_name(id)?);
+        for (name, value) in params {
+            rendered.push(' ');
+            rendered.push_str(&check_sd_name(name)?);
+            rendered.push_str("=\"");
+            for ch in value.chars() {
+                if matches!(ch, '"' | '\\' | ']') {
+                    rendered.push('\\');
+                }
+                rendered.push(ch);
+            }
+            rendered.push('"');
+        }
+        rendered.push(']');
+    }
+    Ok(rendered)
+}
+
+#[must_use]
+pub(crate) fn executable_name() -> Option<String> {
+    let exe = std::env::current_exe().ok()?;
+    Some(exe.file_stem()?.to_str()?.to_string())
+}
+
+#[cfg(unix)]
+#[must_use]
+fn local_hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    let ret = unsafe { libc::gethostname(buf.as_mut_ptr() as *mut c_char, buf.len()) };
+    if ret != 0 {
+        return None;
+    }
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8(buf[..len].to_vec()).ok()
+}
+
+#[cfg(not(unix))]
+#[must_use]
+fn local_hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, net::TcpListener};
+
+    use super::*;
+    use crate::test_utils::*;
+
+    fn build_sink(transport: SyslogTransport, format: SyslogFormat) -> SyslogSink {
+        SyslogSink::builder()
+            .transport(transport)
+            .format(format)
+            .facility(SyslogFacility::Local3)
+            .hostname("host")
+            .app_name("app")
+            .msgid("ID47")
+            .structured_data("meta@32473", [("a", "x\"y]"), ("b", "2")])
+            .error_handler(|err| panic!("{}", err))
+            .build()
+            .unwrap()
+    }
+
+    fn octet_counted_payloads(mut rest: &str) -> Vec<String> {
+        let mut payloads = vec![];
+        while !rest.is_empty() {
+            let (len, msg) = rest.split_once(' ').unwrap();
+            let (msg, next) = msg.split_at(len.parse().unwrap());
+            payloads.push(msg.rsplit(": ").next().unwrap().to_string());
+            rest = next;
+        }
+        payloads
+    }
+
+    fn udp_receiver() -> UdpSocket {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        socket
+    }
+
+    fn recv(socket: &UdpSocket) -> String {
+        let mut buf = [0; 1024];
+        let len = socket.recv(&mut buf).unwrap();
+        String::from_utf8(buf[..len].to_vec()).unwrap()
+    }
+
+    #[test]
+    fn rfc5424() {
+        let socket = udp_receiver();
+        let sink = build_sink(
+            SyslogTransport::Udp(socket.local_addr().unwrap().to_string()),
+            SyslogFormat::Rfc5424,
+        );
+        let record = Record::builder(Level::Warn, "hello\n")
+            .logger_name("logger")
+            .build();
+        sink.log(&record).unwrap();
+
+        // Local3 (19) * 8 + warning (4)
+        let msg = recv(&socket);
+        let (pri, rest) = msg.split_at(6);
+        assert_eq!(pri, "<156>1");
+        let fields = rest.trim_start().splitn(2, ' ').collect::<Vec<_>>();
+        assert!(fields[0].ends_with('Z'));
+        assert_eq!(
+            fields[1],
+            format!(
+                "host app {} ID47 [meta@32473 a=\"x\\\"y\\]\" b=\"2\"] [logger] hello",
+                std::process::id()
+            )
+        );
+    }
+
+    #[test]
+    fn rfc3164() {
+        let socket = udp_receiver();
+        let sink = build_sink(
+            SyslogTransport::Udp(socket.local_addr().unwrap().to_string()),
+            SyslogFormat::Rfc3164,
+        );
+        sink.log(&Record::new(Level::Trace, "hello")).unwrap();
+
+        // Local3 (19) * 8 + debug (7)
+        let msg = recv(&socket);
+        assert!(msg.starts_with("<159>"));
+        assert!(msg.ends_with(&format!(" host app[{}]: hello", std::process::id())));
+    }
+
+    #[test]
+    fn tcp_octet_counting() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let sink = build_sink(
+            SyslogTransport::Tcp(listener.local_addr().unwrap().to_string()),
+            SyslogFormat::Rfc3164,
+        );
+        sink.log(&Record::new(Level::Info, "a")).unwrap();
+        sink.log(&Record::new(Level::Info, "bc")).unwrap();
+
+        let (mut stream, _) = listener.accept().unwrap();
+        drop(sink);
+        let mut buf = String::new();
+        stream.read_to_string(&mut buf).unwrap();
+
+        assert_eq!(octet_counted_payloads(&buf), ["a", "bc"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn tcp_reconnect_and_buffer() {
+        static CONNECT_ERRORS: AtomicUsize = AtomicUsize::new(0);
+
+        fn handle_error(err: Error) {
+            match err {
+                Error::Connect(_) => {
+                    CONNECT_ERRORS.fetch_add(1, Ordering::SeqCst);
+                }
+                Error::WriteRecord(err) if err.kind() == std::io::ErrorKind::NotConnected => {}
+                Error::Multiple(errs) => errs.into_iter().for_each(handle_error),
+                err => panic!("{}", err),
+            }
+        }
+
+        let port = ClosedTcpPort::new();
+        let sink = SyslogSink::builder()
+            .transport(SyslogTransport::Tcp(port.addr().to_string()))
+            .format(SyslogFormat::Rfc3164)
+            .connect_timeout(Some(Duration::from_secs(5)))
+            .reconnect_backoff(Duration::ZERO, Duration::ZERO)
+            .buffer_capacity(2)
+            .error_handler(handle_error)
+            .build()
+            .unwrap();
+
+        sink.log(&Record::new(Level::Info, "1")).unwrap();
+        sink.log(&Record::new(Level::Info, "2")).unwrap();
+        // The buffer is full, this one is dropped.
+        sink.log(&Record::new(Level::Info, "3")).unwrap();
+        assert_eq!(CONNECT_ERRORS.load(Ordering::SeqCst), 3);
+
+        let listener = port.listen();
+        sink.log(&Record::new(Level::Info, "4")).unwrap();
+
+        let (mut stream, _) = listener.accept().unwrap();
+        drop(sink);
+        let mut buf = String::new();
+        stream.read_to_string(&mut buf).unwrap();
+        assert_eq!(octet_counted_payloads(&buf), ["1", "2", "4"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_socket() {
+        let path = TEST_LOGS_PATH.join("syslog_sink.sock");
+        let _ = std::fs::remove_file(&path);
+        let socket = UnixDatagram::bind(&path).unwrap();
+        let sink = build_sink(SyslogTransport::Unix(path), SyslogFormat::Rfc5424);
+        sink.log(&Record::new(Level::Critical, "hello")).unwrap();
+
+        let mut buf = [0; 1024];
+        let len = socket.recv(&mut buf).unwrap();
+        let msg = String::from_utf8(buf[..len].to_vec()).unwrap();
+        assert!(msg.starts_with("<154>1 "));
+        assert!(msg.ends_with("] hello"));
+    }
+
+    #[test]
+    fn invalid_fields() {
+        let result = SyslogSink::builder()
+            .transport(SyslogTransport::Udp("127.0.0.1:514".into()))
+            .app_name("my app")
+            .build();
+        assert!(matches!(
+            result,
+            Err(Error::InvalidArgument(InvalidArgumentError::Syslog(_)))
+        ));
+
+        let result = SyslogSink::builder()
+            .transport(SyslogTransport::Udp("127.0.0.1:514".into()))
+            .structured_data("a=b", [("k", "v")])
+            .build();
+        assert!(matches!(
+            result,
+            Err(Error::InvalidArgument(InvalidArgumentError::Syslog(_)))
+        ));
+    }
+}
@@ -0,0 +1,338 @@
+//! Provides a TCP sink.
+
+use std::{
+    convert::Infallible,
+    io,
+    net::{SocketAddr, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use crate::{
+    formatter::FormatterContext,
+    sink::{
+        helper,
+        reconnect::{Backoff, ReconnectingWriter},
+        Sink,
+    },
+    sync::*,
+    Error, Record, Result, StringBuf,
+};
+
+// Connecting is blocking and happens while logging, so it's bounded by default
+// rather than relying on the timeout of the operating system, which may take
+// minutes for an unreachable host.
+pub(crate) const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A sink with a TCP connection as the target.
+///
+/// It writes formatted records to a remote `host:port`. The connection is
+/// established when the first record is logged, not when the sink is built.
+///
+/// If connecting fails or the connection drops, the sink reconnects with
+/// exponential backoff: the delay starts at `initial` and doubles after each
+/// failed attempt, up to `max`, and it's reset once a connection succeeds.
+/// Reconnection is only attempted when logging or flushing, records logged
+/// while waiting for the next attempt are stored in a bounded buffer and sent
+/// in order once reconnected. When the buffer is full, further records are
+/// dropped.
+///
+/// Connection failures and dropped records are reported to the [error handler]
+/// of the sink instead of being returned from [`Sink::log`], so that an
+/// unavailable endpoint doesn't flood the logger.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::{sync::Arc, time::Duration};
+///
+/// use spdlog::{prelude::*, sink::TcpSink};
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// let sink = TcpSink::builder()
+///     .addr("127.0.0.1:5170")
+///     .reconnect_backoff(Duration::from_millis(500), Duration::from_secs(60))
+///     .buffer_capacity(1024)
+///     .build()?;
+/// let logger = Logger::builder().sink(Arc::new(sink)).build()?;
+///
+/// info!(logger: logger, "hello, remote");
+/// # Ok(()) }
+/// ```
+///
+/// [error handler]: Sink::set_error_handler
+pub struct TcpSink {
+    common_impl: helper::CommonImpl,
+    writer: Mutex<ReconnectingWriter<TcpStream>>,
+}
+
+impl TcpSink {
+    /// Gets a builder of `TcpSink` with default parameters:
+    ///
+    /// | Parameter           | Default Value           |
+    /// |---------------------|-------------------------|
+    /// | [level_filter]      | `All`                   |
+    /// | [formatter]         | `FullFormatter`         |
+    /// | [error_handler]     | [default error handler] |
+    /// |                     |                         |
+    /// | [addr]              | *must be specified*     |
+    /// | [connect_timeout]   | `Some(5s)`              |
+    /// | [reconnect_backoff] | `100ms`, `30s`          |
+    /// | [buffer_capacity]   | `0`                     |
+    ///
+    /// [level_filter]: TcpSinkBuilder::level_filter
+    /// [formatter]: TcpSinkBuilder::formatter
+    /// [error_handler]: TcpSinkBuilder::error_handler
+    /// [default error handler]: error/index.html#default-error-handler
+    /// [addr]: TcpSinkBuilder::addr
+    /// [connect_timeout]: TcpSinkBuilder::connect_timeout
+    /// [reconnect_backoff]: TcpSinkBuilder::reconnect_backoff
+    /// [buffer_capacity]: TcpSinkBuilder::buffer_capacity
+    #[must_use]
+    pub fn builder() -> TcpSinkBuilder<()> {
+        TcpSinkBuilder {
+            common_builder_impl: helper::CommonBuilderImpl::new(),
+            addr: (),
+            connect_timeout: Some(DEFAULT_CONNECT_TIMEOUT),
+            backoff: Backoff::default(),
+            buffer_capacity: 0,
+        }
+    }
+}
+
+impl Sink for TcpSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        let mut string_buf = StringBuf::new();
+        let mut ctx = FormatterContext::new();
+        self.common_impl
+            .formatter
+            .read()
+            .format(record, &mut string_buf, &mut ctx)?;
+
+        if let Err(err) = self.writer.lock_expect().write(string_buf.as_bytes()) {
+            self.common_impl.non_returnable_error("TcpSink", err);
+        }
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<()> {
+        if let Err(err) = self.writer.lock_expect().flush() {
+            self.common_impl.non_returnable_error("TcpSink", err);
+        }
+        Ok(())
+    }
+
+    helper::common_impl!(@Sink: common_impl);
+}
+
+// --------------------------------------------------
+
+/// #
+#[doc = include_str!("../include/doc/generic-builder-note.md")]
+pub struct TcpSinkBuilder<ArgAddr> {
+    common_builder_impl: helper::CommonBuilderImpl,
+    addr: ArgAddr,
+    connect_timeout: Option<Duration>,
+    backoff: Backoff,
+    buffer_capacity: usize,
+}
+
+impl<ArgAddr> TcpSinkBuilder<ArgAddr> {
+    /// The remote address to connect to, in the form of `host:port`.
+    ///
+    /// The host name is resolved again on each connection attempt.
+    ///
+    /// This parameter is **required**.
+    #[must_use]
+    pub fn addr<A>(self, addr: A) -> TcpSinkBuilder<String>
+    where
+        A: Into<String>,
+    {
+        TcpSinkBuilder {
+            common_builder_impl: self.common_builder_impl,
+            addr: addr.into(),
+            connect_timeout: self.connect_timeout,
+            backoff: self.backoff,
+            buffer_capacity: self.buffer_capacity,
+        }
+    }
+
+    /// Specifies the timeout of each connection attempt.
+    ///
+    /// Connecting blocks the thread logging a record, so the timeout should be
+    /// short. If it's `None`, the timeout of the operating system is used,
+    /// which may take minutes for an unreachable host.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn connect_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Specifies the initial and the maximum delay between reconnection
+    /// attempts.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn reconnect_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.backoff = Backoff { initial, max };
+        self
+    }
+
+    /// Specifies the maximum number of records buffered while disconnected.
+    ///
+    /// If it's `0`, records logged while disconnected are dropped.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn buffer_capacity(mut self, capacity: usize) -> Self {
+        self.buffer_capacity = capacity;
+        self
+    }
+
+    helper::common_impl!(@SinkBuilder: common_builder_impl);
+}
+
+impl TcpSinkBuilder<()> {
+    #[doc(hidden)]
+    #[deprecated(note = "\n\n\
+        builder compile-time error:\n\
+        - missing required parameter `addr`\n\n\
+    ")]
+    pub fn build(self, _: Infallible) {}
+}
+
+impl TcpSinkBuilder<String> {
+    /// Builds a [`TcpSink`].
+    ///
+    /// No connection is established until the first record is logged.
+    pub fn build(self) -> Result<TcpSink> {
+        let (addr, timeout) = (self.addr, self.connect_timeout);
+        let writer = ReconnectingWriter::new(
+            move || connect(&addr, timeout),
+            self.backoff,
+            self.buffer_capacity,
+        );
+
+        Ok(TcpSink {
+            common_impl: helper::CommonImpl::from_builder(self.common_builder_impl),
+            writer: Mutex::new(writer),
+        })
+    }
+}
+
+pub(crate) fn connect(addr: &str, timeout: Option<Duration>) -> Result<TcpStream> {
+    let addrs = resolve(addr).map_err(Error::ResolveAddress)?;
+    connect_to(&addrs, timeout).map_err(Error::Connect)
+}
+
+// Resolves `host:port` to at least one address.
+pub(crate) fn resolve(addr: &str) -> io::Result<Vec<SocketAddr>> {
+    let addrs = addr.to_socket_addrs()?.collect::<Vec<_>>();
+    if addrs.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "could not resolve to any addresses",
+        ));
+    }
+    Ok(addrs)
+}
+
+// Connects to the addresses in order, returns the first successful connection.
+// `addrs` must not be empty.
+pub(crate) fn connect_to(addrs: &[SocketAddr], timeout: Option<Duration>) -> io::Result<TcpStream> {
+    let mut last_err = None;
+    for addr in addrs {
+        let result = match timeout {
+            Some(timeout) => TcpStream::connect_timeout(addr, timeout),
+            None => TcpStream::connect(addr),
+        };
+        match result {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_err = Some(err),
+        }
+    }
+    Err(last_err.unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, net::TcpListener};
+
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    fn read_to_end(listener: &TcpListener, logger: Logger) -> String {
+        let (mut stream, _) = listener.accept().unwrap();
+        // Closes the connection, so the peer reads to the end.
+        drop(logger);
+        let mut buf = String::new();
+        stream.read_to_string(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn deliver() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let sink = TcpSink::builder()
+            .addr(listener.local_addr().unwrap().to_string())
+            .formatter(Box::new(NoModFormatter::new()))
+            .error_handler(|err| panic!("{}", err))
+            .build()
+            .unwrap();
+        let logger = build_test_logger(|b| b.sink(Arc::new(sink)));
+
+        info!(logger: logger, "hello\n");
+        warn!(logger: logger, "world\n");
+
+        assert_eq!(read_to_end(&listener, logger), "hello\nworld\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reconnect_and_buffer() {
+        static CONNECT_ERRORS: AtomicUsize = AtomicUsize::new(0);
+
+        fn handle_error(err: Error) {
+            match err {
+                Error::Connect(_) => {
+                    CONNECT_ERRORS.fetch_add(1, Ordering::SeqCst);
+                }
+                Error::WriteRecord(err) if err.kind() == io::ErrorKind::NotConnected => {}
+                Error::Multiple(errs) => errs.into_iter().for_each(handle_error),
+                err => panic!("{}", err),
+            }
+        }
+
+        let port = ClosedTcpPort::new();
+        let addr = port.addr();
+
+        let sink = TcpSink::builder()
+            .addr(addr.to_string())
+            .formatter(Box::new(NoModFormatter::new()))
+            .reconnect_backoff(Duration::ZERO, Duration::ZERO)
+            .buffer_capacity(2)
+            .error_handler(handle_error)
+            .build()
+            .unwrap();
+        let logger = build_test_logger(|b| b.sink(Arc::new(sink)));
+
+        info!(logger: logger, "1\n");
+        info!(logger: logger, "2\n");
+        // The buffer is full, this one is dropped.
+        info!(logger: logger, "3\n");
+        assert_eq!(CONNECT_ERRORS.load(Ordering::SeqCst), 3);
+
+        let listener = port.listen();
+        info!(logger: logger, "4\n");
+
+        assert_eq!(read_to_end(&listener, logger), "1\n2\n4\n");
+    }
+    #[test]
+    fn unresolvable_addr() {
+        assert!(matches!(
+            connect("missing port", None),
+            Err(Error::ResolveAddress(_))
+        ));
+    }
+}
@@ -0,0 +1,357 @@
+//! Provides a UDP sink.
+
+use std::{
+    convert::Infallible,
+    io,
+    net::{SocketAddr, ToSocketAddrs, UdpSocket},
+};
+
+use crate::{
+    error::InvalidArgumentError,
+    formatter::FormatterContext,
+    sink::{helper, Sink},
+    Error, Record, Result, StringBuf,
+};
+
+/// The largest payload of a UDP datagram over IPv4.
+const MAX_UDP_PAYLOAD: usize = 65_507;
+
+/// Policies for records larger than the maximum datagram size of [`UdpSink`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum OversizePolicy {
+    /// Sends only the leading part of the record that fits in one datagram.
+    Truncate,
+    /// Splits the record into multiple datagrams.
+    Split,
+    /// Drops the record, and returns an [`Error::WriteRecord`] from
+    /// [`Sink::log`].
+    Drop,
+}
+
+/// A sink with a UDP address as the target.
+///
+/// It sends one datagram per formatted record, without any acknowledgement or
+/// retransmission, so records may be lost or reordered by the network. The
+/// address is resolved once when the sink is built.
+///
+/// Records larger than the maximum datagram size are handled according to
+/// [`OversizePolicy`]. When truncating or splitting, records are cut at UTF-8
+/// character boundaries whenever possible.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use spdlog::{
+///     prelude::*,
+///     sink::{OversizePolicy, UdpSink},
+/// };
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// let sink = UdpSink::builder()
+///     .addr("127.0.0.1:8125")
+///     .max_datagram_size(1472)
+///     .oversize_policy(OversizePolicy::Split)
+///     .build()?;
+/// let logger = Logger::builder().sink(Arc::new(sink)).build()?;
+///
+/// info!(logger: logger, "requests:1|c");
+/// # Ok(()) }
+/// ```
+pub struct UdpSink {
+    common_impl: helper::CommonImpl,
+    socket: UdpSocket,
+    addr: SocketAddr,
+    max_datagram_size: usize,
+    oversize_policy: OversizePolicy,
+}
+
+impl UdpSink {
+    /// Gets a builder of `UdpSink` with default parameters:
+    ///
+    /// | Parameter           | Default Value           |
+    /// |---------------------|-------------------------|
+    /// | [level_filter]      | `All`                   |
+    /// | [formatter]         | `FullFormatter`         |
+    /// | [error_handler]     | [default error handler] |
+    /// |                     |                         |
+    /// | [addr]              | *must be specified*     |
+    /// | [max_datagram_size] | `65507`                 |
+    /// | [oversize_policy]   | `Truncate`              |
+    ///
+    /// [level_filter]: UdpSinkBuilder::level_filter
+    /// [formatter]: UdpSinkBuilder::formatter
+    /// [error_handler]: UdpSinkBuilder::error_handler
+    /// [default error handler]: error/index.html#default-error-handler
+    /// [addr]: UdpSinkBuilder::addr
+    /// [max_datagram_size]: UdpSinkBuilder::max_datagram_size
+    /// [oversize_policy]: UdpSinkBuilder::oversize_policy
+    #[must_use]
+    pub fn builder() -> UdpSinkBuilder<()> {
+        UdpSinkBuilder {
+            common_builder_impl: helper::CommonBuilderImpl::new(),
+            addr: (),
+            max_datagram_size: MAX_UDP_PAYLOAD,
+            oversize_policy: OversizePolicy::Truncate,
+        }
+    }
+
+    fn send(&self, datagram: &[u8]) -> Result<()> {
+        self.socket
+            .send_to(datagram, self.addr)
+            .map(|_| ())
+            .map_err(Error::WriteRecord)
+    }
+}
+
+impl Sink for UdpSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        let mut string_buf = StringBuf::new();
+        let mut ctx = FormatterContext::new();
+        self.common_impl
+            .formatter
+            .read()
+            .format(record, &mut string_buf, &mut ctx)?;
+
+        let mut bytes = string_buf.as_bytes();
+        if bytes.len() <= self.max_datagram_size {
+            return self.send(bytes);
+        }
+
+        match self.oversize_policy {
+            OversizePolicy::Truncate => {
+                self.send(&bytes[..chunk_len(bytes, self.max_datagram_size)])
+            }
+            OversizePolicy::Split => {
+                while !bytes.is_empty() {
+                    let (chunk, rest) = bytes.split_at(chunk_len(bytes, self.max_datagram_size));
+                    self.send(chunk)?;
+                    bytes = rest;
+                }
+                Ok(())
+            }
+            OversizePolicy::Drop => Err(Error::WriteRecord(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "record of {} bytes exceeds the max datagram size {}, dropped",
+                    bytes.len(),
+                    self.max_datagram_size
+                ),
+            ))),
+        }
+    }
+
+    fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    helper::common_impl!(@Sink: common_impl);
+}
+
+// Gets the length of the leading part of `bytes` that fits in `max`, without
+// splitting a UTF-8 character unless a single character is larger than `max`.
+#[must_use]
+fn chunk_len(bytes: &[u8], max: usize) -> usize {
+    if bytes.len() <= max {
+        return bytes.len();
+    }
+    let is_char_boundary = |index: usize| (bytes[index] & 0b1100_0000) != 0b1000_0000;
+    (1..=max)
+        .rev()
+        .find(|&index| is_char_boundary(index))
+        .unwrap_or(max)
+}
+
+// --------------------------------------------------
+
+/// #
+#[doc = include_str!("../include/doc/generic-builder-note.md")]
+pub struct UdpSinkBuilder<ArgAddr> {
+    common_builder_impl: helper::CommonBuilderImpl,
+    addr: ArgAddr,
+    max_datagram_size: usize,
+    oversize_policy: OversizePolicy,
+}
+
+impl<ArgAddr> UdpSinkBuilder<ArgAddr> {
+    /// The remote address to send datagrams to, in the form of `host:port`.
+    ///
+    /// This parameter is **required**.
+    #[must_use]
+    pub fn addr<A>(self, addr: A) -> UdpSinkBuilder<String>
+    where
+        A: Into<String>,
+    {
+        UdpSinkBuilder {
+            common_builder_impl: self.common_builder_impl,
+            addr: addr.into(),
+            max_datagram_size: self.max_datagram_size,
+            oversize_policy: self.oversize_policy,
+        }
+    }
+
+    /// Specifies the maximum size of a datagram in bytes.
+    ///
+    /// It must be in the range `1..=65507`.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn max_datagram_size(mut self, size: usize) -> Self {
+        self.max_datagram_size = size;
+        self
+    }
+
+    /// Specifies how records larger than the maximum datagram size are
+    /// handled.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn oversize_policy(mut self, policy: OversizePolicy) -> Self {
+        self.oversize_policy = policy;
+        self
+    }
+
+    helper::common_impl!(@SinkBuilder: common_builder_impl);
+}
+
+impl UdpSinkBuilder<()> {
+    #[doc(hidden)]
+    #[deprecated(note = "\n\n\
+        builder compile-time error:\n\
+        - missing required parameter `addr`\n\n\
+    ")]
+    pub fn build(self, _: Infallible) {}
+}
+
+impl UdpSinkBuilder<String> {
+    /// Builds a [`UdpSink`].
+    ///
+    /// # Error
+    ///
+    /// If the maximum datagram size is out of range,
+    /// [`Error::InvalidArgument`] will be returned. If an error occurs
+    /// resolving the address, [`Error::ResolveAddress`] will be returned. If
+    /// an error occurs binding a local socket, [`Error::BindSocket`] will be
+    /// returned.
+    pub fn build(self) -> Result<UdpSink> {
+        if !(1..=MAX_UDP_PAYLOAD).contains(&self.max_datagram_size) {
+            return Err(Error::InvalidArgument(
+                InvalidArgumentError::MaxDatagramSize(format!(
+                    "must be in the range 1..={}, got {}",
+                    MAX_UDP_PAYLOAD, self.max_datagram_size
+                )),
+            ));
+        }
+
+        let addr = self
+            .addr
+            .to_socket_addrs()
+            .and_then(|mut addrs| {
+                addrs.next().ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "could not resolve to any addresses",
+                    )
+                })
+            })
+            .map_err(Error::ResolveAddress)?;
+        let local: SocketAddr = if addr.is_ipv4() {
+            ([0, 0, 0, 0], 0).into()
+        } else {
+            ([0, 0, 0, 0, 0, 0, 0, 0], 0).into()
+        };
+        let socket = UdpSocket::bind(local).map_err(Error::BindSocket)?;
+
+        Ok(UdpSink {
+            common_impl: helper::CommonImpl::from_builder(self.common_builder_impl),
+            socket,
+            addr,
+            max_datagram_size: self.max_datagram_size,
+            oversize_policy: self.oversize_policy,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{test_utils::*, Level};
+
+    fn receiver() -> UdpSocket {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        socket
+    }
+
+    fn recv(socket: &UdpSocket) -> String {
+        let mut buf = [0; 1024];
+        let len = socket.recv(&mut buf).unwrap();
+        String::from_utf8_lossy(&buf[..len]).into_owned()
+    }
+
+    fn build_sink(receiver: &UdpSocket, policy: OversizePolicy) -> UdpSink {
+        UdpSink::builder()
+            .addr(receiver.local_addr().unwrap().to_string())
+            .formatter(Box::new(NoModFormatter::new()))
+            .max_datagram_size(8)
+            .oversize_policy(policy)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn chunk_boundary() {
+        assert_eq!(chunk_len(b"abc", 8), 3);
+        assert_eq!(chunk_len(b"abcdef", 4), 4);
+        // "é" is 2 bytes.
+        assert_eq!(chunk_len("abcé".as_bytes(), 4), 3);
+        assert_eq!(chunk_len("é".as_bytes(), 1), 1);
+    }
+
+    #[test]
+    fn oversize_policies() {
+        let record = Record::new(Level::Info, "0123456é89");
+
+        let socket = receiver();
+        let sink = build_sink(&socket, OversizePolicy::Truncate);
+        sink.log(&Record::new(Level::Info, "short")).unwrap();
+        sink.log(&record).unwrap();
+        assert_eq!(recv(&socket), "short");
+        assert_eq!(recv(&socket), "0123456");
+
+        let socket = receiver();
+        let sink = build_sink(&socket, OversizePolicy::Split);
+        sink.log(&record).unwrap();
+        assert_eq!(recv(&socket), "0123456");
+        assert_eq!(recv(&socket), "é89");
+
+        let socket = receiver();
+        let sink = build_sink(&socket, OversizePolicy::Drop);
+        assert!(matches!(sink.log(&record), Err(Error::WriteRecord(_))));
+    }
+
+    #[test]
+    fn invalid_size() {
+        let result = UdpSink::builder()
+            .addr("127.0.0.1:0")
+            .max_datagram_size(0)
+            .build();
+        assert!(matches!(
+            result,
+            Err(Error::InvalidArgument(
+                InvalidArgumentError::MaxDatagramSize(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn unresolvable_addr() {
+        let result = UdpSink::builder().addr("missing port").build();
+        assert!(matches!(result, Err(Error::ResolveAddress(_))));
+    }
+}
@@ -0,0 +1,446 @@
+//! Provides a Unix domain socket sink.
+
+use std::{
+    convert::Infallible,
+    io::{self, Write},
+    os::unix::net::{UnixDatagram, UnixStream},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    formatter::FormatterContext,
+    sink::{
+        helper,
+        reconnect::{Backoff, ReconnectingWriter},
+        Sink,
+    },
+    sync::*,
+    Error, Record, Result, StringBuf,
+};
+
+/// Types of Unix domain sockets.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum UnixSocketType {
+    /// `SOCK_STREAM`, a connection-oriented byte stream.
+    Stream,
+    /// `SOCK_DGRAM`, one datagram per record.
+    Datagram,
+}
+
+/// Ways of delimiting records sent by [`UnixSocketSink`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum SocketFraming {
+    /// Each record is terminated by a `\n`.
+    ///
+    /// A trailing line ending written by the formatter is replaced, so each
+    /// record ends with exactly one `\n`.
+    Newline,
+    /// Each record is prefixed by its length in bytes, as a 32-bit big-endian
+    /// unsigned integer.
+    LengthPrefixed,
+}
+
+enum Connection {
+    Stream(UnixStream),
+    Datagram(UnixDatagram),
+}
+
+impl Connection {
+    fn connect(path: &Path, socket_type: UnixSocketType) -> io::Result<Self> {
+        match socket_type {
+            UnixSocketType::Stream => UnixStream::connect(path).map(Connection::Stream),
+            UnixSocketType::Datagram => {
+                let socket = UnixDatagram::unbound()?;
+                socket.connect(path)?;
+                Ok(Connection::Datagram(socket))
+            }
+        }
+    }
+}
+
+impl Write for Connection {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Connection::Stream(stream) => stream.write(buf),
+            Connection::Datagram(socket) => socket.send(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Connection::Stream(stream) => stream.flush(),
+            Connection::Datagram(_) => Ok(()),
+        }
+    }
+}
+
+/// A sink with a Unix domain socket as the target.
+///
+/// It connects to a socket at the given path, e.g. a local log shipper, and
+/// sends each formatted record framed by the specified [`SocketFraming`].
+/// With [`UnixSocketType::Stream`], records are written to the byte stream one
+/// after another and the receiver splits them by the framing. With
+/// [`UnixSocketType::Datagram`], each record is sent in its own datagram, and a
+/// record exceeding the maximum datagram size of the socket is dropped.
+///
+/// The connection is established when the first record is logged. If
+/// connecting fails or the connection drops, the sink reconnects with
+/// exponential backoff, and buffers a bounded number of records in the
+/// meantime, the same as [`TcpSink`]. Records failed by other errors are
+/// dropped instead of being retried. Connection failures and dropped records
+/// are reported to the [error handler] of the sink.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use spdlog::{
+///     prelude::*,
+///     sink::{SocketFraming, UnixSocketSink, UnixSocketType},
+/// };
+///
+/// # fn main() -> Result<(), spdlog::Error> {
+/// let sink = UnixSocketSink::builder()
+///     .path("/run/shipper.sock")
+///     .socket_type(UnixSocketType::Stream)
+///     .framing(SocketFraming::LengthPrefixed)
+///     .build()?;
+/// let logger = Logger::builder().sink(Arc::new(sink)).build()?;
+///
+/// info!(logger: logger, "hello, shipper");
+/// # Ok(()) }
+/// ```
+///
+/// [`TcpSink`]: crate::sink::TcpSink
+/// [error handler]: Sink::set_error_handler
+pub struct UnixSocketSink {
+    common_impl: helper::CommonImpl,
+    framing: SocketFraming,
+    writer: Mutex<ReconnectingWriter<Connection>>,
+}
+
+impl UnixSocketSink {
+    /// Gets a builder of `UnixSocketSink` with default parameters:
+    ///
+    /// | Parameter           | Default Value           |
+    /// |---------------------|-------------------------|
+    /// | [level_filter]      | `All`                   |
+    /// | [formatter]         | `FullFormatter`         |
+    /// | [error_handler]     | [default error handler] |
+    /// |                     |                         |
+    /// | [path]              | *must be specified*     |
+    /// | [socket_type]       | `Stream`                |
+    /// | [framing]           | `Newline`               |
+    /// | [reconnect_backoff] | `100ms`, `30s`          |
+    /// | [buffer_capacity]   | `0`                     |
+    ///
+    /// [level_filter]: UnixSocketSinkBuilder::level_filter
+    /// [formatter]: UnixSocketSinkBuilder::formatter
+    /// [error_handler]: UnixSocketSinkBuilder::error_handler
+    /// [default error handler]: error/index.html#default-error-handler
+    /// [path]: UnixSocketSinkBuilder::path
+    /// [socket_type]: UnixSocketSinkBuilder::socket_type
+    /// [framing]: UnixSocketSinkBuilder::framing
+    /// [reconnect_backoff]: UnixSocketSinkBuilder::reconnect_backoff
+    /// [buffer_capacity]: UnixSocketSinkBuilder::buffer_capacity
+    #[must_use]
+    pub fn builder() -> UnixSocketSinkBuilder<()> {
+        UnixSocketSinkBuilder {
+            common_builder_impl: helper::CommonBuilderImpl::new(),
+            path: (),
+            socket_type: UnixSocketType::Stream,
+            framing: SocketFraming::Newline,
+            backoff: Backoff::default(),
+            buffer_capacity: 0,
+        }
+    }
+}
+
+impl Sink for UnixSocketSink {
+    fn log(&self, record: &Record) -> Result<()> {
+        let mut string_buf = StringBuf::new();
+        let mut ctx = FormatterContext::new();
+        self.common_impl
+            .formatter
+            .read()
+            .format(record, &mut string_buf, &mut ctx)?;
+
+        let framed = frame(self.framing, string_buf.as_bytes());
+        if let Err(err) = self.writer.lock_expect().write(&framed) {
+            self.common_impl.non_returnable_error("UnixSocketSink", err);
+        }
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<()> {
+        if let Err(err) = self.writer.lock_expect().flush() {
+            self.common_impl.non_returnable_error("UnixSocketSink", err);
+        }
+        Ok(())
+    }
+
+    helper::common_impl!(@Sink: common_impl);
+}
+
+#[must_use]
+fn frame(framing: SocketFraming, record: &[u8]) -> Vec<u8> {
+    match framing {
+        SocketFraming::Newline => {
+            let mut line = record;
+            while let Some((b'\n' | b'\r', rest)) = line.split_last() {
+                line = rest;
+            }
+            let mut framed = Vec::with_capacity(line.len() + 1);
+            framed.extend_from_slice(line);
+            framed.push(b'\n');
+            framed
+        }
+        SocketFraming::LengthPrefixed => {
+            let mut framed = Vec::with_capacity(record.len() + 4);
+            framed.extend_from_slice(&(record.len() as u32).to_be_bytes());
+            framed.extend_from_slice(record);
+            framed
+        }
+    }
+}
+
+// --------------------------------------------------
+
+/// #
+#[doc = include_str!("../include/doc/generic-builder-note.md")]
+pub struct UnixSocketSinkBuilder<ArgPath> {
+    common_builder_impl: helper::CommonBuilderImpl,
+    path: ArgPath,
+    socket_type: UnixSocketType,
+    framing: SocketFraming,
+    backoff: Backoff,
+    buffer_capacity: usize,
+}
+
+impl<ArgPath> UnixSocketSinkBuilder<ArgPath> {
+    /// The path of the socket to connect to.
+    ///
+    /// This parameter is **required**.
+    #[must_use]
+    pub fn path<P>(self, path: P) -> UnixSocketSinkBuilder<PathBuf>
+    where
+        P: Into<PathBuf>,
+    {
+        UnixSocketSinkBuilder {
+            common_builder_impl: self.common_builder_impl,
+            path: path.into(),
+            socket_type: self.socket_type,
+            framing: self.framing,
+            backoff: self.backoff,
+            buffer_capacity: self.buffer_capacity,
+        }
+    }
+
+    /// Specifies the type of the socket.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn socket_type(mut self, socket_type: UnixSocketType) -> Self {
+        self.socket_type = socket_type;
+        self
+    }
+
+    /// Specifies how records are delimited.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn framing(mut self, framing: SocketFraming) -> Self {
+        self.framing = framing;
+        self
+    }
+
+    /// Specifies the initial and the maximum delay between reconnection
+    /// attempts.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn reconnect_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.backoff = Backoff { initial, max };
+        self
+    }
+
+    /// Specifies the maximum number of records buffered while disconnected.
+    ///
+    /// If it's `0`, records logged while disconnected are dropped.
+    ///
+    /// This parameter is **optional**.
+    #[must_use]
+    pub fn buffer_capacity(mut self, capacity: usize) -> Self {
+        self.buffer_capacity = capacity;
+        self
+    }
+
+    helper::common_impl!(@SinkBuilder: common_builder_impl);
+}
+
+impl UnixSocketSinkBuilder<()> {
+    #[doc(hidden)]
+    #[deprecated(note = "\n\n\
+        builder compile-time error:\n\
+        - missing required parameter `path`\n\n\
+    ")]
+    pub fn build(self, _: Infallible) {}
+}
+
+impl UnixSocketSinkBuilder<PathBuf> {
+    /// Builds a [`UnixSocketSink`].
+    ///
+    /// No connection is established until the first record is logged.
+    pub fn build(self) -> Result<UnixSocketSink> {
+        let (path, socket_type) = (self.path, self.socket_type);
+        let writer = ReconnectingWriter::new(
+            move || Connection::connect(&path, socket_type).map_err(Error::Connect),
+            self.backoff,
+            self.buffer_capacity,
+        );
+
+        Ok(UnixSocketSink {
+            common_impl: helper::CommonImpl::from_builder(self.common_builder_impl),
+            framing: self.framing,
+            writer: Mutex::new(writer),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, os::unix::net::UnixListener};
+
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    fn socket_path(name: &str) -> PathBuf {
+        let path = TEST_LOGS_PATH.join(name);
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn build_logger(path: &Path, socket_type: UnixSocketType, framing: SocketFraming) -> Logger {
+        let sink = UnixSocketSink::builder()
+            .path(path)
+            .socket_type(socket_type)
+            .framing(framing)
+            .formatter(Box::new(NoModFormatter::new()))
+            .reconnect_backoff(Duration::ZERO, Duration::ZERO)
+            .buffer_capacity(8)
+            .error_handler(|err| panic!("{}", err))
+            .build()
+            .unwrap();
+        build_test_logger(|b| b.sink(Arc::new(sink)))
+    }
+
+    #[test]
+    fn framing() {
+        assert_eq!(frame(SocketFraming::Newline, b"a"), b"a\n");
+        assert_eq!(frame(SocketFraming::Newline, b"a\r\n"), b"a\n");
+        assert_eq!(frame(SocketFraming::LengthPrefixed, b"ab"), b"\0\0\0\x02ab");
+    }
+
+    #[test]
+    fn stream() {
+        let path = socket_path("unix_socket_sink_stream.sock");
+        let listener = UnixListener::bind(&path).unwrap();
+        let logger = build_logger(&path, UnixSocketType::Stream, SocketFraming::Newline);
+
+        info!(logger: logger, "hello");
+        info!(logger: logger, "world\n");
+
+        let (mut stream, _) = listener.accept().unwrap();
+        drop(logger);
+        let mut buf = String::new();
+        stream.read_to_string(&mut buf).unwrap();
+        assert_eq!(buf, "hello\nworld\n");
+    }
+
+    #[test]
+    fn datagram() {
+        let path = socket_path("unix_socket_sink_datagram.sock");
+        let socket = UnixDatagram::bind(&path).unwrap();
+        let logger = build_logger(
+            &path,
+            UnixSocketType::Datagram,
+            SocketFraming::LengthPrefixed,
+        );
+
+        info!(logger: logger, "hello");
+        info!(logger: logger, "world");
+
+        let mut buf = [0; 64];
+        let len = socket.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"\0\0\0\x05hello");
+        let len = socket.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"\0\0\0\x05world");
+    }
+
+    #[test]
+    fn oversized_datagram() {
+        static ERRORS: AtomicUsize = AtomicUsize::new(0);
+
+        let path = socket_path("unix_socket_sink_oversized.sock");
+        let socket = UnixDatagram::bind(&path).unwrap();
+        let logger = {
+            let sink = UnixSocketSink::builder()
+                .path(&path)
+                .socket_type(UnixSocketType::Datagram)
+                .formatter(Box::new(NoModFormatter::new()))
+                .buffer_capacity(8)
+                .error_handler(|err| match err {
+                    crate::Error::WriteRecord(_) => {
+                        ERRORS.fetch_add(1, Ordering::SeqCst);
+                    }
+                    err => panic!("{}", err),
+                })
+                .build()
+                .unwrap();
+            build_test_logger(|b| b.sink(Arc::new(sink)))
+        };
+
+        // Exceeds the maximum datagram size, dropped rather than buffered.
+        info!(logger: logger, "{}", "x".repeat(16 * 1024 * 1024));
+        assert_eq!(ERRORS.load(Ordering::SeqCst), 1);
+        info!(logger: logger, "small");
+        assert_eq!(ERRORS.load(Ordering::SeqCst), 1);
+
+        let mut buf = [0; 64];
+        let len = socket.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"small\n");
+    }
+
+    #[test]
+    fn reconnect() {
+        let path = socket_path("unix_socket_sink_reconnect.sock");
+        let logger = {
+            let sink = UnixSocketSink::builder()
+                .path(&path)
+                .formatter(Box::new(NoModFormatter::new()))
+                .reconnect_backoff(Duration::ZERO, Duration::ZERO)
+                .buffer_capacity(8)
+                .error_handler(|err| match err {
+                    crate::Error::Connect(_) => {}
+                    err => panic!("{}", err),
+                })
+                .build()
+                .unwrap();
+            build_test_logger(|b| b.sink(Arc::new(sink)))
+        };
+
+        // Nothing is listening yet, buffered.
+        info!(logger: logger, "1");
+
+        let listener = UnixListener::bind(&path).unwrap();
+        info!(logger: logger, "2");
+
+        let (mut stream, _) = listener.accept().unwrap();
+        drop(logger);
+        let mut buf = String::new();
+        stream.read_to_string(&mut buf).unwrap();
+        assert_eq!(buf, "1\n2\n");
+    }
+}
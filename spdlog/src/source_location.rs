@@ -98,6 +98,22 @@ impl SourceLocation {
             }),
         }
     }
+
+    #[cfg(feature = "tracing")]
+    #[must_use]
+    pub(crate) fn from_tracing_metadata(metadata: &tracing::Metadata<'static>) -> Option<Self> {
+        let (module_path, file, line) = (metadata.module_path(), metadata.file(), metadata.line());
+
+        match (module_path, file, line) {
+            (None, None, None) => None,
+            _ => Some(Self {
+                module_path: module_path.unwrap_or(""),
+                file: file.unwrap_or(""),
+                line: line.unwrap_or(0),
+                column: 0,
+            }),
+        }
+    }
 }
 
 /// Constructs a [`SourceLocation`] with current source location.
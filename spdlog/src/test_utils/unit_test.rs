@@ -17,3 +17,61 @@ pub static TEST_LOGS_PATH: Lazy<PathBuf> = Lazy::new(|| {
     }
     path
 });
+
+// A local TCP port that refuses connections until `listen` is called.
+//
+// Unlike binding a listener and dropping it, the port is never released in
+// between, so no other socket can take it.
+#[cfg(unix)]
+pub struct ClosedTcpPort {
+    fd: std::os::unix::io::RawFd,
+    addr: std::net::SocketAddr,
+}
+
+#[cfg(unix)]
+impl ClosedTcpPort {
+    #[must_use]
+    pub fn new() -> Self {
+        use std::{mem, net::Ipv4Addr};
+
+        unsafe {
+            let fd = libc::socket(libc::AF_INET, libc::SOCK_STREAM, 0);
+            assert!(fd >= 0);
+
+            let mut sockaddr: libc::sockaddr_in = mem::zeroed();
+            sockaddr.sin_family = libc::AF_INET as libc::sa_family_t;
+            sockaddr.sin_addr.s_addr = u32::from(Ipv4Addr::LOCALHOST).to_be();
+            let mut len = mem::size_of::<libc::sockaddr_in>() as libc::socklen_t;
+            let sockaddr_ptr = &mut sockaddr as *mut libc::sockaddr_in as *mut libc::sockaddr;
+            assert_eq!(libc::bind(fd, sockaddr_ptr, len), 0);
+            assert_eq!(libc::getsockname(fd, sockaddr_ptr, &mut len), 0);
+
+            let addr = (Ipv4Addr::LOCALHOST, u16::from_be(sockaddr.sin_port)).into();
+            Self { fd, addr }
+        }
+    }
+
+    #[must_use]
+    pub fn addr(&self) -> std::net::SocketAddr {
+        self.addr
+    }
+
+    #[must_use]
+    pub fn listen(self) -> std::net::TcpListener {
+        use std::os::unix::io::FromRawFd;
+
+        let fd = self.fd;
+        std::mem::forget(self);
+        unsafe {
+            assert_eq!(libc::listen(fd, 128), 0);
+            std::net::TcpListener::from_raw_fd(fd)
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for ClosedTcpPort {
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
@@ -0,0 +1,313 @@
+use std::{error::Error as StdError, fmt, time::SystemTime};
+
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+use crate::{
+    default_logger,
+    kv::{Key, PairOwned, Value},
+    sync::*,
+    Logger, Record,
+};
+
+/// Bridge layer for compatible [tracing crate].
+///
+/// `TracingLayer` is a [`tracing_subscriber::Layer`] that converts events from
+/// `tracing` crate into [`Record`]s and forwards them to the global default
+/// logger or the logger set by [`TracingLayer::with_logger`].
+///
+/// Events are converted as follows:
+///
+/// - The level of the event is mapped to [`Level`] of the same name.
+/// - The target of the event is used as the logger name, or the name of the
+///   logger if [`TracingLayer::prefer_logger_name`] is enabled. Either of them
+///   falls back to the other if it's empty.
+/// - The `message` field of the event becomes the payload, other fields are
+///   available via [`Record::key_values`].
+/// - If the event occurs inside spans, a `span` key with the span names from
+///   root to leaf joined by `:` is added, followed by the fields of those spans
+///   and then the fields of the event.
+///
+/// Filtering is done by the logger level filter, the layer itself does not
+/// disable any callsite so it can be composed with other layers freely.
+///
+/// ## Examples
+///
+/// ```
+/// use tracing_subscriber::layer::SubscriberExt;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let subscriber = tracing_subscriber::registry().with(spdlog::TracingLayer::new());
+/// tracing::subscriber::set_global_default(subscriber)?;
+///
+/// tracing::info!(user_id = 42, "hello");
+/// # Ok(()) }
+/// ```
+///
+/// [tracing crate]: https://crates.io/crates/tracing
+/// [`Level`]: crate::Level
+#[derive(Default)]
+pub struct TracingLayer {
+    logger: Option<Arc<Logger>>,
+    prefer_logger_name: bool,
+}
+
+impl TracingLayer {
+    /// Constructs a `TracingLayer` that forwards events to the global default
+    /// logger.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a `TracingLayer` that forwards events to the given logger.
+    #[must_use]
+    pub fn with_logger(logger: Arc<Logger>) -> Self {
+        Self {
+            logger: Some(logger),
+            prefer_logger_name: false,
+        }
+    }
+
+    /// Specifies whether to use the name of the logger as the logger name of
+    /// records instead of the target of events.
+    ///
+    /// It's disabled by default.
+    #[must_use]
+    pub fn prefer_logger_name(mut self, enabled: bool) -> Self {
+        self.prefer_logger_name = enabled;
+        self
+    }
+
+    #[must_use]
+    fn logger(&self) -> Arc<Logger> {
+        self.logger.clone().unwrap_or_else(default_logger)
+    }
+}
+
+impl<S> Layer<S> for TracingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut fields = SpanFields(vec![]);
+            attrs.record(&mut FieldVisitor::new(None, &mut fields.0));
+            span.extensions_mut().insert(fields);
+        }
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut extensions = span.extensions_mut();
+            if let Some(fields) = extensions.get_mut::<SpanFields>() {
+                values.record(&mut FieldVisitor::new(None, &mut fields.0));
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let logger = self.logger();
+        let metadata = event.metadata();
+        if !logger.should_log((*metadata.level()).into()) {
+            return;
+        }
+
+        let mut kvs = vec![];
+        if let Some(scope) = ctx.event_scope(event) {
+            let mut names = String::new();
+            let mut span_kvs = vec![];
+            for span in scope.from_root() {
+                if !names.is_empty() {
+                    names.push(':');
+                }
+                names.push_str(span.name());
+                if let Some(fields) = span.extensions().get::<SpanFields>() {
+                    span_kvs.extend(fields.0.iter().cloned());
+                }
+            }
+            if !names.is_empty() {
+                kvs.push((
+                    Key::__from_static_str("span"),
+                    Value::from(names.as_str()).to_owned(),
+                ));
+            }
+            kvs.append(&mut span_kvs);
+        }
+
+        let mut payload = String::new();
+        event.record(&mut FieldVisitor::new(Some(&mut payload), &mut kvs));
+
+        let target = Some(metadata.target()).filter(|target| !target.is_empty());
+        let logger_name = if self.prefer_logger_name {
+            logger.name().or(target)
+        } else {
+            target.or_else(|| logger.name())
+        };
+        let record =
+            Record::from_tracing_event(logger_name, metadata, payload, kvs, SystemTime::now());
+        logger.log(&record);
+    }
+}
+
+// Stored in the extensions of spans.
+struct SpanFields(Vec<PairOwned>);
+
+struct FieldVisitor<'a> {
+    payload: Option<&'a mut String>,
+    kvs: &'a mut Vec<PairOwned>,
+}
+
+impl<'a> FieldVisitor<'a> {
+    #[must_use]
+    fn new(payload: Option<&'a mut String>, kvs: &'a mut Vec<PairOwned>) -> Self {
+        Self { payload, kvs }
+    }
+
+    fn push(&mut self, field: &Field, value: Value) {
+        match &mut self.payload {
+            Some(payload) if field.name() == "message" => {
+                **payload = value.to_string();
+            }
+            _ => self
+                .kvs
+                .push((Key::__from_static_str(field.name()), value.to_owned())),
+        }
+    }
+}
+
+impl Visit for FieldVisitor<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.push(field, value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.push(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.push(field, value.into());
+    }
+
+    fn record_i128(&mut self, field: &Field, value: i128) {
+        self.push(field, value.into());
+    }
+
+    fn record_u128(&mut self, field: &Field, value: u128) {
+        self.push(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.push(field, value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.push(field, value.into());
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn StdError + 'static)) {
+        self.push(field, Value::from_display(&value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.push(field, Value::from_debug(&value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+    use crate::{test_utils::*, Level};
+
+    #[test]
+    fn forward_events() {
+        let sink = Arc::new(TestSink::new());
+        let logger = Arc::new(build_test_logger(|b| {
+            b.sink(sink.clone())
+                .level_filter(crate::LevelFilter::MoreSevereEqual(Level::Debug))
+        }));
+        let subscriber = tracing_subscriber::registry().with(TracingLayer::with_logger(logger));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("hello {}", 1);
+            tracing::trace!("filtered out");
+            tracing::warn!(target: "my_target", user_id = 42, ok = true, "world");
+        });
+
+        let records = sink.records();
+        assert_eq!(records.len(), 2);
+
+        assert_eq!(records[0].level(), Level::Info);
+        assert_eq!(records[0].logger_name(), Some(module_path!()));
+        assert_eq!(records[0].payload(), "hello 1");
+        assert!(records[0].key_values().is_empty());
+
+        assert_eq!(records[1].level(), Level::Warn);
+        assert_eq!(records[1].logger_name(), Some("my_target"));
+        assert_eq!(records[1].payload(), "world");
+        let kvs = records[1].key_values();
+        assert_eq!(kvs.get("user_id").and_then(|v| v.to_i64()), Some(42));
+        assert_eq!(kvs.get("ok").and_then(|v| v.to_bool()), Some(true));
+    }
+
+    #[test]
+    fn logger_name() {
+        let sink = Arc::new(TestSink::new());
+        let logger = Arc::new(build_test_logger(|b| b.sink(sink.clone()).name("named")));
+
+        let subscriber =
+            tracing_subscriber::registry().with(TracingLayer::with_logger(logger.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(target: "my_target", "default");
+        });
+        let subscriber = tracing_subscriber::registry()
+            .with(TracingLayer::with_logger(logger).prefer_logger_name(true));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(target: "my_target", "preferred");
+        });
+
+        let records = sink.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].logger_name(), Some("my_target"));
+        assert_eq!(records[1].logger_name(), Some("named"));
+    }
+
+    #[test]
+    fn span_context() {
+        let sink = Arc::new(TestSink::new());
+        let logger = Arc::new(build_test_logger(|b| b.sink(sink.clone())));
+        let subscriber = tracing_subscriber::registry().with(TracingLayer::with_logger(logger));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let outer = tracing::info_span!("outer", req = 1, later = tracing::field::Empty);
+            let _outer = outer.enter();
+            outer.record("later", "set");
+            let inner = tracing::info_span!("inner", addr = %"127.0.0.1");
+            let _inner = inner.enter();
+            tracing::info!(msg_id = 7, "in span");
+        });
+
+        let records = sink.records();
+        assert_eq!(records.len(), 1);
+        let kvs = records[0]
+            .key_values()
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kvs,
+            [
+                "span=outer:inner",
+                "req=1",
+                "later=set",
+                "addr=127.0.0.1",
+                "msg_id=7"
+            ]
+        );
+    }
+}
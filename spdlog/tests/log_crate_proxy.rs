@@ -11,6 +11,7 @@ include!(concat!(
 ));
 use test_utils::*;
 
+#[allow(clippy::incompatible_msrv)] // Integration tests are not bound to the MSRV
 static GLOBAL_LOG_CRATE_PROXY_MUTEX: Mutex<()> = Mutex::new(());
 
 #[cfg(feature = "log")]
@@ -49,3 +50,23 @@ fn test_target() {
     log::info!(target: "MyLogger", "body");
     assert_eq!(sink.clone_string(), format!("[MyLogger] body{__EOL}"));
 }
+
+#[cfg(feature = "log")]
+#[test]
+fn test_kv() {
+    let formatter = Box::new(PatternFormatter::new(pattern!("{payload} [{kv}]{eol}")));
+    let sink = Arc::new(StringSink::with(|b| b.formatter(formatter)));
+    let logger = Arc::new(build_test_logger(|b| b.sink(sink.clone())));
+
+    let _guard = GLOBAL_LOG_CRATE_PROXY_MUTEX.lock().unwrap();
+    spdlog::init_log_crate_proxy().ok();
+    spdlog::log_crate_proxy().set_logger(Some(logger));
+    log::set_max_level(log::LevelFilter::Trace);
+
+    log::info!(user_id = 42, ip:% = "127.0.0.1", ok = true, ratio = 0.5; "login");
+    log::info!("no kv");
+    assert_eq!(
+        sink.clone_string(),
+        format!("login [user_id=42 ip=127.0.0.1 ok=true ratio=0.5]{__EOL}no kv []{__EOL}")
+    );
+}
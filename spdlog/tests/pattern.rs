@@ -91,6 +91,28 @@ fn test_style_range() {
     );
 }
 
+#[test]
+fn test_kv() {
+    let (logger, sink) =
+        test_utils::echo_logger_from_pattern(pattern!("{payload} [{kv}]{eol}"), None);
+
+    let addr = std::net::Ipv4Addr::LOCALHOST;
+    let state = Some("online");
+    let user_id = 42;
+    info!(logger: logger, "no kv");
+    info!(logger: logger, "login {}", 1, kv: { user_id, ip = %addr, ?state, http.status = 200_u16, "content-type" = "text", });
+    warn!(logger: logger, "ratio", kv: { ratio = 0.5, ok = true, r#type = 'x' });
+
+    assert_eq!(
+        sink.clone_string(),
+        format!(
+            "no kv []{__EOL}\
+             login 1 [user_id=42 ip=127.0.0.1 state=Some(\"online\") http.status=200 content-type=text]{__EOL}\
+             ratio [ratio=0.5 ok=true type=x]{__EOL}"
+        )
+    );
+}
+
 #[track_caller]
 fn test_pattern_inner<P, F>(pat: P, expect_formatted: F, expect_style_range: Option<Range<usize>>)
 where
@@ -466,6 +488,7 @@ fn test_builtin_patterns() {
     }
     check!("{logger}", Some(["logger-name"]), vec![]);
     check!("{payload}", Some(["test payload"]), vec![]);
+    check!("{kv}", Some([""]), vec![]);
     check!("{pid}", None as Option<Vec<&str>>, vec![OS_ID_RANGE]);
     check!("{tid}", None as Option<Vec<&str>>, vec![OS_ID_RANGE]);
     check!("{eol}", Some(["{eol}"]), vec![]);